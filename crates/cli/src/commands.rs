@@ -0,0 +1,965 @@
+use std::path::PathBuf;
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use anyhow::{anyhow, bail, Context as _, Result};
+use prediction_market_client::{
+    decode_category, decode_category_page, decode_config, decode_market, decode_market_entry,
+    decode_market_metadata, decode_parlay, decode_parlay_pool, decode_referrer, decode_registry,
+    decode_return_data, decode_token_account, decode_user_info, decode_user_stats, instructions,
+    pda, registry, trade, AmountConfig, Category, Config, CreateMarketParams, Market,
+    MarketCondition, MarketEntry, MarketKeys, MarketMetadata, MarketMetadataParams,
+    MarketStatus, Parlay, ParlayPool, Referrer, RegistrySlot, SwapQuote, TradeLimits, Twap,
+    UserInfo, UserStats,
+};
+use serde_json::{json, Value};
+use solana_sdk::{
+    clock::Clock,
+    signature::{read_keypair_file, Keypair},
+    signer::Signer,
+    transaction::Transaction,
+};
+
+use crate::{
+    output, rpc::Rpc, Command, ConfigArgs, Direction, MarketArgs, MetadataArgs, TradeLimitsArgs,
+};
+
+//  defaults of a first `configure`, matching the TypeScript scripts
+const DEFAULT_PLATFORM_FEE_BPS: u64 = 100;
+const DEFAULT_LP_FEE_BPS: u64 = 20;
+const DEFAULT_TOKEN_DECIMALS: u8 = 6;
+const DEFAULT_TOKEN_SUPPLY: u64 = 1_000_000_000_000_000;
+const DEFAULT_MIN_SOL_LIQUIDITY: u64 = 5_000_000_000;
+const UNBOUNDED: AmountConfig<u64> = AmountConfig::Range {
+    min: None,
+    max: None,
+};
+
+pub struct Context {
+    rpc: Rpc,
+    keypair_path: PathBuf,
+    dry_run: bool,
+    json: bool,
+}
+
+impl Context {
+    pub fn new(url: &str, keypair_path: PathBuf, dry_run: bool, json: bool) -> Self {
+        Self {
+            rpc: Rpc::new(url),
+            keypair_path,
+            dry_run,
+            json,
+        }
+    }
+
+    //  loaded on demand so read-only commands work without a keypair
+    fn payer(&self) -> Result<Keypair> {
+        read_keypair_file(&self.keypair_path)
+            .map_err(|e| anyhow!("failed to read keypair {}: {e}", self.keypair_path.display()))
+    }
+
+    fn fetch_config(&self) -> Result<Option<Config>> {
+        self.rpc
+            .account_data(&pda::config().0)?
+            .map(|data| decode_config(&data))
+            .transpose()
+    }
+
+    fn config(&self) -> Result<Config> {
+        self.fetch_config()?
+            .ok_or_else(|| anyhow!("the program has not been configured"))
+    }
+
+    fn market(&self, keys: &MarketKeys) -> Result<Market> {
+        let data = self
+            .rpc
+            .account_data(&keys.market)?
+            .ok_or_else(|| anyhow!("market {} does not exist", keys.market))?;
+        decode_market(&data)
+    }
+
+    fn market_metadata(&self, market: &Pubkey) -> Result<Option<MarketMetadata>> {
+        self.rpc
+            .account_data(&pda::market_metadata(market).0)?
+            .map(|data| decode_market_metadata(&data))
+            .transpose()
+    }
+
+    fn category(&self, name: &str) -> Result<Option<Category>> {
+        self.rpc
+            .account_data(&pda::category(name).0)?
+            .map(|data| decode_category(&data))
+            .transpose()
+    }
+
+    fn market_entry(&self, market_id: u64) -> Result<MarketEntry> {
+        let data = self
+            .rpc
+            .account_data(&pda::market_entry(market_id).0)?
+            .ok_or_else(|| anyhow!("market #{market_id} is not registered"))?;
+        decode_market_entry(&data)
+    }
+
+    //  where the next market of `category` gets registered
+    fn registry_slot(&self, category: &str) -> Result<RegistrySlot> {
+        let registry = self
+            .rpc
+            .account_data(&pda::registry().0)?
+            .map(|data| decode_registry(&data))
+            .transpose()?;
+        Ok(RegistrySlot::next(
+            registry.as_ref(),
+            self.category(category)?.as_ref(),
+        ))
+    }
+
+    fn user_info(&self, address: &Pubkey) -> Result<Option<UserInfo>> {
+        self.rpc
+            .account_data(address)?
+            .map(|data| decode_user_info(&data))
+            .transpose()
+    }
+
+    //  amount in the owner's associated token account, zero before it is created
+    fn token_balance(&self, owner: &Pubkey, mint: &Pubkey) -> Result<u64> {
+        self.rpc
+            .account_data(&pda::user_ata(owner, mint))?
+            .map_or(Ok(0), |data| Ok(decode_token_account(&data)?.amount))
+    }
+
+    fn referrer(&self, address: &Pubkey) -> Result<Option<Referrer>> {
+        self.rpc
+            .account_data(address)?
+            .map(|data| decode_referrer(&data))
+            .transpose()
+    }
+
+    fn user_stats(&self, user: &Pubkey) -> Result<Option<UserStats>> {
+        self.rpc
+            .account_data(&pda::user_stats(user).0)?
+            .map(|data| decode_user_stats(&data))
+            .transpose()
+    }
+
+    fn parlay_pool(&self) -> Result<Option<ParlayPool>> {
+        self.rpc
+            .account_data(&pda::parlay_pool().0)?
+            .map(|data| decode_parlay_pool(&data))
+            .transpose()
+    }
+
+    fn parlay(&self, parlay_id: u64) -> Result<Parlay> {
+        let data = self
+            .rpc
+            .account_data(&pda::parlay(parlay_id).0)?
+            .ok_or_else(|| anyhow!("parlay #{parlay_id} does not exist or is settled"))?;
+        decode_parlay(&data)
+    }
+
+    fn clock(&self) -> Result<Clock> {
+        let data = self
+            .rpc
+            .account_data(&solana_sdk::sysvar::clock::ID)?
+            .ok_or_else(|| anyhow!("the clock sysvar is missing"))?;
+        Ok(bincode::deserialize(&data)?)
+    }
+
+    //  lamports traded within the fee tier window, as `swap` will count them
+    fn volume(&self, user: &Pubkey) -> Result<u64> {
+        match self.user_stats(user)? {
+            Some(stats) => Ok(stats.volume(self.clock()?.unix_timestamp)),
+            None => Ok(0),
+        }
+    }
+
+    //  signs with the payer and `signers`, then simulates or sends; `summary` is printed with the outcome
+    fn execute(
+        &self,
+        payer: &Keypair,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+        summary: Value,
+    ) -> Result<()> {
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            self.rpc.latest_blockhash()?,
+        );
+
+        let mut summary = summary;
+        if self.dry_run {
+            let simulation = self.rpc.simulate(&tx)?;
+            summary["dry_run"] = json!(true);
+            summary["error"] = simulation["err"].clone();
+            summary["units_consumed"] = simulation["unitsConsumed"].clone();
+            summary["logs"] = simulation["logs"].clone();
+            output::print(&summary, self.json);
+            if !simulation["err"].is_null() {
+                bail!("simulation failed: {}", simulation["err"]);
+            }
+        } else {
+            summary["signature"] = json!(self.rpc.send_and_confirm(&tx)?);
+            output::print(&summary, self.json);
+        }
+        Ok(())
+    }
+
+    //  simulates a read-only instruction paid by the keypair and returns its return data
+    fn view(&self, instruction: Instruction) -> Result<Vec<u8>> {
+        let mut tx = Transaction::new_with_payer(&[instruction], Some(&self.payer()?.pubkey()));
+        tx.message.recent_blockhash = self.rpc.latest_blockhash()?;
+        self.rpc.return_data(&tx)
+    }
+
+    pub fn run(&self, command: Command) -> Result<()> {
+        match command {
+            Command::Configure(args) => self.configure(args),
+            Command::NominateAuthority { new_admin } => {
+                let payer = self.payer()?;
+                self.execute(
+                    &payer,
+                    &[instructions::nominate_authority(&payer.pubkey(), &new_admin)],
+                    &[],
+                    json!({ "pending_authority": new_admin.to_string() }),
+                )
+            }
+            Command::AcceptAuthority => {
+                let payer = self.payer()?;
+                self.execute(
+                    &payer,
+                    &[instructions::accept_authority(&payer.pubkey())],
+                    &[],
+                    json!({ "authority": payer.pubkey().to_string() }),
+                )
+            }
+            Command::CreateMarket {
+                yes_symbol,
+                yes_uri,
+                no_symbol,
+                no_uri,
+                start_slot,
+                ending_slot,
+                start_time,
+                end_time,
+                condition,
+                trade_limits,
+                metadata,
+            } => {
+                let payer = self.payer()?;
+                let config = self.config()?;
+                let yes_token = Keypair::new();
+                let no_token = Keypair::new();
+                let keys = MarketKeys::new(yes_token.pubkey(), no_token.pubkey());
+                let metadata = metadata_params(metadata);
+                let slot = self.registry_slot(&metadata.category)?;
+
+                self.execute(
+                    &payer,
+                    &[
+                        instructions::mint_no_token(
+                            &payer.pubkey(),
+                            &keys.no_token,
+                            no_symbol,
+                            no_uri,
+                        ),
+                        instructions::create_market(
+                            &payer.pubkey(),
+                            &keys,
+                            &config.team_wallet,
+                            slot,
+                            CreateMarketParams {
+                                yes_symbol,
+                                yes_uri,
+                                start_slot,
+                                ending_slot,
+                                start_time,
+                                end_time,
+                                trade_limits: apply_trade_limits(
+                                    TradeLimits::default(),
+                                    trade_limits,
+                                ),
+                                metadata,
+                                condition: condition.map(|(parent, outcome)| MarketCondition {
+                                    parent: parent.market,
+                                    outcome,
+                                }),
+                            },
+                        ),
+                    ],
+                    &[&no_token, &yes_token],
+                    json!({
+                        "market": keys.market.to_string(),
+                        "yes_token": keys.yes_token.to_string(),
+                        "no_token": keys.no_token.to_string(),
+                        "market_id": slot.market_id,
+                    }),
+                )
+            }
+            Command::UpdateTradeLimits {
+                market,
+                trade_limits,
+            } => {
+                let payer = self.payer()?;
+                let keys = market_keys(&market);
+                let trade_limits =
+                    apply_trade_limits(self.market(&keys)?.trade_limits, trade_limits);
+                self.execute(
+                    &payer,
+                    &[instructions::update_trade_limits(
+                        &payer.pubkey(),
+                        &keys,
+                        trade_limits,
+                    )],
+                    &[],
+                    json!({
+                        "market": keys.market.to_string(),
+                        "max_position_per_wallet": trade_limits.max_position_per_wallet,
+                        "min_trade_lamports": trade_limits.min_trade_lamports,
+                        "max_trade_lamports": trade_limits.max_trade_lamports,
+                    }),
+                )
+            }
+            Command::UpdateMetadata { market, metadata } => {
+                let payer = self.payer()?;
+                let keys = market_keys(&market);
+                let mut params = metadata_params(metadata);
+                //  the category is fixed at creation, keep it unless one is given
+                if params.category.is_empty() {
+                    if let Some(current) = self.market_metadata(&keys.market)? {
+                        params.category = current.category;
+                    }
+                }
+                self.execute(
+                    &payer,
+                    &[instructions::update_market_metadata(
+                        &payer.pubkey(),
+                        &keys,
+                        params,
+                    )],
+                    &[],
+                    json!({ "market": keys.market.to_string() }),
+                )
+            }
+            Command::ArchiveMarket { market } => {
+                let payer = self.payer()?;
+                let keys = market_keys(&market);
+                let entry = self.market_entry(self.market(&keys)?.id)?;
+                self.execute(
+                    &payer,
+                    &[instructions::archive_market(&payer.pubkey(), &entry)],
+                    &[],
+                    json!({ "market": keys.market.to_string(), "market_id": entry.id }),
+                )
+            }
+            Command::ListMarkets { category, all } => {
+                let markets = match category {
+                    Some(name) => self.category_markets(&name)?,
+                    None => self.registered_markets(all)?,
+                };
+                output::print(&json!({ "markets": markets }), self.json);
+                Ok(())
+            }
+            Command::Swap {
+                market,
+                amount,
+                direction,
+                outcome,
+                slippage_bps,
+                valid_for_slots,
+                max_price_impact_bps,
+                referrer,
+            } => {
+                let payer = self.payer()?;
+                let config = self.config()?;
+                let market = self.market(&market_keys(&market))?;
+                let token_type = outcome.token_type();
+                let volume = self.volume(&payer.pubkey())?;
+                let plan = match direction {
+                    Direction::Buy => trade::buy(
+                        &payer.pubkey(),
+                        &config,
+                        &market,
+                        token_type,
+                        amount,
+                        slippage_bps,
+                        volume,
+                        referrer.as_ref(),
+                    )?,
+                    Direction::Sell => trade::sell(
+                        &payer.pubkey(),
+                        &config,
+                        &market,
+                        token_type,
+                        amount,
+                        slippage_bps,
+                        volume,
+                        referrer.as_ref(),
+                    )?,
+                };
+                let valid_until_slot = match valid_for_slots {
+                    Some(slots) => Some(self.rpc.slot()? + slots),
+                    None => None,
+                };
+                let plan = plan.with_guards(valid_until_slot, max_price_impact_bps)?;
+
+                self.execute(
+                    &payer,
+                    &[plan.instruction],
+                    &[],
+                    json!({
+                        "amount": amount,
+                        "expected_amount": plan.expected_amount,
+                        "minimum_receive_amount": plan.minimum_receive_amount,
+                        "platform_fee": plan.fees.platform_fee,
+                        "lp_fee": plan.fees.lp_fee,
+                        "price_impact_bps": plan.price_impact_bps,
+                        "valid_until_slot": valid_until_slot,
+                    }),
+                )
+            }
+            Command::BatchSwap {
+                legs,
+                valid_for_slots,
+                referrer,
+            } => {
+                let payer = self.payer()?;
+                let config = self.config()?;
+                let markets: Vec<String> = legs
+                    .iter()
+                    .map(|(keys, _)| keys.market.to_string())
+                    .collect();
+                let valid_until_slot = match valid_for_slots {
+                    Some(slots) => Some(self.rpc.slot()? + slots),
+                    None => None,
+                };
+                self.execute(
+                    &payer,
+                    &[instructions::batch_swap(
+                        &payer.pubkey(),
+                        &config.team_wallet,
+                        &legs,
+                        valid_until_slot,
+                        referrer.as_ref(),
+                    )],
+                    &[],
+                    json!({ "markets": markets }),
+                )
+            }
+            Command::Quote {
+                market,
+                amount,
+                direction,
+                outcome,
+                max_price_impact_bps,
+            } => {
+                let keys = market_keys(&market);
+                //  at the signer's fee tier once they have traded
+                let trader = self.payer()?.pubkey();
+                let trader = self.user_stats(&trader)?.map(|_| trader);
+                let data = self.view(instructions::quote_swap(
+                    &keys,
+                    amount,
+                    direction.direction(),
+                    outcome.token_type(),
+                    0,
+                    None,
+                    max_price_impact_bps,
+                    trader.as_ref(),
+                ))?;
+                let quote: SwapQuote = decode_return_data(&data)?;
+                output::print(&output::swap_quote(&keys.market, &quote), self.json);
+                Ok(())
+            }
+            Command::AddLiquidity { market, amount } => {
+                let payer = self.payer()?;
+                let config = self.config()?;
+                let keys = market_keys(&market);
+                self.execute(
+                    &payer,
+                    &[instructions::add_liquidity(
+                        &payer.pubkey(),
+                        &keys,
+                        &config.team_wallet,
+                        amount,
+                    )],
+                    &[],
+                    json!({ "market": keys.market.to_string(), "amount": amount }),
+                )
+            }
+            Command::WithdrawLiquidity { market, amount } => {
+                let payer = self.payer()?;
+                let config = self.config()?;
+                let keys = market_keys(&market);
+                self.execute(
+                    &payer,
+                    &[instructions::withdraw_liquidity(
+                        &payer.pubkey(),
+                        &keys,
+                        &config.team_wallet,
+                        amount,
+                    )],
+                    &[],
+                    json!({ "market": keys.market.to_string(), "amount": amount }),
+                )
+            }
+            Command::ProposeResolution { market, winner } => {
+                let payer = self.payer()?;
+                let keys = market_keys(&market);
+                let parent = self
+                    .market(&keys)?
+                    .condition
+                    .map(|condition| condition.parent);
+                self.execute(
+                    &payer,
+                    &[instructions::propose_resolution(
+                        &payer.pubkey(),
+                        &keys,
+                        winner.token_type(),
+                        parent.as_ref(),
+                    )],
+                    &[],
+                    json!({ "market": keys.market.to_string() }),
+                )
+            }
+            Command::Resolve {
+                market,
+                winner,
+                user_keypair,
+                yes_amount,
+                no_amount,
+            } => {
+                let payer = self.payer()?;
+                let keys = market_keys(&market);
+                let user_keypair = user_keypair
+                    .map(|path| {
+                        read_keypair_file(&path)
+                            .map_err(|e| anyhow!("failed to read keypair {}: {e}", path.display()))
+                    })
+                    .transpose()?;
+                let user = user_keypair
+                    .as_ref()
+                    .map_or(payer.pubkey(), |user| user.pubkey());
+                self.user_info(&pda::user_info(&user, &keys.market).0)?
+                    .ok_or_else(|| anyhow!("{user} has no position in {}", keys.market))?;
+                //  the signer co-signs as the authority only to confirm a proposed outcome
+                let confirming = self.market(&keys)?.status == MarketStatus::ResolutionProposed;
+                let yes_amount = match yes_amount {
+                    Some(amount) => amount,
+                    None => self.token_balance(&user, &keys.yes_token)?,
+                };
+                let no_amount = match no_amount {
+                    Some(amount) => amount,
+                    None => self.token_balance(&user, &keys.no_token)?,
+                };
+
+                self.execute(
+                    &payer,
+                    &[instructions::resolution(
+                        confirming.then(|| payer.pubkey()).as_ref(),
+                        &user,
+                        &keys,
+                        yes_amount,
+                        no_amount,
+                        winner.token_type(),
+                    )],
+                    &user_keypair.iter().collect::<Vec<_>>(),
+                    json!({
+                        "market": keys.market.to_string(),
+                        "user": user.to_string(),
+                        "yes_amount": yes_amount,
+                        "no_amount": no_amount,
+                    }),
+                )
+            }
+            Command::UpdateOutcomeMetadata { market } => {
+                let payer = self.payer()?;
+                let keys = market_keys(&market);
+                self.execute(
+                    &payer,
+                    &[instructions::update_outcome_metadata(&payer.pubkey(), &keys)],
+                    &[],
+                    json!({ "market": keys.market.to_string() }),
+                )
+            }
+            Command::VoidConditionalMarket { market } => {
+                let payer = self.payer()?;
+                let keys = market_keys(&market);
+                let condition = self
+                    .market(&keys)?
+                    .condition
+                    .ok_or_else(|| anyhow!("market {} is not conditional", keys.market))?;
+                self.execute(
+                    &payer,
+                    &[instructions::void_conditional_market(
+                        &payer.pubkey(),
+                        &keys.market,
+                        &condition.parent,
+                    )],
+                    &[],
+                    json!({ "market": keys.market.to_string(), "parent": condition.parent.to_string() }),
+                )
+            }
+            Command::VoidMarket { market } => {
+                let payer = self.payer()?;
+                let keys = market_keys(&market);
+                self.execute(
+                    &payer,
+                    &[instructions::void_market(&payer.pubkey(), &keys)],
+                    &[],
+                    json!({ "market": keys.market.to_string() }),
+                )
+            }
+            Command::Refund { market } => {
+                let payer = self.payer()?;
+                let keys = market_keys(&market);
+                self.execute(
+                    &payer,
+                    &[instructions::refund(&payer.pubkey(), &keys)],
+                    &[],
+                    json!({ "market": keys.market.to_string() }),
+                )
+            }
+            Command::RegisterReferrer { authority } => {
+                let payer = self.payer()?;
+                let referrer = pda::referrer(&authority).0;
+                self.execute(
+                    &payer,
+                    &[instructions::register_referrer(&payer.pubkey(), &authority)],
+                    &[],
+                    json!({ "referrer": referrer.to_string() }),
+                )
+            }
+            Command::ClaimReferralFees => {
+                let payer = self.payer()?;
+                let address = pda::referrer(&payer.pubkey()).0;
+                let referrer = self
+                    .referrer(&address)?
+                    .ok_or_else(|| anyhow!("{} is not a registered referrer", payer.pubkey()))?;
+                self.execute(
+                    &payer,
+                    &[instructions::claim_referral_fees(&payer.pubkey())],
+                    &[],
+                    json!({ "referrer": address.to_string(), "amount": referrer.unclaimed }),
+                )
+            }
+            Command::ShowReferrer { authority } => {
+                let authority = match authority {
+                    Some(authority) => authority,
+                    None => self.payer()?.pubkey(),
+                };
+                let address = pda::referrer(&authority).0;
+                let referrer = self
+                    .referrer(&address)?
+                    .ok_or_else(|| anyhow!("{authority} is not a registered referrer"))?;
+                output::print(&output::referrer(&address, &referrer), self.json);
+                Ok(())
+            }
+            Command::ShowUserStats { user } => {
+                let user = match user {
+                    Some(user) => user,
+                    None => self.payer()?.pubkey(),
+                };
+                let stats = self
+                    .user_stats(&user)?
+                    .ok_or_else(|| anyhow!("{user} has not traded yet"))?;
+                let volume = stats.volume(self.clock()?.unix_timestamp);
+                output::print(
+                    &output::user_stats(&pda::user_stats(&user).0, &stats, volume, &self.config()?),
+                    self.json,
+                );
+                Ok(())
+            }
+            Command::FundParlayPool { amount } => {
+                let payer = self.payer()?;
+                self.execute(
+                    &payer,
+                    &[instructions::fund_parlay_pool(&payer.pubkey(), amount)],
+                    &[],
+                    json!({ "parlay_pool": pda::parlay_pool().0.to_string(), "amount": amount }),
+                )
+            }
+            Command::WithdrawParlayPool { amount } => {
+                let payer = self.payer()?;
+                self.execute(
+                    &payer,
+                    &[instructions::withdraw_parlay_pool(&payer.pubkey(), amount)],
+                    &[],
+                    json!({ "parlay_pool": pda::parlay_pool().0.to_string(), "amount": amount }),
+                )
+            }
+            Command::OpenParlay {
+                picks,
+                amount,
+                slippage_bps,
+            } => {
+                let payer = self.payer()?;
+                let config = self.config()?;
+                let pool = self
+                    .parlay_pool()?
+                    .ok_or_else(|| anyhow!("the parlay pool has not been funded"))?;
+                let picks = picks
+                    .iter()
+                    .map(|(keys, token_type)| Ok((self.market(keys)?, *token_type)))
+                    .collect::<Result<Vec<_>>>()?;
+                let plan = trade::parlay(
+                    &payer.pubkey(),
+                    &config,
+                    pool.parlay_count,
+                    &picks,
+                    amount,
+                    slippage_bps,
+                )?;
+                self.execute(
+                    &payer,
+                    &[plan.instruction],
+                    &[],
+                    json!({
+                        "parlay": pda::parlay(plan.parlay_id).0.to_string(),
+                        "id": plan.parlay_id,
+                        "stake": amount,
+                        "fee_lamports": plan.fee_lamports,
+                        "expected_payout": plan.expected_payout,
+                        "min_payout": plan.min_payout,
+                    }),
+                )
+            }
+            Command::SettleParlay { id } => {
+                let payer = self.payer()?;
+                let parlay = self.parlay(id)?;
+                self.execute(
+                    &payer,
+                    &[instructions::settle_parlay(&parlay)],
+                    &[],
+                    json!({ "parlay": pda::parlay(id).0.to_string(), "owner": parlay.owner.to_string() }),
+                )
+            }
+            Command::ShowParlay { id } => {
+                output::print(
+                    &output::parlay(&pda::parlay(id).0, &self.parlay(id)?),
+                    self.json,
+                );
+                Ok(())
+            }
+            Command::ShowParlayPool => {
+                let address = pda::parlay_pool().0;
+                let pool = self
+                    .parlay_pool()?
+                    .ok_or_else(|| anyhow!("the parlay pool has not been funded"))?;
+                output::print(&output::parlay_pool(&address, &pool), self.json);
+                Ok(())
+            }
+            Command::ClosePosition { market } => {
+                let payer = self.payer()?;
+                let keys = market_keys(&market);
+                let user_info = pda::user_info(&payer.pubkey(), &keys.market).0;
+                self.execute(
+                    &payer,
+                    &[instructions::close_user_info(&payer.pubkey(), &keys.market)],
+                    &[],
+                    json!({ "market": keys.market.to_string(), "user_info": user_info.to_string() }),
+                )
+            }
+            Command::CloseMarket { market } => {
+                let payer = self.payer()?;
+                let config = self.config()?;
+                let keys = market_keys(&market);
+                let market = self.market(&keys)?;
+                self.execute(
+                    &payer,
+                    &[instructions::close_market(&keys, &market, &config.team_wallet)],
+                    &[],
+                    json!({ "market": keys.market.to_string(), "creator": market.creator.to_string() }),
+                )
+            }
+            Command::ShowConfig => {
+                output::print(&output::config(&pda::config().0, &self.config()?), self.json);
+                Ok(())
+            }
+            Command::ShowMarket { market } => {
+                let keys = market_keys(&market);
+                let mut value = output::market(&keys.market, &self.market(&keys)?);
+                if let Some(metadata) = self.market_metadata(&keys.market)? {
+                    output::add_market_metadata(&mut value, &metadata);
+                }
+                output::print(&value, self.json);
+                Ok(())
+            }
+            Command::Observe {
+                market,
+                window_slots,
+            } => {
+                let keys = market_keys(&market);
+                let data = self.view(instructions::observe(&keys, window_slots))?;
+                let twap: Twap = decode_return_data(&data)?;
+                output::print(&output::twap(&keys.market, &twap), self.json);
+                Ok(())
+            }
+            Command::ShowPosition { market, user } => {
+                let keys = market_keys(&market);
+                let user = match user {
+                    Some(user) => user,
+                    None => self.payer()?.pubkey(),
+                };
+                let address = pda::user_info(&user, &keys.market).0;
+                let position = self
+                    .user_info(&address)?
+                    .ok_or_else(|| anyhow!("{user} has no position in {}", keys.market))?;
+                output::print(
+                    &output::user_info(&address, &keys.market, &position),
+                    self.json,
+                );
+                Ok(())
+            }
+        }
+    }
+
+    fn category_markets(&self, name: &str) -> Result<Vec<String>> {
+        let Some(category) = self.category(name)? else {
+            return Ok(vec![]);
+        };
+        let mut markets = vec![];
+        for address in registry::category_pages(name, &category) {
+            if let Some(data) = self.rpc.account_data(&address)? {
+                let page = decode_category_page(&data)?;
+                markets.extend(page.markets.iter().map(Pubkey::to_string));
+            }
+        }
+        Ok(markets)
+    }
+
+    fn registered_markets(&self, include_archived: bool) -> Result<Vec<String>> {
+        let Some(data) = self.rpc.account_data(&pda::registry().0)? else {
+            return Ok(vec![]);
+        };
+        let mut markets = vec![];
+        for address in registry::market_entries(&decode_registry(&data)?) {
+            let data = self
+                .rpc
+                .account_data(&address)?
+                .ok_or_else(|| anyhow!("market entry {address} does not exist"))?;
+            let entry = decode_market_entry(&data)?;
+            if include_archived || !entry.archived {
+                markets.push(format!("#{} {} {}", entry.id, entry.market, entry.category));
+            }
+        }
+        Ok(markets)
+    }
+
+    fn configure(&self, args: ConfigArgs) -> Result<()> {
+        let payer = self.payer()?;
+        let mut config = self
+            .fetch_config()
+            .context("failed to load the current config")?
+            .unwrap_or_else(|| default_config(&payer.pubkey()));
+
+        if let Some(team_wallet) = args.team_wallet {
+            config.team_wallet = team_wallet;
+        }
+        if let Some(fee) = args.platform_buy_fee {
+            config.platform_buy_fee = fee;
+        }
+        if let Some(fee) = args.platform_sell_fee {
+            config.platform_sell_fee = fee;
+        }
+        if let Some(fee) = args.lp_buy_fee {
+            config.lp_buy_fee = fee;
+        }
+        if let Some(fee) = args.lp_sell_fee {
+            config.lp_sell_fee = fee;
+        }
+        if let Some(bps) = args.referral_fee_bps {
+            config.referral_fee_bps = bps;
+        }
+        if let Some(supply) = args.token_supply {
+            config.token_supply_config = supply;
+        }
+        if let Some(decimals) = args.token_decimals {
+            config.token_decimals_config = decimals;
+        }
+        if let Some(reserves) = args.initial_real_token_reserves {
+            config.initial_real_token_reserves_config = reserves;
+        }
+        if let Some(lamports) = args.min_sol_liquidity {
+            config.min_sol_liquidity = lamports;
+        }
+        if let Some(bounds) = args.max_position_per_wallet {
+            config.max_position_per_wallet_config = bounds;
+        }
+        if let Some(bounds) = args.min_trade_lamports {
+            config.min_trade_lamports_config = bounds;
+        }
+        if let Some(bounds) = args.max_trade_lamports {
+            config.max_trade_lamports_config = bounds;
+        }
+        if !args.fee_tiers.is_empty() || args.clear_fee_tiers {
+            config.fee_tiers = args.fee_tiers;
+        }
+        if let Some(uri) = args.won_uri {
+            config.won_uri = uri;
+        }
+        if let Some(uri) = args.lost_uri {
+            config.lost_uri = uri;
+        }
+
+        let summary = output::config(&pda::config().0, &config);
+        self.execute(
+            &payer,
+            &[instructions::configure(&payer.pubkey(), config)],
+            &[],
+            summary,
+        )
+    }
+}
+
+fn metadata_params(args: MetadataArgs) -> MarketMetadataParams {
+    MarketMetadataParams {
+        question: args.question,
+        rules: args.rules,
+        resolution_source: args.resolution_source,
+        category: args.category,
+        tags: args.tags,
+    }
+}
+
+fn apply_trade_limits(mut trade_limits: TradeLimits, args: TradeLimitsArgs) -> TradeLimits {
+    if let Some(tokens) = args.max_position {
+        trade_limits.max_position_per_wallet = tokens;
+    }
+    if let Some(lamports) = args.min_trade {
+        trade_limits.min_trade_lamports = lamports;
+    }
+    if let Some(lamports) = args.max_trade {
+        trade_limits.max_trade_lamports = lamports;
+    }
+    trade_limits
+}
+
+fn market_keys(market: &MarketArgs) -> MarketKeys {
+    MarketKeys::new(market.yes_token, market.no_token)
+}
+
+fn default_config(authority: &Pubkey) -> Config {
+    Config {
+        authority: *authority,
+        pending_authority: Pubkey::default(),
+        team_wallet: *authority,
+        platform_buy_fee: DEFAULT_PLATFORM_FEE_BPS,
+        platform_sell_fee: DEFAULT_PLATFORM_FEE_BPS,
+        lp_buy_fee: DEFAULT_LP_FEE_BPS,
+        lp_sell_fee: DEFAULT_LP_FEE_BPS,
+        referral_fee_bps: 0,
+        fee_tiers: Vec::new(),
+        token_supply_config: DEFAULT_TOKEN_SUPPLY,
+        token_decimals_config: DEFAULT_TOKEN_DECIMALS,
+        initial_real_token_reserves_config: DEFAULT_TOKEN_SUPPLY,
+        min_sol_liquidity: DEFAULT_MIN_SOL_LIQUIDITY,
+        max_position_per_wallet_config: UNBOUNDED,
+        min_trade_lamports_config: UNBOUNDED,
+        max_trade_lamports_config: UNBOUNDED,
+        won_uri: String::new(),
+        lost_uri: String::new(),
+        initialized: true,
+    }
+}
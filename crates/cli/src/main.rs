@@ -0,0 +1,482 @@
+use std::{env, path::PathBuf, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use prediction_market::state::{
+    config::{AmountConfig, FeeTier},
+    market::{SwapLeg, DIRECTION_BUY, DIRECTION_SELL, TOKEN_TYPE_NO, TOKEN_TYPE_YES},
+};
+use prediction_market_client::{parse_amount_config, parse_fee_tier, MarketKeys};
+
+mod commands;
+mod output;
+mod rpc;
+
+#[derive(Parser)]
+#[command(about = "Administer and trade prediction markets")]
+struct Cli {
+    #[arg(long, short = 'u', global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    //  signer and fee payer, defaults to the Solana CLI keypair
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<PathBuf>,
+
+    /// Simulate the transaction instead of sending it
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Print machine readable JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Outcome {
+    Yes,
+    No,
+}
+
+impl Outcome {
+    fn token_type(self) -> u8 {
+        match self {
+            Self::Yes => TOKEN_TYPE_YES,
+            Self::No => TOKEN_TYPE_NO,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Direction {
+    Buy,
+    Sell,
+}
+
+impl Direction {
+    fn direction(self) -> u8 {
+        match self {
+            Self::Buy => DIRECTION_BUY,
+            Self::Sell => DIRECTION_SELL,
+        }
+    }
+}
+
+#[derive(Args)]
+struct MarketArgs {
+    #[arg(long, short = 'y', value_parser = Pubkey::from_str)]
+    yes_token: Pubkey,
+
+    #[arg(long, short = 'n', value_parser = Pubkey::from_str)]
+    no_token: Pubkey,
+}
+
+#[derive(Args)]
+struct MetadataArgs {
+    #[arg(long)]
+    question: String,
+    //  how the outcome is decided
+    #[arg(long, default_value = "")]
+    rules: String,
+    #[arg(long, default_value = "")]
+    resolution_source: String,
+    #[arg(long, default_value = "")]
+    category: String,
+    //  repeat for several tags
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+}
+
+//  unset limits keep their current value, or no limit on a new market
+#[derive(Args)]
+struct TradeLimitsArgs {
+    //  tokens of one outcome a wallet may buy up to
+    #[arg(long)]
+    max_position: Option<u64>,
+    #[arg(long)]
+    min_trade: Option<u64>,
+    #[arg(long)]
+    max_trade: Option<u64>,
+}
+
+#[derive(Args)]
+struct ConfigArgs {
+    #[arg(long, value_parser = Pubkey::from_str)]
+    team_wallet: Option<Pubkey>,
+    #[arg(long)]
+    platform_buy_fee: Option<u64>,
+    #[arg(long)]
+    platform_sell_fee: Option<u64>,
+    #[arg(long)]
+    lp_buy_fee: Option<u64>,
+    #[arg(long)]
+    lp_sell_fee: Option<u64>,
+    //  share of the platform fee paid to a swap's referrer
+    #[arg(long)]
+    referral_fee_bps: Option<u64>,
+    #[arg(long)]
+    token_supply: Option<u64>,
+    #[arg(long)]
+    token_decimals: Option<u8>,
+    #[arg(long)]
+    initial_real_token_reserves: Option<u64>,
+    #[arg(long)]
+    min_sol_liquidity: Option<u64>,
+    //  bounds on creator picked trade limits, `MIN..MAX` or `A,B,C`
+    #[arg(long, value_parser = parse_amount_config)]
+    max_position_per_wallet: Option<AmountConfig<u64>>,
+    #[arg(long, value_parser = parse_amount_config)]
+    min_trade_lamports: Option<AmountConfig<u64>>,
+    #[arg(long, value_parser = parse_amount_config)]
+    max_trade_lamports: Option<AmountConfig<u64>>,
+    //  `MIN_VOLUME:BUY_BPS:SELL_BPS`, repeat for each tier; replaces the current tiers
+    #[arg(long = "fee-tier", value_parser = parse_fee_tier)]
+    fee_tiers: Vec<FeeTier>,
+    //  back to the flat platform fees for every trader
+    #[arg(long, conflicts_with = "fee_tiers")]
+    clear_fee_tiers: bool,
+    //  base metadata uri the winning token of a resolved market gets, followed by its mint
+    #[arg(long)]
+    won_uri: Option<String>,
+    //  base metadata uri the losing token of a resolved market gets, followed by its mint
+    #[arg(long)]
+    lost_uri: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create or update the global config, unset fields keep their current value
+    Configure(ConfigArgs),
+
+    /// Nominate a new config authority
+    NominateAuthority {
+        #[arg(long, value_parser = Pubkey::from_str)]
+        new_admin: Pubkey,
+    },
+
+    /// Accept a pending authority nomination
+    AcceptAuthority,
+
+    /// Mint a NO token and create its market in one transaction
+    CreateMarket {
+        #[arg(long)]
+        yes_symbol: String,
+        #[arg(long)]
+        yes_uri: String,
+        #[arg(long)]
+        no_symbol: String,
+        #[arg(long)]
+        no_uri: String,
+        #[arg(long)]
+        start_slot: Option<u64>,
+        #[arg(long)]
+        ending_slot: Option<u64>,
+        //  unix timestamps, enforced together with the slots
+        #[arg(long)]
+        start_time: Option<i64>,
+        #[arg(long)]
+        end_time: Option<i64>,
+        //  `PARENT_YES_TOKEN:PARENT_NO_TOKEN:yes|no`, voided unless the parent resolves to it
+        #[arg(long, value_parser = parse_pick)]
+        condition: Option<(MarketKeys, u8)>,
+        #[command(flatten)]
+        trade_limits: TradeLimitsArgs,
+        #[command(flatten)]
+        metadata: MetadataArgs,
+    },
+
+    /// Replace a market's question, rules and tags before it starts
+    UpdateMetadata {
+        #[command(flatten)]
+        market: MarketArgs,
+        #[command(flatten)]
+        metadata: MetadataArgs,
+    },
+
+    /// Change a market's position cap and trade size bounds
+    UpdateTradeLimits {
+        #[command(flatten)]
+        market: MarketArgs,
+        #[command(flatten)]
+        trade_limits: TradeLimitsArgs,
+    },
+
+    /// Drop a resolved or voided market from its category listing
+    ArchiveMarket {
+        #[command(flatten)]
+        market: MarketArgs,
+    },
+
+    /// List open markets of a category, or every registered market
+    ListMarkets {
+        #[arg(long)]
+        category: Option<String>,
+        //  include archived markets when listing every market
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Buy with lamports or sell tokens
+    Swap {
+        #[command(flatten)]
+        market: MarketArgs,
+        #[arg(long, short = 'a')]
+        amount: u64,
+        #[arg(long, short = 'd', value_enum)]
+        direction: Direction,
+        #[arg(long, short = 't', value_enum)]
+        outcome: Outcome,
+        //  tolerated move of the quoted output
+        #[arg(long, default_value_t = 100)]
+        slippage_bps: u64,
+        //  slots after the current one the transaction may still land in
+        #[arg(long)]
+        valid_for_slots: Option<u64>,
+        //  tolerated move of the traded side's price
+        #[arg(long)]
+        max_price_impact_bps: Option<u64>,
+        //  authority of a registered referrer credited with part of the platform fee
+        #[arg(long, value_parser = Pubkey::from_str)]
+        referrer: Option<Pubkey>,
+    },
+
+    /// Swap on several markets in one transaction, all legs or none
+    BatchSwap {
+        //  `YES_TOKEN:NO_TOKEN:buy|sell:yes|no:AMOUNT[:MIN_OUT[:MAX_IMPACT_BPS]]`, repeat for each leg
+        #[arg(long = "leg", value_parser = parse_leg, required = true)]
+        legs: Vec<(MarketKeys, SwapLeg)>,
+        //  slots after the current one the transaction may still land in
+        #[arg(long)]
+        valid_for_slots: Option<u64>,
+        //  authority of a registered referrer credited with part of every leg's platform fee
+        #[arg(long, value_parser = Pubkey::from_str)]
+        referrer: Option<Pubkey>,
+    },
+
+    /// Preview a swap through the program without sending it
+    Quote {
+        #[command(flatten)]
+        market: MarketArgs,
+        #[arg(long, short = 'a')]
+        amount: u64,
+        #[arg(long, short = 'd', value_enum)]
+        direction: Direction,
+        #[arg(long, short = 't', value_enum)]
+        outcome: Outcome,
+        #[arg(long)]
+        max_price_impact_bps: Option<u64>,
+    },
+
+    AddLiquidity {
+        #[command(flatten)]
+        market: MarketArgs,
+        #[arg(long, short = 'a')]
+        amount: u64,
+    },
+
+    WithdrawLiquidity {
+        #[command(flatten)]
+        market: MarketArgs,
+        #[arg(long, short = 'a')]
+        amount: u64,
+    },
+
+    /// End trading and name the winner, confirmed by the first payout
+    ProposeResolution {
+        #[command(flatten)]
+        market: MarketArgs,
+        #[arg(long, value_enum)]
+        winner: Outcome,
+    },
+
+    /// Burn a user's tokens and pay out the position on the proposed or confirmed winner
+    Resolve {
+        #[command(flatten)]
+        market: MarketArgs,
+        #[arg(long, value_enum)]
+        winner: Outcome,
+        //  holder of the position, co-signs to burn the redeemed tokens, defaults to the signer
+        #[arg(long)]
+        user_keypair: Option<PathBuf>,
+        //  default to the user's token balances
+        #[arg(long)]
+        yes_amount: Option<u64>,
+        #[arg(long)]
+        no_amount: Option<u64>,
+    },
+
+    /// Rename the outcome tokens of a resolved market as won and lost, with the config's uris
+    UpdateOutcomeMetadata {
+        #[command(flatten)]
+        market: MarketArgs,
+    },
+
+    /// Void a market that cannot be resolved, positions are refunded at cost
+    VoidMarket {
+        #[command(flatten)]
+        market: MarketArgs,
+    },
+
+    /// Void a conditional market whose parent resolved against its condition, anyone can send it
+    VoidConditionalMarket {
+        #[command(flatten)]
+        market: MarketArgs,
+    },
+
+    /// Refund the signer's position in a voided market
+    Refund {
+        #[command(flatten)]
+        market: MarketArgs,
+    },
+
+    /// Approve a frontend as a referrer, signed by the config authority; swaps naming it
+    /// share the platform fee
+    RegisterReferrer {
+        //  wallet the frontend claims its referral fees with
+        #[arg(long, value_parser = Pubkey::from_str)]
+        authority: Pubkey,
+    },
+
+    /// Withdraw the referral fees accrued to the signer's referrer account
+    ClaimReferralFees,
+
+    ShowReferrer {
+        //  defaults to the signer
+        #[arg(long, value_parser = Pubkey::from_str)]
+        authority: Option<Pubkey>,
+    },
+
+    /// Show a trader's rolling volume and the fee tier it reaches
+    ShowUserStats {
+        //  defaults to the signer
+        #[arg(long, value_parser = Pubkey::from_str)]
+        user: Option<Pubkey>,
+    },
+
+    /// Add lamports to the pool backing parlays, config authority only
+    FundParlayPool {
+        #[arg(long, short = 'a')]
+        amount: u64,
+    },
+
+    /// Take back parlay pool liquidity no open parlay reserves, config authority only
+    WithdrawParlayPool {
+        #[arg(long, short = 'a')]
+        amount: u64,
+    },
+
+    /// Stake on every pick winning, priced at the markets' current implied prices
+    OpenParlay {
+        //  `YES_TOKEN:NO_TOKEN:yes|no`, repeat for each market
+        #[arg(long = "pick", value_parser = parse_pick, required = true)]
+        picks: Vec<(MarketKeys, u8)>,
+        //  lamports at risk, the platform fee is charged on top
+        #[arg(long, short = 'a')]
+        amount: u64,
+        //  tolerated move of the quoted payout
+        #[arg(long, default_value_t = 100)]
+        slippage_bps: u64,
+    },
+
+    /// Pay out a parlay once every one of its markets is final, anyone can send it
+    SettleParlay {
+        #[arg(long)]
+        id: u64,
+    },
+
+    ShowParlay {
+        #[arg(long)]
+        id: u64,
+    },
+
+    ShowParlayPool,
+
+    /// Close the signer's position and reclaim its rent
+    ClosePosition {
+        #[command(flatten)]
+        market: MarketArgs,
+    },
+
+    /// Close a settled, archived market, returning rent to its creator
+    CloseMarket {
+        #[command(flatten)]
+        market: MarketArgs,
+    },
+
+    ShowConfig,
+
+    ShowMarket {
+        #[command(flatten)]
+        market: MarketArgs,
+    },
+
+    /// Time weighted average prices of a market over the last slots
+    Observe {
+        #[command(flatten)]
+        market: MarketArgs,
+        #[arg(long, default_value_t = 150)]
+        window_slots: u64,
+    },
+
+    ShowPosition {
+        #[command(flatten)]
+        market: MarketArgs,
+        //  defaults to the signer
+        #[arg(long, value_parser = Pubkey::from_str)]
+        user: Option<Pubkey>,
+    },
+}
+
+fn parse_leg(spec: &str) -> Result<(MarketKeys, SwapLeg)> {
+    let parts: Vec<&str> = spec.split(':').collect();
+    let (yes_token, no_token, direction, outcome, amount, limits) = match parts[..] {
+        [yes, no, direction, outcome, amount, ref limits @ ..] if limits.len() <= 2 => {
+            (yes, no, direction, outcome, amount, limits)
+        }
+        _ => bail!(
+            "expected YES_TOKEN:NO_TOKEN:buy|sell:yes|no:AMOUNT[:MIN_OUT[:MAX_IMPACT_BPS]], got {spec:?}"
+        ),
+    };
+    let keys = MarketKeys::new(Pubkey::from_str(yes_token)?, Pubkey::from_str(no_token)?);
+    let direction = Direction::from_str(direction, true).map_err(|e| anyhow!(e))?;
+    let outcome = Outcome::from_str(outcome, true).map_err(|e| anyhow!(e))?;
+    Ok((
+        keys,
+        SwapLeg {
+            market: keys.market,
+            token_type: outcome.token_type(),
+            direction: direction.direction(),
+            amount: amount.parse()?,
+            minimum_receive_amount: limits.first().map_or(Ok(0), |min_out| min_out.parse())?,
+            max_price_impact_bps: limits.get(1).map(|bps| bps.parse()).transpose()?,
+        },
+    ))
+}
+
+fn parse_pick(spec: &str) -> Result<(MarketKeys, u8)> {
+    let [yes_token, no_token, outcome] = spec.split(':').collect::<Vec<_>>()[..] else {
+        bail!("expected YES_TOKEN:NO_TOKEN:yes|no, got {spec:?}");
+    };
+    let keys = MarketKeys::new(Pubkey::from_str(yes_token)?, Pubkey::from_str(no_token)?);
+    let outcome = Outcome::from_str(outcome, true).map_err(|e| anyhow!(e))?;
+    Ok((keys, outcome.token_type()))
+}
+
+fn default_keypair() -> PathBuf {
+    let home = env::var_os("HOME").unwrap_or_default();
+    PathBuf::from(home).join(".config/solana/id.json")
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let ctx = commands::Context::new(
+        &cli.url,
+        cli.keypair.unwrap_or_else(default_keypair),
+        cli.dry_run,
+        cli.json,
+    );
+    ctx.run(cli.command)
+}
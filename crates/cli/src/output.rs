@@ -0,0 +1,214 @@
+use anchor_lang::prelude::Pubkey;
+use prediction_market::{
+    math::DECIMAL_SCALE,
+    state::market::{TOKEN_TYPE_NO, TOKEN_TYPE_YES},
+};
+use prediction_market_client::{
+    Config, Market, MarketMetadata, Parlay, ParlayPool, Referrer, SwapQuote, Twap, UserInfo,
+    UserStats,
+};
+use serde_json::{json, Value};
+
+pub fn config(address: &Pubkey, config: &Config) -> Value {
+    json!({
+        "address": address.to_string(),
+        "authority": config.authority.to_string(),
+        "pending_authority": config.pending_authority.to_string(),
+        "team_wallet": config.team_wallet.to_string(),
+        "platform_buy_fee": config.platform_buy_fee,
+        "platform_sell_fee": config.platform_sell_fee,
+        "lp_buy_fee": config.lp_buy_fee,
+        "lp_sell_fee": config.lp_sell_fee,
+        "referral_fee_bps": config.referral_fee_bps,
+        "fee_tiers": config
+            .fee_tiers
+            .iter()
+            .map(|tier| format!(
+                "from {} lamports: buy {} bps, sell {} bps",
+                tier.min_volume, tier.platform_buy_fee, tier.platform_sell_fee
+            ))
+            .collect::<Vec<_>>(),
+        "token_supply": config.token_supply_config,
+        "token_decimals": config.token_decimals_config,
+        "initial_real_token_reserves": config.initial_real_token_reserves_config,
+        "min_sol_liquidity": config.min_sol_liquidity,
+        "max_position_per_wallet": format!("{:?}", config.max_position_per_wallet_config),
+        "min_trade_lamports": format!("{:?}", config.min_trade_lamports_config),
+        "max_trade_lamports": format!("{:?}", config.max_trade_lamports_config),
+        "won_uri": config.won_uri,
+        "lost_uri": config.lost_uri,
+        "initialized": config.initialized,
+    })
+}
+
+pub fn market(address: &Pubkey, market: &Market) -> Value {
+    json!({
+        "address": address.to_string(),
+        "yes_token": market.yes_token_mint.to_string(),
+        "no_token": market.no_token_mint.to_string(),
+        "creator": market.creator.to_string(),
+        "id": market.id,
+        "real_yes_token_reserves": market.real_yes_token_reserves,
+        "real_yes_sol_reserves": market.real_yes_sol_reserves,
+        "real_no_token_reserves": market.real_no_token_reserves,
+        "real_no_sol_reserves": market.real_no_sol_reserves,
+        "token_yes_total_supply": market.token_yes_total_supply,
+        "token_no_total_supply": market.token_no_total_supply,
+        "total_lp_amount": market.total_lp_amount,
+        "lp_fees": market.lp_fees,
+        "lp_count": market.lps.len(),
+        "start_slot": market.start_slot,
+        "ending_slot": market.ending_slot,
+        "start_time": market.start_time,
+        "end_time": market.end_time,
+        "status": format!("{:?}", market.status),
+        "winning_token_type": market.winning_token_type,
+        "open_cost_basis": market.open_cost_basis,
+        "open_parlays": market.open_parlays,
+        "open_conditional_markets": market.open_conditional_markets,
+        "max_position_per_wallet": market.trade_limits.max_position_per_wallet,
+        "min_trade_lamports": market.trade_limits.min_trade_lamports,
+        "max_trade_lamports": market.trade_limits.max_trade_lamports,
+        "parent_market": market.condition.map(|condition| condition.parent.to_string()),
+        "condition_outcome": market.condition.map(|condition| condition.outcome),
+    })
+}
+
+pub fn swap_quote(market: &Pubkey, quote: &SwapQuote) -> Value {
+    json!({
+        "market": market.to_string(),
+        "token_amount": quote.token_amount,
+        "change_amount": quote.change_amount,
+        "amount_out": quote.amount_out,
+        "platform_fee": quote.platform_fee,
+        "lp_fee": quote.lp_fee,
+        "price_impact_bps": quote.price_impact_bps,
+        "new_yes_reserves": quote.new_yes_reserves,
+        "new_no_reserves": quote.new_no_reserves,
+    })
+}
+
+//  prices as probabilities, 1.0 is a certain outcome
+pub fn twap(market: &Pubkey, twap: &Twap) -> Value {
+    json!({
+        "market": market.to_string(),
+        "start_slot": twap.start_slot,
+        "end_slot": twap.end_slot,
+        "yes_price": twap.yes_price as f64 / DECIMAL_SCALE as f64,
+        "no_price": twap.no_price as f64 / DECIMAL_SCALE as f64,
+    })
+}
+
+pub fn add_market_metadata(value: &mut Value, metadata: &MarketMetadata) {
+    value["question"] = json!(metadata.question);
+    value["rules"] = json!(metadata.rules);
+    value["resolution_source"] = json!(metadata.resolution_source);
+    value["category"] = json!(metadata.category);
+    value["tags"] = json!(metadata.tags);
+}
+
+pub fn user_info(address: &Pubkey, market: &Pubkey, user_info: &UserInfo) -> Value {
+    json!({
+        "address": address.to_string(),
+        "market": market.to_string(),
+        "user": user_info.user.to_string(),
+        "yes_balance": user_info.yes_balance,
+        "no_balance": user_info.no_balance,
+        "yes_cost_basis": user_info.yes_cost_basis,
+        "no_cost_basis": user_info.no_cost_basis,
+        "yes_average_price": average_entry_price(user_info, TOKEN_TYPE_YES),
+        "no_average_price": average_entry_price(user_info, TOKEN_TYPE_NO),
+        "total_sol_spent": user_info.total_sol_spent,
+        "total_sol_received": user_info.total_sol_received,
+        "realized_pnl": user_info.realized_pnl,
+        "fees_paid": user_info.fees_paid,
+        "lp_amount": user_info.lp_amount,
+        "is_lp": user_info.is_lp,
+    })
+}
+
+pub fn referrer(address: &Pubkey, referrer: &Referrer) -> Value {
+    json!({
+        "address": address.to_string(),
+        "authority": referrer.authority.to_string(),
+        "unclaimed": referrer.unclaimed,
+        "total_earned": referrer.total_earned,
+    })
+}
+
+//  `volume` is the rolling volume at the current clock
+pub fn user_stats(address: &Pubkey, stats: &UserStats, volume: u64, config: &Config) -> Value {
+    json!({
+        "address": address.to_string(),
+        "user": stats.user.to_string(),
+        "volume_30d": volume,
+        "total_volume": stats.total_volume,
+        "platform_buy_fee": config.buy_fee_rates(volume).platform_bps,
+        "platform_sell_fee": config.sell_fee_rates(volume).platform_bps,
+    })
+}
+
+pub fn parlay_pool(address: &Pubkey, pool: &ParlayPool) -> Value {
+    json!({
+        "address": address.to_string(),
+        "liquidity": pool.liquidity,
+        "reserved": pool.reserved,
+        "parlay_count": pool.parlay_count,
+    })
+}
+
+pub fn parlay(address: &Pubkey, parlay: &Parlay) -> Value {
+    let legs: Vec<Value> = parlay
+        .legs
+        .iter()
+        .map(|leg| {
+            json!({
+                "market": leg.market.to_string(),
+                "outcome": if leg.token_type == TOKEN_TYPE_YES { "yes" } else { "no" },
+                "entry_price": leg.entry_price as f64 / DECIMAL_SCALE as f64,
+            })
+        })
+        .collect();
+    json!({
+        "address": address.to_string(),
+        "id": parlay.id,
+        "owner": parlay.owner.to_string(),
+        "legs": legs,
+        "stake": parlay.stake,
+        "payout": parlay.payout,
+        "opened_at": parlay.opened_at,
+    })
+}
+
+//  lamports per token base unit, null without a position
+fn average_entry_price(user_info: &UserInfo, token_type: u8) -> Option<f64> {
+    user_info
+        .average_entry_price(token_type)
+        .map(|price| price.raw as f64 / DECIMAL_SCALE as f64)
+}
+
+//  pretty JSON for scripting, otherwise one `key: value` line per field
+pub fn print(value: &Value, as_json: bool) {
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(value).unwrap_or_default());
+        return;
+    }
+    match value {
+        Value::Object(fields) => {
+            let width = fields.keys().map(String::len).max().unwrap_or(0);
+            for (key, value) in fields {
+                match value {
+                    Value::String(value) => println!("{key:width$}  {value}"),
+                    Value::Array(lines) => {
+                        println!("{key}:");
+                        for line in lines {
+                            println!("  {}", line.as_str().map_or(line.to_string(), str::to_string));
+                        }
+                    }
+                    value => println!("{key:width$}  {value}"),
+                }
+            }
+        }
+        value => println!("{value}"),
+    }
+}
@@ -0,0 +1,136 @@
+use std::{thread, time::Duration};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_sdk::{hash::Hash, transaction::Transaction};
+
+//  how long `send_and_confirm` waits for a confirmed status
+const CONFIRM_ATTEMPTS: usize = 60;
+const CONFIRM_INTERVAL: Duration = Duration::from_millis(500);
+
+//  minimal JSON-RPC client, `solana-client` cannot share the program's spl-token pin
+pub struct Rpc {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl Rpc {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            agent: ureq::Agent::new(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .with_context(|| format!("{method} request to {} failed", self.url))?
+            .into_json()?;
+
+        if let Some(error) = response.get("error") {
+            bail!("{method} failed: {error}");
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| anyhow!("{method} returned no result"))
+    }
+
+    //  account data, `None` when the account does not exist
+    pub fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        let value = &result["value"];
+        if value.is_null() {
+            return Ok(None);
+        }
+        let data = value["data"][0]
+            .as_str()
+            .ok_or_else(|| anyhow!("getAccountInfo returned {value}"))?;
+        Ok(Some(STANDARD.decode(data)?))
+    }
+
+    pub fn latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let blockhash = result["value"]["blockhash"]
+            .as_str()
+            .ok_or_else(|| anyhow!("getLatestBlockhash returned {result}"))?;
+        Ok(blockhash.parse()?)
+    }
+
+    pub fn slot(&self) -> Result<u64> {
+        let result = self.call("getSlot", json!([{ "commitment": "confirmed" }]))?;
+        result
+            .as_u64()
+            .ok_or_else(|| anyhow!("getSlot returned {result}"))
+    }
+
+    //  `{ err, logs, unitsConsumed }` of the transaction against the current bank
+    pub fn simulate(&self, tx: &Transaction) -> Result<Value> {
+        let result = self.call(
+            "simulateTransaction",
+            json!([
+                encode(tx)?,
+                { "encoding": "base64", "commitment": "confirmed", "sigVerify": false }
+            ]),
+        )?;
+        Ok(result["value"].clone())
+    }
+
+    //  what the program passed to `set_return_data`, trailing zero bytes stripped
+    pub fn return_data(&self, tx: &Transaction) -> Result<Vec<u8>> {
+        let simulation = self.simulate(tx)?;
+        if !simulation["err"].is_null() {
+            bail!("simulation failed: {}", simulation["err"]);
+        }
+        let data = simulation["returnData"]["data"][0]
+            .as_str()
+            .ok_or_else(|| anyhow!("no return data in {simulation}"))?;
+        Ok(STANDARD.decode(data)?)
+    }
+
+    pub fn send_and_confirm(&self, tx: &Transaction) -> Result<String> {
+        let signature = self.call(
+            "sendTransaction",
+            json!([encode(tx)?, { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        let signature = signature
+            .as_str()
+            .ok_or_else(|| anyhow!("sendTransaction returned {signature}"))?
+            .to_string();
+
+        for _ in 0..CONFIRM_ATTEMPTS {
+            let statuses = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &statuses["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    bail!("transaction {signature} failed: {}", status["err"]);
+                }
+                if matches!(
+                    status["confirmationStatus"].as_str(),
+                    Some("confirmed") | Some("finalized")
+                ) {
+                    return Ok(signature);
+                }
+            }
+            thread::sleep(CONFIRM_INTERVAL);
+        }
+        bail!("transaction {signature} was not confirmed in time")
+    }
+}
+
+fn encode(tx: &Transaction) -> Result<String> {
+    Ok(STANDARD.encode(bincode::serialize(tx)?))
+}
@@ -0,0 +1,78 @@
+use anchor_lang::{
+    solana_program::program::MAX_RETURN_DATA, AccountDeserialize, AnchorDeserialize,
+};
+use anchor_spl::token::TokenAccount;
+use anyhow::{Context, Result};
+use prediction_market::state::{
+    config::Config,
+    market::{Market, UserInfo},
+    market_metadata::MarketMetadata,
+    parlay::{Parlay, ParlayPool},
+    referrer::Referrer,
+    registry::{Category, CategoryPage, MarketEntry, MarketRegistry},
+    user_stats::UserStats,
+};
+
+//  checks the discriminator before deserializing
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    let mut data = data;
+    Ok(T::try_deserialize(&mut data)?)
+}
+
+//  the runtime strips trailing zero bytes of return data, they are read back as zeros
+pub fn decode_return_data<T: AnchorDeserialize>(data: &[u8]) -> Result<T> {
+    let padded = [data, &[0; MAX_RETURN_DATA]].concat();
+    T::deserialize(&mut padded.as_slice()).context("decode return data")
+}
+
+pub fn decode_config(data: &[u8]) -> Result<Config> {
+    decode(data).context("decode Config account")
+}
+
+pub fn decode_market(data: &[u8]) -> Result<Market> {
+    decode(data).context("decode Market account")
+}
+
+pub fn decode_market_metadata(data: &[u8]) -> Result<MarketMetadata> {
+    decode(data).context("decode MarketMetadata account")
+}
+
+pub fn decode_user_info(data: &[u8]) -> Result<UserInfo> {
+    decode(data).context("decode UserInfo account")
+}
+
+pub fn decode_referrer(data: &[u8]) -> Result<Referrer> {
+    decode(data).context("decode Referrer account")
+}
+
+pub fn decode_user_stats(data: &[u8]) -> Result<UserStats> {
+    decode(data).context("decode UserStats account")
+}
+
+pub fn decode_parlay_pool(data: &[u8]) -> Result<ParlayPool> {
+    decode(data).context("decode ParlayPool account")
+}
+
+pub fn decode_parlay(data: &[u8]) -> Result<Parlay> {
+    decode(data).context("decode Parlay account")
+}
+
+pub fn decode_registry(data: &[u8]) -> Result<MarketRegistry> {
+    decode(data).context("decode MarketRegistry account")
+}
+
+pub fn decode_market_entry(data: &[u8]) -> Result<MarketEntry> {
+    decode(data).context("decode MarketEntry account")
+}
+
+pub fn decode_category(data: &[u8]) -> Result<Category> {
+    decode(data).context("decode Category account")
+}
+
+pub fn decode_category_page(data: &[u8]) -> Result<CategoryPage> {
+    decode(data).context("decode CategoryPage account")
+}
+
+pub fn decode_token_account(data: &[u8]) -> Result<TokenAccount> {
+    decode(data).context("decode token account")
+}
@@ -0,0 +1,51 @@
+use anyhow::{anyhow, ensure, Result};
+use prediction_market::state::config::{AmountConfig, FeeTier};
+
+//  `MIN..MAX` with either side optional, or a comma separated list of accepted values
+pub fn parse_amount_config(spec: &str) -> Result<AmountConfig<u64>> {
+    let bound = |value: &str| -> Result<Option<u64>> {
+        match value.trim() {
+            "" => Ok(None),
+            value => Ok(Some(value.parse()?)),
+        }
+    };
+
+    if let Some((min, max)) = spec.split_once("..") {
+        return Ok(AmountConfig::Range {
+            min: bound(min)?,
+            max: bound(max)?,
+        });
+    }
+    let options = spec
+        .split(',')
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| anyhow!("invalid amount {value:?} in {spec:?}"))
+        })
+        .collect::<Result<Vec<u64>>>()?;
+    ensure!(!options.is_empty(), "no accepted values in {spec:?}");
+    Ok(AmountConfig::Enum(options))
+}
+
+//  `MIN_VOLUME:BUY_BPS:SELL_BPS`, the platform fees from `MIN_VOLUME` lamports of rolling volume
+pub fn parse_fee_tier(spec: &str) -> Result<FeeTier> {
+    let parts = spec
+        .split(':')
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| anyhow!("invalid amount {value:?} in {spec:?}"))
+        })
+        .collect::<Result<Vec<u64>>>()?;
+    match parts[..] {
+        [min_volume, platform_buy_fee, platform_sell_fee] => Ok(FeeTier {
+            min_volume,
+            platform_buy_fee,
+            platform_sell_fee,
+        }),
+        _ => Err(anyhow!("expected MIN_VOLUME:BUY_BPS:SELL_BPS, got {spec:?}")),
+    }
+}
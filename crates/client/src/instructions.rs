@@ -0,0 +1,753 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program, sysvar,
+    },
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::{associated_token, metadata, token};
+use prediction_market::{
+    accounts, instruction,
+    state::{
+        config::Config,
+        market::{CreateMarketParams, Market, SwapLeg, TradeLimits, TOKEN_TYPE_YES},
+        market_metadata::MarketMetadataParams,
+        parlay::{Parlay, ParlayPick},
+        registry::MarketEntry,
+    },
+    ID,
+};
+
+use crate::{pda, registry::RegistrySlot};
+
+//  mints and PDA of one market
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MarketKeys {
+    pub yes_token: Pubkey,
+    pub no_token: Pubkey,
+    pub market: Pubkey,
+}
+
+impl MarketKeys {
+    pub fn new(yes_token: Pubkey, no_token: Pubkey) -> Self {
+        Self {
+            yes_token,
+            no_token,
+            market: pda::market(&yes_token, &no_token).0,
+        }
+    }
+
+    pub fn outcome_mint(&self, token_type: u8) -> Pubkey {
+        if token_type == TOKEN_TYPE_YES {
+            self.yes_token
+        } else {
+            self.no_token
+        }
+    }
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn configure(payer: &Pubkey, new_config: Config) -> Instruction {
+    build(
+        accounts::Configure {
+            payer: *payer,
+            config: pda::config().0,
+            global_vault: pda::global_vault().0,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::Configure { new_config },
+    )
+}
+
+pub fn nominate_authority(admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    build(
+        accounts::NominateAuthority {
+            admin: *admin,
+            global_config: pda::config().0,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::NominateAuthority {
+            new_admin: *new_admin,
+        },
+    )
+}
+
+pub fn accept_authority(new_admin: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAuthority {
+            new_admin: *new_admin,
+            global_config: pda::config().0,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::AcceptAuthority {},
+    )
+}
+
+//  `no_token` is a fresh mint keypair and must sign
+pub fn mint_no_token(
+    creator: &Pubkey,
+    no_token: &Pubkey,
+    no_symbol: String,
+    no_uri: String,
+) -> Instruction {
+    build(
+        accounts::MintNoToken {
+            global_config: pda::config().0,
+            global_vault: pda::global_vault().0,
+            creator: *creator,
+            no_token: *no_token,
+            no_token_record: pda::no_token_record(no_token).0,
+            no_token_metadata_account: pda::metadata(no_token).0,
+            global_no_token_account: pda::vault_ata(no_token),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            mpl_token_metadata_program: metadata::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::MintNoToken { no_symbol, no_uri },
+    )
+}
+
+//  `keys.yes_token` is a fresh mint keypair and must sign, the parent of `params.condition`
+//  is passed along
+pub fn create_market(
+    creator: &Pubkey,
+    keys: &MarketKeys,
+    team_wallet: &Pubkey,
+    slot: RegistrySlot,
+    params: CreateMarketParams,
+) -> Instruction {
+    let category = &params.metadata.category;
+    let parent_market = params.condition.map(|condition| condition.parent);
+    build(
+        accounts::CreateMarket {
+            global_config: pda::config().0,
+            global_vault: pda::global_vault().0,
+            creator: *creator,
+            yes_token: keys.yes_token,
+            no_token: keys.no_token,
+            no_token_record: pda::no_token_record(&keys.no_token).0,
+            market: keys.market,
+            market_metadata: pda::market_metadata(&keys.market).0,
+            registry: pda::registry().0,
+            market_entry: pda::market_entry(slot.market_id).0,
+            category: pda::category(category).0,
+            category_page: pda::category_page(category, slot.page).0,
+            yes_token_metadata_account: pda::metadata(&keys.yes_token).0,
+            no_token_metadata_account: pda::metadata(&keys.no_token).0,
+            global_yes_token_account: pda::vault_ata(&keys.yes_token),
+            global_no_token_account: pda::vault_ata(&keys.no_token),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            mpl_token_metadata_program: metadata::ID,
+            team_wallet: *team_wallet,
+            parent_market,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::CreateMarket { params },
+    )
+}
+
+//  any signer once the market is completed, the config authority at any time
+pub fn archive_market(signer: &Pubkey, entry: &MarketEntry) -> Instruction {
+    build(
+        accounts::ArchiveMarket {
+            global_config: pda::config().0,
+            signer: *signer,
+            market: entry.market,
+            market_entry: pda::market_entry(entry.id).0,
+            category: pda::category(&entry.category).0,
+            category_page: pda::category_page(&entry.category, entry.page).0,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::ArchiveMarket {},
+    )
+}
+
+//  signed by the market creator, only before the start slot
+pub fn update_market_metadata(
+    creator: &Pubkey,
+    keys: &MarketKeys,
+    params: MarketMetadataParams,
+) -> Instruction {
+    build(
+        accounts::UpdateMarketMetadata {
+            creator: *creator,
+            market: keys.market,
+            market_metadata: pda::market_metadata(&keys.market).0,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::UpdateMarketMetadata { params },
+    )
+}
+
+//  signed by the market creator, within the config bounds
+pub fn update_trade_limits(
+    creator: &Pubkey,
+    keys: &MarketKeys,
+    trade_limits: TradeLimits,
+) -> Instruction {
+    build(
+        accounts::UpdateTradeLimits {
+            global_config: pda::config().0,
+            creator: *creator,
+            market: keys.market,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::UpdateTradeLimits { trade_limits },
+    )
+}
+
+//  read-only, the `SwapQuote` of the same swap comes back as return data; with a `trader`
+//  whose stats account exists, the fees of their tier are quoted
+#[allow(clippy::too_many_arguments)]
+pub fn quote_swap(
+    keys: &MarketKeys,
+    amount: u64,
+    direction: u8,
+    token_type: u8,
+    minimum_receive_amount: u64,
+    valid_until_slot: Option<u64>,
+    max_price_impact_bps: Option<u64>,
+    trader: Option<&Pubkey>,
+) -> Instruction {
+    build(
+        accounts::QuoteSwap {
+            global_config: pda::config().0,
+            market: keys.market,
+            user_stats: trader.map(|trader| pda::user_stats(trader).0),
+        },
+        instruction::QuoteSwap {
+            amount,
+            direction,
+            token_type,
+            minimum_receive_amount,
+            valid_until_slot,
+            max_price_impact_bps,
+        },
+    )
+}
+
+//  read-only, the average prices come back as `Twap` return data
+pub fn observe(keys: &MarketKeys, window_slots: u64) -> Instruction {
+    build(
+        accounts::Observe {
+            market: keys.market,
+        },
+        instruction::Observe { window_slots },
+    )
+}
+
+//  `referrer` is the authority of a registered referrer, credited with part of the platform fee
+#[allow(clippy::too_many_arguments)]
+pub fn swap(
+    user: &Pubkey,
+    keys: &MarketKeys,
+    team_wallet: &Pubkey,
+    amount: u64,
+    direction: u8,
+    token_type: u8,
+    minimum_receive_amount: u64,
+    valid_until_slot: Option<u64>,
+    max_price_impact_bps: Option<u64>,
+    referrer: Option<&Pubkey>,
+) -> Instruction {
+    build(
+        accounts::Swap {
+            global_config: pda::config().0,
+            team_wallet: *team_wallet,
+            market: keys.market,
+            global_vault: pda::global_vault().0,
+            yes_token: keys.yes_token,
+            no_token: keys.no_token,
+            global_yes_ata: pda::vault_ata(&keys.yes_token),
+            global_no_ata: pda::vault_ata(&keys.no_token),
+            user_yes_ata: pda::user_ata(user, &keys.yes_token),
+            user_no_ata: pda::user_ata(user, &keys.no_token),
+            user_info: pda::user_info(user, &keys.market).0,
+            user_stats: pda::user_stats(user).0,
+            user: *user,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            referrer: referrer.map(|referrer| pda::referrer(referrer).0),
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::Swap {
+            amount,
+            direction,
+            token_type,
+            minimum_receive_amount,
+            valid_until_slot,
+            max_price_impact_bps,
+        },
+    )
+}
+
+//  `swap` on every leg's market in one transaction, each leg paired with the keys of its market,
+//  `valid_until_slot` and `referrer` apply to every leg
+pub fn batch_swap(
+    user: &Pubkey,
+    team_wallet: &Pubkey,
+    legs: &[(MarketKeys, SwapLeg)],
+    valid_until_slot: Option<u64>,
+    referrer: Option<&Pubkey>,
+) -> Instruction {
+    let mut ix = build(
+        accounts::BatchSwap {
+            global_config: pda::config().0,
+            team_wallet: *team_wallet,
+            global_vault: pda::global_vault().0,
+            user_stats: pda::user_stats(user).0,
+            user: *user,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            referrer: referrer.map(|referrer| pda::referrer(referrer).0),
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::BatchSwap {
+            legs: legs.iter().map(|(_, leg)| *leg).collect(),
+            valid_until_slot,
+        },
+    );
+    //  `BATCH_LEG_ACCOUNTS` per leg
+    for (keys, leg) in legs {
+        let mint = keys.outcome_mint(leg.token_type);
+        ix.accounts.extend([
+            AccountMeta::new(keys.market, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(pda::vault_ata(&mint), false),
+            AccountMeta::new(pda::user_ata(user, &mint), false),
+            AccountMeta::new(pda::user_info(user, &keys.market).0, false),
+        ]);
+    }
+    ix
+}
+
+//  `swap` with a PDA-friendly owner, tokens move through `token_account` and `payer` funds the user_info
+#[allow(clippy::too_many_arguments)]
+pub fn cpi_swap(
+    owner: &Pubkey,
+    payer: &Pubkey,
+    token_account: &Pubkey,
+    keys: &MarketKeys,
+    team_wallet: &Pubkey,
+    amount: u64,
+    direction: u8,
+    token_type: u8,
+    minimum_receive_amount: u64,
+    valid_until_slot: Option<u64>,
+    max_price_impact_bps: Option<u64>,
+) -> Instruction {
+    build(
+        accounts::CpiSwap {
+            global_config: pda::config().0,
+            team_wallet: *team_wallet,
+            market: keys.market,
+            global_vault: pda::global_vault().0,
+            yes_token: keys.yes_token,
+            no_token: keys.no_token,
+            global_yes_ata: pda::vault_ata(&keys.yes_token),
+            global_no_ata: pda::vault_ata(&keys.no_token),
+            token_account: *token_account,
+            user_info: pda::user_info(owner, &keys.market).0,
+            user_stats: pda::user_stats(owner).0,
+            owner: *owner,
+            payer: *payer,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::CpiSwap {
+            amount,
+            direction,
+            token_type,
+            minimum_receive_amount,
+            valid_until_slot,
+            max_price_impact_bps,
+        },
+    )
+}
+
+pub fn add_liquidity(
+    user: &Pubkey,
+    keys: &MarketKeys,
+    team_wallet: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::AddLiquidity {
+            global_config: pda::config().0,
+            team_wallet: *team_wallet,
+            market: keys.market,
+            global_vault: pda::global_vault().0,
+            yes_token: keys.yes_token,
+            no_token: keys.no_token,
+            user_info: pda::user_info(user, &keys.market).0,
+            user: *user,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::AddLiquidity { amount },
+    )
+}
+
+pub fn withdraw_liquidity(
+    user: &Pubkey,
+    keys: &MarketKeys,
+    team_wallet: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::WithdrawLiquidity {
+            global_config: pda::config().0,
+            team_wallet: *team_wallet,
+            market: keys.market,
+            global_vault: pda::global_vault().0,
+            yes_token: keys.yes_token,
+            no_token: keys.no_token,
+            user_info: pda::user_info(user, &keys.market).0,
+            user: *user,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::WithdrawLiquidity { amount },
+    )
+}
+
+//  signed by the config authority, ends trading and names the winner; a conditional market
+//  also takes its parent
+pub fn propose_resolution(
+    authority: &Pubkey,
+    keys: &MarketKeys,
+    winning_token_type: u8,
+    parent_market: Option<&Pubkey>,
+) -> Instruction {
+    build(
+        accounts::ProposeResolution {
+            global_config: pda::config().0,
+            market: keys.market,
+            authority: *authority,
+            parent_market: parent_market.copied(),
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::ProposeResolution { winning_token_type },
+    )
+}
+
+//  signed by `user`, burns the redeemed tokens and pays `user` out of the pool, the config
+//  `authority` co-signs only the payout that confirms a proposed outcome
+pub fn resolution(
+    authority: Option<&Pubkey>,
+    user: &Pubkey,
+    keys: &MarketKeys,
+    yes_amount: u64,
+    no_amount: u64,
+    token_type: u8,
+) -> Instruction {
+    build(
+        accounts::Resolution {
+            global_config: pda::config().0,
+            market: keys.market,
+            global_vault: pda::global_vault().0,
+            yes_token: keys.yes_token,
+            no_token: keys.no_token,
+            user_yes_ata: pda::user_ata(user, &keys.yes_token),
+            user_no_ata: pda::user_ata(user, &keys.no_token),
+            user_info: pda::user_info(user, &keys.market).0,
+            user: *user,
+            authority: authority.copied(),
+            system_program: system_program::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::Resolution {
+            yes_amount,
+            no_amount,
+            token_type,
+        },
+    )
+}
+
+//  permissionless once the outcome is confirmed, renames both outcome tokens
+pub fn update_outcome_metadata(signer: &Pubkey, keys: &MarketKeys) -> Instruction {
+    build(
+        accounts::UpdateOutcomeMetadata {
+            global_config: pda::config().0,
+            market: keys.market,
+            global_vault: pda::global_vault().0,
+            yes_token_metadata_account: pda::metadata(&keys.yes_token).0,
+            no_token_metadata_account: pda::metadata(&keys.no_token).0,
+            signer: *signer,
+            mpl_token_metadata_program: metadata::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::UpdateOutcomeMetadata {},
+    )
+}
+
+//  signed by the position owner once the outcome is confirmed, burns the position from the
+//  given token accounts and pays `recipient`
+pub fn cpi_redeem(
+    owner: &Pubkey,
+    recipient: &Pubkey,
+    yes_token_account: &Pubkey,
+    no_token_account: &Pubkey,
+    keys: &MarketKeys,
+) -> Instruction {
+    build(
+        accounts::CpiRedeem {
+            market: keys.market,
+            global_vault: pda::global_vault().0,
+            user_info: pda::user_info(owner, &keys.market).0,
+            yes_token: keys.yes_token,
+            no_token: keys.no_token,
+            yes_token_account: *yes_token_account,
+            no_token_account: *no_token_account,
+            owner: *owner,
+            recipient: *recipient,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::CpiRedeem {},
+    )
+}
+
+//  signed by the config authority, positions are refunded at cost afterwards
+pub fn void_market(authority: &Pubkey, keys: &MarketKeys) -> Instruction {
+    build(
+        accounts::VoidMarket {
+            global_config: pda::config().0,
+            market: keys.market,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::VoidMarket {},
+    )
+}
+
+//  any signer, once the parent of the conditional `market` resolved against its condition
+pub fn void_conditional_market(
+    signer: &Pubkey,
+    market: &Pubkey,
+    parent_market: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::VoidConditionalMarket {
+            market: *market,
+            parent_market: *parent_market,
+            signer: *signer,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::VoidConditionalMarket {},
+    )
+}
+
+//  signed by the position owner once the market is voided, burns the position's tokens
+pub fn refund(user: &Pubkey, keys: &MarketKeys) -> Instruction {
+    build(
+        accounts::Refund {
+            market: keys.market,
+            global_vault: pda::global_vault().0,
+            yes_token: keys.yes_token,
+            no_token: keys.no_token,
+            user_yes_ata: pda::user_ata(user, &keys.yes_token),
+            user_no_ata: pda::user_ata(user, &keys.no_token),
+            user_info: pda::user_info(user, &keys.market).0,
+            user: *user,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::Refund {},
+    )
+}
+
+//  signed and paid for by the referrer's authority
+//  signed by the config authority, approves `referrer_authority` as a referrer
+pub fn register_referrer(authority: &Pubkey, referrer_authority: &Pubkey) -> Instruction {
+    build(
+        accounts::RegisterReferrer {
+            global_config: pda::config().0,
+            authority: *authority,
+            referrer: pda::referrer(referrer_authority).0,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::RegisterReferrer {
+            referrer_authority: *referrer_authority,
+        },
+    )
+}
+
+pub fn claim_referral_fees(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::ClaimReferralFees {
+            authority: *authority,
+            referrer: pda::referrer(authority).0,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::ClaimReferralFees {},
+    )
+}
+
+//  signed by the config authority, creates the pool on its first funding
+pub fn fund_parlay_pool(authority: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::FundParlayPool {
+            global_config: pda::config().0,
+            parlay_pool: pda::parlay_pool().0,
+            authority: *authority,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::FundParlayPool { amount },
+    )
+}
+
+pub fn withdraw_parlay_pool(authority: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::WithdrawParlayPool {
+            global_config: pda::config().0,
+            parlay_pool: pda::parlay_pool().0,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::WithdrawParlayPool { amount },
+    )
+}
+
+//  `parlay_id` is the pool's `parlay_count`, the markets follow in pick order
+pub fn open_parlay(
+    owner: &Pubkey,
+    team_wallet: &Pubkey,
+    parlay_id: u64,
+    picks: &[ParlayPick],
+    stake: u64,
+    min_payout: u64,
+) -> Instruction {
+    let mut ix = build(
+        accounts::OpenParlay {
+            global_config: pda::config().0,
+            team_wallet: *team_wallet,
+            parlay_pool: pda::parlay_pool().0,
+            parlay: pda::parlay(parlay_id).0,
+            owner: *owner,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::OpenParlay {
+            picks: picks.to_vec(),
+            stake,
+            min_payout,
+        },
+    );
+    ix.accounts.extend(
+        picks
+            .iter()
+            .map(|pick| AccountMeta::new(pick.market, false)),
+    );
+    ix
+}
+
+//  permissionless, pays the owner of the decoded `parlay`
+pub fn settle_parlay(parlay: &Parlay) -> Instruction {
+    let mut ix = build(
+        accounts::SettleParlay {
+            parlay_pool: pda::parlay_pool().0,
+            parlay: pda::parlay(parlay.id).0,
+            owner: parlay.owner,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SettleParlay {},
+    );
+    ix.accounts.extend(
+        parlay
+            .legs
+            .iter()
+            .map(|leg| AccountMeta::new(leg.market, false)),
+    );
+    ix
+}
+
+//  signed by the position owner, who gets the rent back
+pub fn close_user_info(user: &Pubkey, market: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseUserInfo {
+            user: *user,
+            market: *market,
+            user_info: pda::user_info(user, market).0,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::CloseUserInfo {},
+    )
+}
+
+//  permissionless, `market` must be the decoded account at `keys.market`
+pub fn close_market(keys: &MarketKeys, market: &Market, team_wallet: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseMarket {
+            global_config: pda::config().0,
+            team_wallet: *team_wallet,
+            global_vault: pda::global_vault().0,
+            creator: market.creator,
+            market: keys.market,
+            market_metadata: pda::market_metadata(&keys.market).0,
+            market_entry: pda::market_entry(market.id).0,
+            parent_market: market.condition.map(|condition| condition.parent),
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::CloseMarket {},
+    )
+}
@@ -0,0 +1,40 @@
+//! Rust client for the prediction-market program.
+//!
+//! `pda` derives every program address, `instructions` builds each instruction
+//! with its account metas, `accounts` decodes program accounts and return
+//! data, `registry` enumerates markets by id or category, `trade` turns
+//! on-chain markets into a swap or parlay with a slippage bound and `config`
+//! parses config bounds.
+
+pub mod accounts;
+pub mod config;
+pub mod instructions;
+pub mod pda;
+pub mod registry;
+pub mod trade;
+
+pub use accounts::{
+    decode_category, decode_category_page, decode_config, decode_market, decode_market_entry,
+    decode_market_metadata, decode_parlay, decode_parlay_pool, decode_referrer, decode_registry,
+    decode_return_data, decode_token_account, decode_user_info, decode_user_stats,
+};
+pub use config::{parse_amount_config, parse_fee_tier};
+pub use instructions::MarketKeys;
+pub use prediction_market::{
+    state::{
+        config::{AmountConfig, Config, FeeTier},
+        market::{
+            CreateMarketParams, Market, MarketCondition, MarketStatus, SwapLeg, SwapQuote,
+            TradeLimits, UserInfo,
+        },
+        market_metadata::{MarketMetadata, MarketMetadataParams},
+        parlay::{Parlay, ParlayLeg, ParlayOutcome, ParlayPick, ParlayPool},
+        price_oracle::Twap,
+        referrer::Referrer,
+        registry::{Category, CategoryPage, MarketEntry, MarketRegistry},
+        user_stats::UserStats,
+    },
+    ID,
+};
+pub use registry::RegistrySlot;
+pub use trade::{buy, buy_no, buy_yes, parlay, sell, ParlayPlan, TradePlan};
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::{associated_token::get_associated_token_address, metadata};
+use prediction_market::{constants::*, ID};
+
+pub fn config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG.as_bytes()], &ID)
+}
+
+//  holds the pool SOL and signs for the vault token accounts
+pub fn global_vault() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GLOBAL.as_bytes()], &ID)
+}
+
+pub fn market(yes_token: &Pubkey, no_token: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MARKET.as_bytes(), &yes_token.to_bytes(), &no_token.to_bytes()],
+        &ID,
+    )
+}
+
+//  binds a NO mint to its creator and the one market using it
+pub fn no_token_record(no_token: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[NO_TOKEN.as_bytes(), no_token.as_ref()], &ID)
+}
+
+//  question, rules and tags of a market
+pub fn market_metadata(market: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MARKET_METADATA.as_bytes(), &market.to_bytes()], &ID)
+}
+
+//  sequential market id counter
+pub fn registry() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REGISTRY.as_bytes()], &ID)
+}
+
+pub fn market_entry(market_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MARKET_ENTRY.as_bytes(), &market_id.to_le_bytes()], &ID)
+}
+
+pub fn category(name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CATEGORY.as_bytes(), name.as_bytes()], &ID)
+}
+
+pub fn category_page(name: &str, index: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CATEGORY_PAGE.as_bytes(), name.as_bytes(), &index.to_le_bytes()],
+        &ID,
+    )
+}
+
+pub fn user_info(user: &Pubkey, market: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[USERINFO.as_bytes(), &user.to_bytes(), &market.to_bytes()],
+        &ID,
+    )
+}
+
+//  Metaplex metadata account of an outcome token mint
+pub fn metadata(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[METADATA.as_bytes(), metadata::ID.as_ref(), mint.as_ref()],
+        &metadata::ID,
+    )
+}
+
+pub fn event_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"__event_authority"], &ID)
+}
+
+//  global vault token account holding the pool side of `mint`
+pub fn vault_ata(mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(&global_vault().0, mint)
+}
+
+pub fn user_ata(user: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(user, mint)
+}
+
+//  referral fees of a frontend, keyed by its authority
+pub fn referrer(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REFERRER.as_bytes(), authority.as_ref()], &ID)
+}
+
+//  rolling trading volume of a user across markets, picks their fee tier
+pub fn user_stats(user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[USER_STATS.as_bytes(), user.as_ref()], &ID)
+}
+
+//  house liquidity backing every parlay, also holds the stakes
+pub fn parlay_pool() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PARLAY_POOL.as_bytes()], &ID)
+}
+
+//  ids are sequential, the next one is `ParlayPool::parlay_count`
+pub fn parlay(parlay_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PARLAY.as_bytes(), &parlay_id.to_le_bytes()], &ID)
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::Pubkey;
+use prediction_market::state::registry::{Category, MarketRegistry};
+
+use crate::pda;
+
+//  registry accounts a new market will be written to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegistrySlot {
+    pub market_id: u64,
+    pub page: u32,
+}
+
+impl RegistrySlot {
+    //  `None` for accounts that do not exist yet
+    pub fn next(registry: Option<&MarketRegistry>, category: Option<&Category>) -> Self {
+        Self {
+            market_id: registry.map_or(0, |registry| registry.market_count),
+            page: category.map_or(0, Category::append_page),
+        }
+    }
+}
+
+//  every page of a category, reading them all lists its open markets
+pub fn category_pages(name: &str, category: &Category) -> Vec<Pubkey> {
+    (0..category.page_count)
+        .map(|index| pda::category_page(name, index).0)
+        .collect()
+}
+
+//  id lookup accounts of every market created so far
+pub fn market_entries(registry: &MarketRegistry) -> Vec<Pubkey> {
+    (0..registry.market_count)
+        .map(|market_id| pda::market_entry(market_id).0)
+        .collect()
+}
@@ -0,0 +1,261 @@
+use anchor_lang::{
+    prelude::Pubkey, solana_program::instruction::Instruction, AnchorDeserialize, InstructionData,
+};
+use anyhow::{anyhow, Result};
+use prediction_market::{
+    instruction,
+    state::{
+        config::Config,
+        market::{Market, DIRECTION_BUY, DIRECTION_SELL, TOKEN_TYPE_NO, TOKEN_TYPE_YES},
+        parlay::ParlayPick,
+    },
+};
+use prediction_market_math::{
+    bps_of, parlay_payout, price_impact_bps, quote_buy, quote_sell, FeeBreakdown, Reserves,
+    Rounding, BPS_DENOMINATOR,
+};
+
+use crate::instructions::{self, MarketKeys};
+
+//  a swap instruction together with the quote it was built from
+#[derive(Clone, Debug)]
+pub struct TradePlan {
+    pub instruction: Instruction,
+    //  tokens for a buy, lamports for a sell, at the current reserves
+    pub expected_amount: u64,
+    pub minimum_receive_amount: u64,
+    pub fees: FeeBreakdown,
+    //  move of the traded side's price, the lp fee excluded as on-chain
+    pub price_impact_bps: u64,
+}
+
+impl TradePlan {
+    //  reject the swap once `valid_until_slot` passed or the price moves more than
+    //  `max_price_impact_bps`
+    pub fn with_guards(
+        mut self,
+        valid_until_slot: Option<u64>,
+        max_price_impact_bps: Option<u64>,
+    ) -> Result<Self> {
+        let mut args = instruction::Swap::deserialize(&mut &self.instruction.data[8..])?;
+        args.valid_until_slot = valid_until_slot;
+        args.max_price_impact_bps = max_price_impact_bps;
+        self.instruction.data = args.data();
+        Ok(self)
+    }
+}
+
+//  lowest amount accepted when `expected` may move by `slippage_bps`, rounding up
+fn minimum_receive(expected: u64, slippage_bps: u64) -> Result<u64> {
+    let slippage = bps_of(slippage_bps.min(BPS_DENOMINATOR), expected, Rounding::Up)
+        .ok_or_else(|| anyhow!("slippage overflow"))?;
+    Ok(expected.saturating_sub(slippage))
+}
+
+fn impact(before: &Reserves, after: Option<Reserves>, token_type: u8) -> Result<u64> {
+    after
+        .and_then(|after| price_impact_bps(before, &after, token_type))
+        .ok_or_else(|| anyhow!("price impact overflow"))
+}
+
+fn market_keys(market: &Market) -> MarketKeys {
+    MarketKeys::new(market.yes_token_mint, market.no_token_mint)
+}
+
+//  spend `lamports` (fees included) on `token_type` tokens, at the fee tier of `volume`, the
+//  user's `UserStats::volume`
+#[allow(clippy::too_many_arguments)]
+pub fn buy(
+    user: &Pubkey,
+    config: &Config,
+    market: &Market,
+    token_type: u8,
+    lamports: u64,
+    slippage_bps: u64,
+    volume: u64,
+    referrer: Option<&Pubkey>,
+) -> Result<TradePlan> {
+    let reserves = market.curve_reserves();
+    let quote = quote_buy(
+        &reserves,
+        lamports,
+        token_type,
+        config.buy_fee_rates(volume),
+    )
+    .ok_or_else(|| anyhow!("buy of {lamports} lamports cannot be filled"))?;
+    let expected_amount = quote.result.token_amount;
+    let minimum_receive_amount = minimum_receive(expected_amount, slippage_bps)?;
+
+    Ok(TradePlan {
+        instruction: instructions::swap(
+            user,
+            &market_keys(market),
+            &config.team_wallet,
+            lamports,
+            DIRECTION_BUY,
+            token_type,
+            minimum_receive_amount,
+            None,
+            None,
+            referrer,
+        ),
+        expected_amount,
+        minimum_receive_amount,
+        fees: quote.fees,
+        price_impact_bps: impact(
+            &reserves,
+            quote.result.reserves_after(&reserves, token_type),
+            token_type,
+        )?,
+    })
+}
+
+//  sell `token_amount` tokens of `token_type` for SOL (fees deducted), at the fee tier of `volume`
+#[allow(clippy::too_many_arguments)]
+pub fn sell(
+    user: &Pubkey,
+    config: &Config,
+    market: &Market,
+    token_type: u8,
+    token_amount: u64,
+    slippage_bps: u64,
+    volume: u64,
+    referrer: Option<&Pubkey>,
+) -> Result<TradePlan> {
+    let reserves = market.curve_reserves();
+    let quote = quote_sell(
+        &reserves,
+        token_amount,
+        token_type,
+        config.sell_fee_rates(volume),
+    )
+    .ok_or_else(|| anyhow!("sell of {token_amount} tokens cannot be filled"))?;
+    let expected_amount = quote.amount_out;
+    let minimum_receive_amount = minimum_receive(expected_amount, slippage_bps)?;
+
+    Ok(TradePlan {
+        instruction: instructions::swap(
+            user,
+            &market_keys(market),
+            &config.team_wallet,
+            token_amount,
+            DIRECTION_SELL,
+            token_type,
+            minimum_receive_amount,
+            None,
+            None,
+            referrer,
+        ),
+        expected_amount,
+        minimum_receive_amount,
+        fees: quote.fees,
+        price_impact_bps: impact(
+            &reserves,
+            quote.result.reserves_after(&reserves, token_type),
+            token_type,
+        )?,
+    })
+}
+
+//  quoted at the flat fees, a trader in a lower tier receives more than expected
+pub fn buy_yes(
+    user: &Pubkey,
+    config: &Config,
+    market: &Market,
+    lamports: u64,
+    slippage_bps: u64,
+) -> Result<TradePlan> {
+    buy(
+        user,
+        config,
+        market,
+        TOKEN_TYPE_YES,
+        lamports,
+        slippage_bps,
+        0,
+        None,
+    )
+}
+
+pub fn buy_no(
+    user: &Pubkey,
+    config: &Config,
+    market: &Market,
+    lamports: u64,
+    slippage_bps: u64,
+) -> Result<TradePlan> {
+    buy(
+        user,
+        config,
+        market,
+        TOKEN_TYPE_NO,
+        lamports,
+        slippage_bps,
+        0,
+        None,
+    )
+}
+
+//  an open_parlay instruction together with the payout it was priced at
+#[derive(Clone, Debug)]
+pub struct ParlayPlan {
+    pub instruction: Instruction,
+    pub parlay_id: u64,
+    //  paid if every pick wins, at the current implied prices
+    pub expected_payout: u64,
+    pub min_payout: u64,
+    //  platform fee charged on top of the stake
+    pub fee_lamports: u64,
+}
+
+//  stake `lamports` on every `(market, token_type)` pick winning, `parlay_id` is the
+//  pool's `ParlayPool::parlay_count`
+pub fn parlay(
+    owner: &Pubkey,
+    config: &Config,
+    parlay_id: u64,
+    picks: &[(Market, u8)],
+    lamports: u64,
+    slippage_bps: u64,
+) -> Result<ParlayPlan> {
+    let prices = picks
+        .iter()
+        .map(|(market, token_type)| {
+            let (yes_price, no_price) = market
+                .implied_prices()
+                .ok_or_else(|| anyhow!("market #{} has no price", market.id))?;
+            Ok(if *token_type == TOKEN_TYPE_YES {
+                yes_price
+            } else {
+                no_price
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let expected_payout = parlay_payout(lamports, &prices)
+        .ok_or_else(|| anyhow!("payout of a {lamports} lamports parlay overflows"))?;
+    let min_payout = minimum_receive(expected_payout, slippage_bps)?.max(lamports);
+    let fee_lamports = bps_of(config.platform_buy_fee, lamports, Rounding::Up)
+        .ok_or_else(|| anyhow!("fee overflow"))?;
+    let picks: Vec<ParlayPick> = picks
+        .iter()
+        .map(|(market, token_type)| ParlayPick {
+            market: market_keys(market).market,
+            token_type: *token_type,
+        })
+        .collect();
+
+    Ok(ParlayPlan {
+        instruction: instructions::open_parlay(
+            owner,
+            &config.team_wallet,
+            parlay_id,
+            &picks,
+            lamports,
+            min_payout,
+        ),
+        parlay_id,
+        expected_payout,
+        min_payout,
+        fee_lamports,
+    })
+}
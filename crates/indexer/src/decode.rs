@@ -0,0 +1,230 @@
+use std::str::FromStr;
+
+use anchor_lang::{
+    event::EVENT_IX_TAG_LE, prelude::Pubkey, AnchorDeserialize, Discriminator,
+};
+use anyhow::{anyhow, Context, Result};
+use prediction_market::events::*;
+use serde_json::Value;
+
+//  every event the program emits through `emit_cpi!`
+pub enum ProgramEvent {
+    ConfigUpdate(ConfigUpdateEvent),
+    NominateAuthority(NominateAuthorityEvent),
+    AcceptAuthority(AcceptAuthorityEvent),
+    MintNoToken(MintNoTokenEvent),
+    Create(CreateEvent),
+    MarketMetadata(MarketMetadataEvent),
+    TradeLimits(TradeLimitsEvent),
+    MarketRegistry(MarketRegistryEvent),
+    Trade(TradeEvent),
+    RegisterReferrer(RegisterReferrerEvent),
+    ReferralClaim(ReferralClaimEvent),
+    AddLiquidity(AddLiquidityEvent),
+    WithdrawLiquidity(WithdrawLiquidityEvent),
+    ResolutionProposed(ResolutionProposedEvent),
+    Resolution(ResolutionEvent),
+    Payout(PayoutEvent),
+    MarketVoided(MarketVoidedEvent),
+    OutcomeMetadata(OutcomeMetadataEvent),
+    Refund(RefundEvent),
+    CloseUserInfo(CloseUserInfoEvent),
+    CloseMarket(CloseMarketEvent),
+    ParlayPool(ParlayPoolEvent),
+    ParlayOpen(ParlayOpenEvent),
+    ParlaySettle(ParlaySettleEvent),
+}
+
+impl ProgramEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ConfigUpdate(_) => "config_update",
+            Self::NominateAuthority(_) => "nominate_authority",
+            Self::AcceptAuthority(_) => "accept_authority",
+            Self::MintNoToken(_) => "mint_no_token",
+            Self::Create(_) => "create",
+            Self::MarketMetadata(_) => "market_metadata",
+            Self::TradeLimits(_) => "trade_limits",
+            Self::MarketRegistry(_) => "market_registry",
+            Self::Trade(_) => "trade",
+            Self::RegisterReferrer(_) => "register_referrer",
+            Self::ReferralClaim(_) => "referral_claim",
+            Self::AddLiquidity(_) => "add_liquidity",
+            Self::WithdrawLiquidity(_) => "withdraw_liquidity",
+            Self::ResolutionProposed(_) => "resolution_proposed",
+            Self::Resolution(_) => "resolution",
+            Self::Payout(_) => "payout",
+            Self::MarketVoided(_) => "market_voided",
+            Self::OutcomeMetadata(_) => "outcome_metadata",
+            Self::Refund(_) => "refund",
+            Self::CloseUserInfo(_) => "close_user_info",
+            Self::CloseMarket(_) => "close_market",
+            Self::ParlayPool(_) => "parlay_pool",
+            Self::ParlayOpen(_) => "parlay_open",
+            Self::ParlaySettle(_) => "parlay_settle",
+        }
+    }
+}
+
+//  an event together with where it was found on chain
+pub struct DecodedEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    //  position of the event inside its transaction, stable across re-ingestion
+    pub event_index: u32,
+    pub event: ProgramEvent,
+}
+
+fn deserialize<T: AnchorDeserialize>(mut data: &[u8]) -> Option<T> {
+    T::deserialize(&mut data).ok()
+}
+
+//  decode `discriminator || borsh(event)`, returns None for unknown discriminators
+pub fn decode_event(data: &[u8]) -> Option<ProgramEvent> {
+    if data.len() < 8 {
+        return None;
+    }
+    let (discriminator, body) = data.split_at(8);
+
+    let event = match discriminator {
+        d if d == ConfigUpdateEvent::DISCRIMINATOR => {
+            ProgramEvent::ConfigUpdate(deserialize(body)?)
+        }
+        d if d == NominateAuthorityEvent::DISCRIMINATOR => {
+            ProgramEvent::NominateAuthority(deserialize(body)?)
+        }
+        d if d == AcceptAuthorityEvent::DISCRIMINATOR => {
+            ProgramEvent::AcceptAuthority(deserialize(body)?)
+        }
+        d if d == MintNoTokenEvent::DISCRIMINATOR => ProgramEvent::MintNoToken(deserialize(body)?),
+        d if d == CreateEvent::DISCRIMINATOR => ProgramEvent::Create(deserialize(body)?),
+        d if d == MarketMetadataEvent::DISCRIMINATOR => {
+            ProgramEvent::MarketMetadata(deserialize(body)?)
+        }
+        d if d == TradeLimitsEvent::DISCRIMINATOR => ProgramEvent::TradeLimits(deserialize(body)?),
+        d if d == MarketRegistryEvent::DISCRIMINATOR => {
+            ProgramEvent::MarketRegistry(deserialize(body)?)
+        }
+        d if d == TradeEvent::DISCRIMINATOR => ProgramEvent::Trade(deserialize(body)?),
+        d if d == RegisterReferrerEvent::DISCRIMINATOR => {
+            ProgramEvent::RegisterReferrer(deserialize(body)?)
+        }
+        d if d == ReferralClaimEvent::DISCRIMINATOR => {
+            ProgramEvent::ReferralClaim(deserialize(body)?)
+        }
+        d if d == AddLiquidityEvent::DISCRIMINATOR => {
+            ProgramEvent::AddLiquidity(deserialize(body)?)
+        }
+        d if d == WithdrawLiquidityEvent::DISCRIMINATOR => {
+            ProgramEvent::WithdrawLiquidity(deserialize(body)?)
+        }
+        d if d == ResolutionProposedEvent::DISCRIMINATOR => {
+            ProgramEvent::ResolutionProposed(deserialize(body)?)
+        }
+        d if d == ResolutionEvent::DISCRIMINATOR => ProgramEvent::Resolution(deserialize(body)?),
+        d if d == PayoutEvent::DISCRIMINATOR => ProgramEvent::Payout(deserialize(body)?),
+        d if d == MarketVoidedEvent::DISCRIMINATOR => ProgramEvent::MarketVoided(deserialize(body)?),
+        d if d == OutcomeMetadataEvent::DISCRIMINATOR => {
+            ProgramEvent::OutcomeMetadata(deserialize(body)?)
+        }
+        d if d == RefundEvent::DISCRIMINATOR => ProgramEvent::Refund(deserialize(body)?),
+        d if d == CloseUserInfoEvent::DISCRIMINATOR => {
+            ProgramEvent::CloseUserInfo(deserialize(body)?)
+        }
+        d if d == CloseMarketEvent::DISCRIMINATOR => ProgramEvent::CloseMarket(deserialize(body)?),
+        d if d == ParlayPoolEvent::DISCRIMINATOR => ProgramEvent::ParlayPool(deserialize(body)?),
+        d if d == ParlayOpenEvent::DISCRIMINATOR => ProgramEvent::ParlayOpen(deserialize(body)?),
+        d if d == ParlaySettleEvent::DISCRIMINATOR => {
+            ProgramEvent::ParlaySettle(deserialize(body)?)
+        }
+        _ => return None,
+    };
+    Some(event)
+}
+
+//  decode the data of a self-CPI instruction emitted by `emit_cpi!`
+pub fn decode_event_cpi(ix_data: &[u8]) -> Option<ProgramEvent> {
+    ix_data
+        .strip_prefix(EVENT_IX_TAG_LE.as_slice())
+        .and_then(decode_event)
+}
+
+//  account keys of a `json` or `jsonParsed` encoded transaction, including loaded addresses
+fn account_keys(tx: &Value) -> Result<Vec<Pubkey>> {
+    let mut keys = vec![];
+    let static_keys = tx["transaction"]["message"]["accountKeys"]
+        .as_array()
+        .ok_or_else(|| anyhow!("transaction has no accountKeys"))?;
+    for key in static_keys {
+        let key = key
+            .as_str()
+            .or_else(|| key["pubkey"].as_str())
+            .ok_or_else(|| anyhow!("invalid account key {key}"))?;
+        keys.push(Pubkey::from_str(key)?);
+    }
+    for section in ["writable", "readonly"] {
+        if let Some(loaded) = tx["meta"]["loadedAddresses"][section].as_array() {
+            for key in loaded {
+                let key = key
+                    .as_str()
+                    .ok_or_else(|| anyhow!("invalid loaded address {key}"))?;
+                keys.push(Pubkey::from_str(key)?);
+            }
+        }
+    }
+    Ok(keys)
+}
+
+//  collect all events emitted by `program_id` in one `getTransaction` result
+pub fn decode_transaction(tx: &Value, program_id: &Pubkey) -> Result<Vec<DecodedEvent>> {
+    //  failed transactions roll back every state change
+    if !tx["meta"]["err"].is_null() {
+        return Ok(vec![]);
+    }
+
+    let signature = tx["transaction"]["signatures"][0]
+        .as_str()
+        .ok_or_else(|| anyhow!("transaction has no signature"))?
+        .to_string();
+    let slot = tx["slot"]
+        .as_u64()
+        .ok_or_else(|| anyhow!("transaction {signature} has no slot"))?;
+    let block_time = tx["blockTime"].as_i64();
+    let keys = account_keys(tx).with_context(|| format!("transaction {signature}"))?;
+
+    let mut events = vec![];
+    let inner_sets = tx["meta"]["innerInstructions"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    for inner_set in inner_sets {
+        let Some(instructions) = inner_set["instructions"].as_array() else {
+            continue;
+        };
+        for ix in instructions {
+            let program = ix["programIdIndex"]
+                .as_u64()
+                .and_then(|index| keys.get(index as usize));
+            if program != Some(program_id) {
+                continue;
+            }
+            let Some(data) = ix["data"].as_str() else {
+                continue;
+            };
+            let data = bs58::decode(data)
+                .into_vec()
+                .with_context(|| format!("transaction {signature}: invalid instruction data"))?;
+            if let Some(event) = decode_event_cpi(&data) {
+                events.push(DecodedEvent {
+                    signature: signature.clone(),
+                    slot,
+                    block_time,
+                    event_index: events.len() as u32,
+                    event,
+                });
+            }
+        }
+    }
+    Ok(events)
+}
@@ -0,0 +1,7 @@
+pub mod decode;
+pub mod source;
+pub mod store;
+
+pub use decode::{decode_event, decode_event_cpi, decode_transaction, DecodedEvent, ProgramEvent};
+pub use source::{read_transactions, RpcSource};
+pub use store::Store;
@@ -0,0 +1,82 @@
+use std::{path::PathBuf, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use prediction_market_indexer::{decode_transaction, read_transactions, RpcSource, Store};
+use serde_json::Value;
+
+#[derive(Parser)]
+#[command(about = "Index prediction-market events into SQLite")]
+struct Cli {
+    //  SQLite database, created on first run
+    #[arg(long, default_value = "prediction-market.sqlite")]
+    db: PathBuf,
+
+    #[arg(long, value_parser = Pubkey::from_str, default_value_t = prediction_market::ID)]
+    program_id: Pubkey,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Ingest `getTransaction` JSON dumps
+    File { paths: Vec<PathBuf> },
+
+    /// Ingest the program's transactions from an RPC node
+    Rpc {
+        #[arg(long, default_value = "http://127.0.0.1:8899")]
+        url: String,
+
+        #[arg(long, default_value_t = 1000)]
+        limit: usize,
+
+        //  stop at this signature, e.g. the last one ingested by a previous run
+        #[arg(long)]
+        until: Option<String>,
+    },
+}
+
+fn ingest(store: &mut Store, program_id: &Pubkey, transactions: &[Value]) -> Result<usize> {
+    let mut applied = 0;
+    for tx in transactions {
+        applied += store.ingest(&decode_transaction(tx, program_id)?)?;
+    }
+    Ok(applied)
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut store = Store::open(&cli.db)?;
+
+    let (transactions, applied) = match cli.command {
+        Command::File { paths } => {
+            let mut transactions = 0;
+            let mut applied = 0;
+            for path in paths {
+                let batch = read_transactions(&path)?;
+                transactions += batch.len();
+                applied += ingest(&mut store, &cli.program_id, &batch)?;
+            }
+            (transactions, applied)
+        }
+        Command::Rpc { url, limit, until } => {
+            let rpc = RpcSource::new(url);
+            let signatures = rpc.signatures(&cli.program_id, until.as_deref(), limit)?;
+            let mut applied = 0;
+            for signature in &signatures {
+                let tx = rpc.transaction(signature)?;
+                applied += ingest(&mut store, &cli.program_id, &[tx])?;
+            }
+            (signatures.len(), applied)
+        }
+    };
+
+    println!(
+        "ingested {applied} new events from {transactions} transactions into {}",
+        cli.db.display()
+    );
+    Ok(())
+}
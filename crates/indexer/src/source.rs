@@ -0,0 +1,127 @@
+use std::{fs, path::Path};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+
+//  `getSignaturesForAddress` returns at most 1000 signatures per page
+const SIGNATURE_PAGE_LIMIT: usize = 1000;
+
+//  a file holds one `getTransaction` result, a JSON-RPC response wrapping it, or an array of either
+pub fn read_transactions(path: impl AsRef<Path>) -> Result<Vec<Value>> {
+    let path = path.as_ref();
+    let content =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let value: Value = serde_json::from_str(&content)
+        .with_context(|| format!("{} is not valid JSON", path.display()))?;
+
+    let items = match value {
+        Value::Array(items) => items,
+        item => vec![item],
+    };
+    Ok(items.into_iter().map(unwrap_response).collect())
+}
+
+fn unwrap_response(value: Value) -> Value {
+    match value {
+        Value::Object(mut object) if object.contains_key("jsonrpc") => {
+            object.remove("result").unwrap_or(Value::Null)
+        }
+        value => value,
+    }
+}
+
+//  minimal JSON-RPC client for a local validator or any RPC node
+pub struct RpcSource {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl RpcSource {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            agent: ureq::Agent::new(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .with_context(|| format!("{method} request to {} failed", self.url))?
+            .into_json()?;
+
+        if let Some(error) = response.get("error") {
+            bail!("{method} failed: {error}");
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| anyhow!("{method} returned no result"))
+    }
+
+    //  signatures touching `address`, oldest first, stopping at `until` when given
+    pub fn signatures(
+        &self,
+        address: &Pubkey,
+        until: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        let mut signatures = vec![];
+        let mut before: Option<String> = None;
+
+        while signatures.len() < limit {
+            let page_limit = SIGNATURE_PAGE_LIMIT.min(limit - signatures.len());
+            let mut config = json!({ "limit": page_limit, "commitment": "confirmed" });
+            if let Some(before) = &before {
+                config["before"] = json!(before);
+            }
+            if let Some(until) = until {
+                config["until"] = json!(until);
+            }
+
+            let page = self.call("getSignaturesForAddress", json!([address.to_string(), config]))?;
+            let page = page
+                .as_array()
+                .ok_or_else(|| anyhow!("getSignaturesForAddress returned {page}"))?;
+            for entry in page {
+                let signature = entry["signature"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("invalid signature entry {entry}"))?;
+                signatures.push(signature.to_string());
+            }
+
+            match page.last() {
+                Some(last) if page.len() == page_limit => {
+                    before = last["signature"].as_str().map(str::to_string);
+                }
+                _ => break,
+            }
+        }
+
+        //  RPC pages newest first, ingestion wants chain order
+        signatures.reverse();
+        Ok(signatures)
+    }
+
+    pub fn transaction(&self, signature: &str) -> Result<Value> {
+        self.call(
+            "getTransaction",
+            json!([
+                signature,
+                {
+                    "encoding": "json",
+                    "commitment": "confirmed",
+                    "maxSupportedTransactionVersion": 0,
+                }
+            ]),
+        )
+    }
+}
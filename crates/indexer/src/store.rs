@@ -0,0 +1,647 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use prediction_market::events::*;
+use rusqlite::{params, Connection, Transaction};
+
+use crate::decode::{DecodedEvent, ProgramEvent};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS processed_events (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS markets (
+    market TEXT PRIMARY KEY,
+    creator TEXT,
+    token_yes TEXT,
+    token_no TEXT,
+    metadata_yes TEXT,
+    metadata_no TEXT,
+    start_slot INTEGER,
+    ending_slot INTEGER,
+    start_time INTEGER,
+    end_time INTEGER,
+    real_sol_reserves INTEGER NOT NULL DEFAULT 0,
+    real_yes_sol_reserves INTEGER NOT NULL DEFAULT 0,
+    real_no_sol_reserves INTEGER NOT NULL DEFAULT 0,
+    real_token_yes_reserves INTEGER,
+    real_token_no_reserves INTEGER,
+    total_lp_amount INTEGER NOT NULL DEFAULT 0,
+    volume INTEGER NOT NULL DEFAULT 0,
+    is_completed INTEGER NOT NULL DEFAULT 0,
+    is_voided INTEGER NOT NULL DEFAULT 0,
+    winning_token_type INTEGER,
+    max_position_per_wallet INTEGER,
+    min_trade_lamports INTEGER NOT NULL DEFAULT 0,
+    max_trade_lamports INTEGER,
+    created_slot INTEGER,
+    updated_slot INTEGER NOT NULL DEFAULT 0,
+    closed_slot INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS market_metadata (
+    market TEXT PRIMARY KEY,
+    creator TEXT NOT NULL,
+    question TEXT NOT NULL,
+    rules TEXT NOT NULL,
+    resolution_source TEXT NOT NULL,
+    category TEXT NOT NULL,
+    tags TEXT NOT NULL,
+    updated_slot INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS market_metadata_category ON market_metadata (category);
+
+CREATE TABLE IF NOT EXISTS market_registry (
+    market TEXT PRIMARY KEY,
+    market_id INTEGER NOT NULL,
+    category TEXT NOT NULL,
+    page INTEGER NOT NULL,
+    archived INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS market_registry_category ON market_registry (category, archived);
+
+CREATE TABLE IF NOT EXISTS trades (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    market TEXT NOT NULL,
+    user TEXT NOT NULL,
+    is_buy INTEGER NOT NULL,
+    is_yes INTEGER NOT NULL,
+    sol_amount INTEGER NOT NULL,
+    token_amount INTEGER NOT NULL,
+    fee_lamports INTEGER NOT NULL,
+    referrer TEXT,
+    referral_fee INTEGER NOT NULL DEFAULT 0,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS trades_market ON trades (market, slot);
+CREATE INDEX IF NOT EXISTS trades_user ON trades (user, slot);
+CREATE INDEX IF NOT EXISTS trades_referrer ON trades (referrer, slot);
+
+CREATE TABLE IF NOT EXISTS referrers (
+    authority TEXT PRIMARY KEY,
+    referrer TEXT NOT NULL,
+    registered_slot INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS positions (
+    market TEXT NOT NULL,
+    user TEXT NOT NULL,
+    yes_amount INTEGER NOT NULL DEFAULT 0,
+    no_amount INTEGER NOT NULL DEFAULT 0,
+    sol_spent INTEGER NOT NULL DEFAULT 0,
+    sol_received INTEGER NOT NULL DEFAULT 0,
+    fees_paid INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (market, user)
+);
+
+CREATE TABLE IF NOT EXISTS lp_actions (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    market TEXT NOT NULL,
+    user TEXT NOT NULL,
+    is_add INTEGER NOT NULL,
+    sol_amount INTEGER NOT NULL,
+    user_lp_amount INTEGER NOT NULL,
+    total_lp_amount INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS lp_actions_market ON lp_actions (market, slot);
+";
+
+//  SQLite stores signed 64-bit integers
+fn int(value: u64) -> Result<i64> {
+    i64::try_from(value).map_err(|_| anyhow!("value {value} does not fit in an INTEGER column"))
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    //  apply events in one database transaction, returns how many were new
+    pub fn ingest(&mut self, events: &[DecodedEvent]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut applied = 0;
+        for event in events {
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO processed_events (signature, event_index, slot, kind)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    event.signature,
+                    event.event_index,
+                    int(event.slot)?,
+                    event.event.kind()
+                ],
+            )?;
+            //  already ingested, every table is left untouched
+            if inserted == 0 {
+                continue;
+            }
+            apply(&tx, event)?;
+            applied += 1;
+        }
+        tx.commit()?;
+        Ok(applied)
+    }
+}
+
+fn apply(tx: &Transaction, decoded: &DecodedEvent) -> Result<()> {
+    let slot = int(decoded.slot)?;
+    match &decoded.event {
+        ProgramEvent::Create(event) => apply_create(tx, event, slot),
+        ProgramEvent::MarketMetadata(event) => apply_market_metadata(tx, event, slot),
+        ProgramEvent::TradeLimits(event) => apply_trade_limits(tx, event),
+        ProgramEvent::MarketRegistry(event) => apply_market_registry(tx, event),
+        ProgramEvent::Trade(event) => apply_trade(tx, decoded, event, slot),
+        ProgramEvent::AddLiquidity(event) => apply_lp_action(
+            tx,
+            decoded,
+            LpAction {
+                user: event.user.to_string(),
+                market: event.market.to_string(),
+                is_add: true,
+                sol_amount: event.sol_amount,
+                user_lp_amount: event.user_lp_amount,
+                total_lp_amount: event.total_lp_amount,
+                real_yes_sol_reserves: event.real_yes_sol_reserves,
+                real_no_sol_reserves: event.real_no_sol_reserves,
+                timestamp: event.timestamp,
+            },
+            slot,
+        ),
+        ProgramEvent::WithdrawLiquidity(event) => apply_lp_action(
+            tx,
+            decoded,
+            LpAction {
+                user: event.user.to_string(),
+                market: event.market.to_string(),
+                is_add: false,
+                sol_amount: event.sol_amount,
+                user_lp_amount: event.user_lp_amount,
+                total_lp_amount: event.total_lp_amount,
+                real_yes_sol_reserves: event.real_yes_sol_reserves,
+                real_no_sol_reserves: event.real_no_sol_reserves,
+                timestamp: event.timestamp,
+            },
+            slot,
+        ),
+        ProgramEvent::ResolutionProposed(event) => apply_resolution_proposed(tx, event),
+        ProgramEvent::Resolution(event) => apply_resolution(tx, event, slot),
+        ProgramEvent::Payout(event) => apply_redemption(
+            tx,
+            Redemption {
+                market: event.market.to_string(),
+                user: event.user.to_string(),
+                yes_amount: event.yes_amount,
+                no_amount: event.no_amount,
+                sol_amount: event.sol_amount,
+            },
+        ),
+        ProgramEvent::MarketVoided(event) => apply_market_voided(tx, event),
+        ProgramEvent::Refund(event) => apply_redemption(
+            tx,
+            Redemption {
+                market: event.market.to_string(),
+                user: event.user.to_string(),
+                yes_amount: event.yes_amount,
+                no_amount: event.no_amount,
+                sol_amount: event.sol_amount,
+            },
+        ),
+        ProgramEvent::CloseMarket(event) => apply_close_market(tx, event, slot),
+        //  admin events only need to be recorded as processed
+        ProgramEvent::ConfigUpdate(_)
+        | ProgramEvent::NominateAuthority(_)
+        | ProgramEvent::AcceptAuthority(_)
+        | ProgramEvent::MintNoToken(_)
+        | ProgramEvent::CloseUserInfo(_) => Ok(()),
+        //  token names and uris are read from Metaplex, not the index
+        ProgramEvent::OutcomeMetadata(_) => Ok(()),
+        ProgramEvent::RegisterReferrer(event) => apply_register_referrer(tx, event, slot),
+        //  referral totals live in the referrer account, trades carry the attribution
+        ProgramEvent::ReferralClaim(_) => Ok(()),
+        //  parlays never touch the markets they reference
+        ProgramEvent::ParlayPool(_)
+        | ProgramEvent::ParlayOpen(_)
+        | ProgramEvent::ParlaySettle(_) => Ok(()),
+    }
+}
+
+fn apply_create(tx: &Transaction, event: &CreateEvent, slot: i64) -> Result<()> {
+    let ending_slot = match event.ending_slot {
+        u64::MAX => None,
+        ending_slot => Some(int(ending_slot)?),
+    };
+    let end_time = (event.end_time != i64::MAX).then_some(event.end_time);
+    tx.execute(
+        "INSERT INTO markets (
+            market, creator, token_yes, token_no, metadata_yes, metadata_no,
+            start_slot, ending_slot, real_yes_sol_reserves, real_no_sol_reserves,
+            real_sol_reserves, created_slot, updated_slot, start_time, end_time
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?12, ?13, ?14)
+        ON CONFLICT (market) DO UPDATE SET
+            creator = excluded.creator,
+            token_yes = excluded.token_yes,
+            token_no = excluded.token_no,
+            metadata_yes = excluded.metadata_yes,
+            metadata_no = excluded.metadata_no,
+            start_slot = excluded.start_slot,
+            ending_slot = excluded.ending_slot,
+            start_time = excluded.start_time,
+            end_time = excluded.end_time,
+            created_slot = excluded.created_slot",
+        params![
+            event.market.to_string(),
+            event.creator.to_string(),
+            event.token_yes.to_string(),
+            event.token_no.to_string(),
+            event.metadata_yes.to_string(),
+            event.metadata_no.to_string(),
+            int(event.start_slot)?,
+            ending_slot,
+            int(event.real_yes_sol_reserves)?,
+            int(event.real_no_sol_reserves)?,
+            int(event.real_yes_sol_reserves + event.real_no_sol_reserves)?,
+            slot,
+            event.start_time,
+            end_time,
+        ],
+    )?;
+    apply_trade_limits(
+        tx,
+        &TradeLimitsEvent {
+            market: event.market,
+            creator: event.creator,
+            max_position_per_wallet: event.max_position_per_wallet,
+            min_trade_lamports: event.min_trade_lamports,
+            max_trade_lamports: event.max_trade_lamports,
+        },
+    )
+}
+
+//  unlimited caps are stored as NULL
+fn apply_trade_limits(tx: &Transaction, event: &TradeLimitsEvent) -> Result<()> {
+    let limit = |value: u64| match value {
+        u64::MAX => Ok(None),
+        value => int(value).map(Some),
+    };
+    let market = event.market.to_string();
+    ensure_market(tx, &market)?;
+    tx.execute(
+        "UPDATE markets SET
+            max_position_per_wallet = ?2,
+            min_trade_lamports = ?3,
+            max_trade_lamports = ?4
+        WHERE market = ?1",
+        params![
+            market,
+            limit(event.max_position_per_wallet)?,
+            int(event.min_trade_lamports)?,
+            limit(event.max_trade_lamports)?,
+        ],
+    )?;
+    Ok(())
+}
+
+//  tags are stored as a JSON array
+fn apply_market_metadata(tx: &Transaction, event: &MarketMetadataEvent, slot: i64) -> Result<()> {
+    tx.execute(
+        "INSERT INTO market_metadata (
+            market, creator, question, rules, resolution_source, category, tags, updated_slot
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        ON CONFLICT (market) DO UPDATE SET
+            question = excluded.question,
+            rules = excluded.rules,
+            resolution_source = excluded.resolution_source,
+            category = excluded.category,
+            tags = excluded.tags,
+            updated_slot = excluded.updated_slot
+        WHERE market_metadata.updated_slot <= excluded.updated_slot",
+        params![
+            event.market.to_string(),
+            event.creator.to_string(),
+            event.question,
+            event.rules,
+            event.resolution_source,
+            event.category,
+            serde_json::to_string(&event.tags)?,
+            slot,
+        ],
+    )?;
+    Ok(())
+}
+
+//  archival is final, a replayed registration never revives an archived market
+fn apply_market_registry(tx: &Transaction, event: &MarketRegistryEvent) -> Result<()> {
+    tx.execute(
+        "INSERT INTO market_registry (market, market_id, category, page, archived)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        ON CONFLICT (market) DO UPDATE SET
+            archived = MAX(market_registry.archived, excluded.archived)",
+        params![
+            event.market.to_string(),
+            int(event.market_id)?,
+            event.category,
+            event.page,
+            event.archived,
+        ],
+    )?;
+    Ok(())
+}
+
+fn apply_register_referrer(
+    tx: &Transaction,
+    event: &RegisterReferrerEvent,
+    slot: i64,
+) -> Result<()> {
+    tx.execute(
+        "INSERT INTO referrers (authority, referrer, registered_slot) VALUES (?1, ?2, ?3)",
+        params![
+            event.authority.to_string(),
+            event.referrer.to_string(),
+            slot
+        ],
+    )?;
+    Ok(())
+}
+
+//  markets referenced before their CreateEvent was ingested get a placeholder row
+fn ensure_market(tx: &Transaction, market: &str) -> Result<()> {
+    tx.execute(
+        "INSERT OR IGNORE INTO markets (market) VALUES (?1)",
+        params![market],
+    )?;
+    Ok(())
+}
+
+fn apply_trade(
+    tx: &Transaction,
+    decoded: &DecodedEvent,
+    event: &TradeEvent,
+    slot: i64,
+) -> Result<()> {
+    let market = event.market_info.to_string();
+    let user = event.user.to_string();
+    let sol_amount = int(event.sol_amount)?;
+    let token_amount = int(event.token_amount)?;
+    let fee_lamports = int(event.fee_lamports)?;
+
+    tx.execute(
+        "INSERT INTO trades (
+            signature, event_index, slot, market, user, is_buy, is_yes,
+            sol_amount, token_amount, fee_lamports, referrer, referral_fee, timestamp
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![
+            decoded.signature,
+            decoded.event_index,
+            slot,
+            market,
+            user,
+            event.is_buy,
+            event.is_yes_no,
+            sol_amount,
+            token_amount,
+            fee_lamports,
+            event.referrer.map(|referrer| referrer.to_string()),
+            int(event.referral_fee)?,
+            event.timestamp,
+        ],
+    )?;
+
+    ensure_market(tx, &market)?;
+    tx.execute(
+        "UPDATE markets SET volume = volume + ?2 WHERE market = ?1",
+        params![market, sol_amount],
+    )?;
+    //  reserves only move forward, older transactions ingested late leave them alone
+    tx.execute(
+        "UPDATE markets SET
+            real_sol_reserves = ?2,
+            real_token_yes_reserves = ?3,
+            real_token_no_reserves = ?4,
+            updated_slot = ?5
+        WHERE market = ?1 AND updated_slot <= ?5",
+        params![
+            market,
+            int(event.real_sol_reserves)?,
+            int(event.real_token_yes_reserves)?,
+            int(event.real_token_no_reserves)?,
+            slot,
+        ],
+    )?;
+
+    let (yes_delta, no_delta) = match event.is_yes_no {
+        true => (token_amount, 0),
+        false => (0, token_amount),
+    };
+    let (sol_spent, sol_received, sign) = match event.is_buy {
+        true => (sol_amount, 0, 1),
+        false => (0, sol_amount, -1),
+    };
+    tx.execute(
+        "INSERT INTO positions (market, user) VALUES (?1, ?2)
+        ON CONFLICT (market, user) DO NOTHING",
+        params![market, user],
+    )?;
+    tx.execute(
+        "UPDATE positions SET
+            yes_amount = MAX(yes_amount + ?3 * ?7, 0),
+            no_amount = MAX(no_amount + ?4 * ?7, 0),
+            sol_spent = sol_spent + ?5,
+            sol_received = sol_received + ?6,
+            fees_paid = fees_paid + ?8
+        WHERE market = ?1 AND user = ?2",
+        params![
+            market,
+            user,
+            yes_delta,
+            no_delta,
+            sol_spent,
+            sol_received,
+            sign,
+            fee_lamports
+        ],
+    )?;
+    Ok(())
+}
+
+struct LpAction {
+    user: String,
+    market: String,
+    is_add: bool,
+    sol_amount: u64,
+    user_lp_amount: u64,
+    total_lp_amount: u64,
+    real_yes_sol_reserves: u64,
+    real_no_sol_reserves: u64,
+    timestamp: i64,
+}
+
+fn apply_lp_action(
+    tx: &Transaction,
+    decoded: &DecodedEvent,
+    action: LpAction,
+    slot: i64,
+) -> Result<()> {
+    tx.execute(
+        "INSERT INTO lp_actions (
+            signature, event_index, slot, market, user, is_add, sol_amount,
+            user_lp_amount, total_lp_amount, timestamp
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            decoded.signature,
+            decoded.event_index,
+            slot,
+            action.market,
+            action.user,
+            action.is_add,
+            int(action.sol_amount)?,
+            int(action.user_lp_amount)?,
+            int(action.total_lp_amount)?,
+            action.timestamp,
+        ],
+    )?;
+
+    ensure_market(tx, &action.market)?;
+    tx.execute(
+        "UPDATE markets SET
+            total_lp_amount = ?2,
+            real_yes_sol_reserves = ?3,
+            real_no_sol_reserves = ?4,
+            real_sol_reserves = ?3 + ?4,
+            updated_slot = ?5
+        WHERE market = ?1 AND updated_slot <= ?5",
+        params![
+            action.market,
+            int(action.total_lp_amount)?,
+            int(action.real_yes_sol_reserves)?,
+            int(action.real_no_sol_reserves)?,
+            slot,
+        ],
+    )?;
+    Ok(())
+}
+
+//  leftover SOL went to the team wallet, the market account is gone
+fn apply_close_market(tx: &Transaction, event: &CloseMarketEvent, slot: i64) -> Result<()> {
+    let market = event.market.to_string();
+    ensure_market(tx, &market)?;
+    tx.execute(
+        "UPDATE markets SET
+            real_yes_sol_reserves = 0,
+            real_no_sol_reserves = 0,
+            real_sol_reserves = 0,
+            closed_slot = ?2,
+            updated_slot = MAX(updated_slot, ?2)
+        WHERE market = ?1",
+        params![market, slot],
+    )?;
+    Ok(())
+}
+
+//  the winner is known but payouts have not started
+fn apply_resolution_proposed(tx: &Transaction, event: &ResolutionProposedEvent) -> Result<()> {
+    let market = event.market.to_string();
+    ensure_market(tx, &market)?;
+    tx.execute(
+        "UPDATE markets SET winning_token_type = ?2 WHERE market = ?1",
+        params![market, event.winning_token_type],
+    )?;
+    Ok(())
+}
+
+fn apply_market_voided(tx: &Transaction, event: &MarketVoidedEvent) -> Result<()> {
+    let market = event.market.to_string();
+    ensure_market(tx, &market)?;
+    tx.execute(
+        "UPDATE markets SET is_completed = 1, is_voided = 1, winning_token_type = NULL
+        WHERE market = ?1",
+        params![market],
+    )?;
+    Ok(())
+}
+
+fn apply_resolution(tx: &Transaction, event: &ResolutionEvent, slot: i64) -> Result<()> {
+    let market = event.market.to_string();
+    ensure_market(tx, &market)?;
+    tx.execute(
+        "UPDATE markets SET is_completed = 1, winning_token_type = ?2 WHERE market = ?1",
+        params![market, event.winning_token_type],
+    )?;
+    tx.execute(
+        "UPDATE markets SET
+            total_lp_amount = ?2,
+            real_yes_sol_reserves = ?3,
+            real_no_sol_reserves = ?4,
+            real_sol_reserves = ?3 + ?4,
+            updated_slot = ?5
+        WHERE market = ?1 AND updated_slot <= ?5",
+        params![
+            market,
+            int(event.total_lp_amount)?,
+            int(event.real_yes_sol_reserves)?,
+            int(event.real_no_sol_reserves)?,
+            slot,
+        ],
+    )?;
+    Ok(())
+}
+
+//  a payout or a refund, the tokens leave the position for SOL
+struct Redemption {
+    market: String,
+    user: String,
+    yes_amount: u64,
+    no_amount: u64,
+    sol_amount: u64,
+}
+
+fn apply_redemption(tx: &Transaction, redemption: Redemption) -> Result<()> {
+    tx.execute(
+        "INSERT INTO positions (market, user) VALUES (?1, ?2)
+        ON CONFLICT (market, user) DO NOTHING",
+        params![redemption.market, redemption.user],
+    )?;
+    tx.execute(
+        "UPDATE positions SET
+            yes_amount = MAX(yes_amount - ?3, 0),
+            no_amount = MAX(no_amount - ?4, 0),
+            sol_received = sol_received + ?5
+        WHERE market = ?1 AND user = ?2",
+        params![
+            redemption.market,
+            redemption.user,
+            int(redemption.yes_amount)?,
+            int(redemption.no_amount)?,
+            int(redemption.sol_amount)?,
+        ],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,114 @@
+//! Decoding and ingestion of `getTransaction` results recorded in `fixtures/`.
+
+use prediction_market_indexer::*;
+use serde_json::Value;
+
+//  a v0 transaction emitting a create and two trades through a lookup table, then a failed one
+const FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/transactions.json"
+);
+
+const MARKET: &str = "8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR";
+const ALICE: &str = "US517G5965aydkZ46HS38QLi7UQiSojurfbQfKCELFx";
+const BOB: &str = "YMN9Qj5jPNp7j14VPcML1B6xGgcPWVZUGLFU3Mnyfaf";
+
+fn transactions() -> Vec<Value> {
+    read_transactions(FIXTURE).unwrap()
+}
+
+fn decode_all() -> Vec<DecodedEvent> {
+    transactions()
+        .iter()
+        .flat_map(|tx| decode_transaction(tx, &prediction_market::ID).unwrap())
+        .collect()
+}
+
+fn count(store: &Store, table: &str) -> i64 {
+    store
+        .connection()
+        .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+}
+
+#[test]
+fn failed_transactions_emit_nothing() {
+    let transactions = transactions();
+    assert_eq!(transactions.len(), 2);
+    assert!(!transactions[1]["meta"]["err"].is_null());
+
+    //  its inner instructions still hold a trade, rolled back with the rest
+    let events = decode_transaction(&transactions[1], &prediction_market::ID).unwrap();
+    assert!(events.is_empty());
+}
+
+#[test]
+fn program_is_resolved_through_loaded_addresses() {
+    let events = decode_transaction(&transactions()[0], &prediction_market::ID).unwrap();
+
+    //  the program sits in the lookup table, the same trade from another program is skipped
+    let kinds: Vec<_> = events.iter().map(|event| event.event.kind()).collect();
+    assert_eq!(kinds, ["create", "trade", "trade"]);
+    assert!(events.iter().all(|event| event.slot == 287000123));
+    assert!(events
+        .iter()
+        .all(|event| event.block_time == Some(1760000000)));
+
+    let ProgramEvent::Create(create) = &events[0].event else {
+        panic!("expected a create event");
+    };
+    assert_eq!(create.market.to_string(), MARKET);
+    let users: Vec<_> = events[1..]
+        .iter()
+        .map(|event| match &event.event {
+            ProgramEvent::Trade(trade) => (trade.user.to_string(), trade.sol_amount),
+            _ => panic!("expected a trade event"),
+        })
+        .collect();
+    assert_eq!(
+        users,
+        [
+            (ALICE.to_string(), 1_000_000_000),
+            (BOB.to_string(), 2_000_000_000)
+        ]
+    );
+}
+
+#[test]
+fn event_index_is_stable_across_decodes() {
+    let first = decode_all();
+    let second = decode_all();
+
+    //  numbered by position among the program's events, skipping every other instruction
+    let indexes: Vec<_> = first.iter().map(|event| event.event_index).collect();
+    assert_eq!(indexes, [0, 1, 2]);
+    let keys = |events: &[DecodedEvent]| {
+        events
+            .iter()
+            .map(|event| (event.signature.clone(), event.event_index))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(keys(&first), keys(&second));
+}
+
+#[test]
+fn ingesting_a_transaction_twice_changes_nothing() {
+    let mut store = Store::open_in_memory().unwrap();
+    assert_eq!(store.ingest(&decode_all()).unwrap(), 3);
+    assert_eq!(store.ingest(&decode_all()).unwrap(), 0);
+
+    assert_eq!(count(&store, "processed_events"), 3);
+    assert_eq!(count(&store, "markets"), 1);
+    assert_eq!(count(&store, "trades"), 2);
+    let volume: i64 = store
+        .connection()
+        .query_row(
+            "SELECT volume FROM markets WHERE market = ?1",
+            [MARKET],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(volume, 3_000_000_000);
+}
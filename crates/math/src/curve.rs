@@ -0,0 +1,217 @@
+use crate::decimal::{mul_div, Decimal, Rounding, BPS_DENOMINATOR, DECIMAL_SCALE};
+
+//  token_type: 0 = NO token, 1 = YES token
+pub const TOKEN_TYPE_NO: u8 = 0;
+pub const TOKEN_TYPE_YES: u8 = 1;
+
+//  direction: 0 = buy, 1 = sell
+pub const DIRECTION_BUY: u8 = 0;
+pub const DIRECTION_SELL: u8 = 1;
+
+//  pool balances of both sides of a market
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Reserves {
+    pub yes_token: u64,
+    pub yes_sol: u64,
+    pub no_token: u64,
+    pub no_sol: u64,
+}
+
+impl Reserves {
+    //  (token reserves, sol reserves) of one side of the market
+    pub fn side(&self, token_type: u8) -> (u64, u64) {
+        if token_type == TOKEN_TYPE_YES {
+            (self.yes_token, self.yes_sol)
+        } else {
+            (self.no_token, self.no_sol)
+        }
+    }
+
+    fn with_side(&self, token_type: u8, token: u64, sol: u64) -> Self {
+        let mut next = *self;
+        if token_type == TOKEN_TYPE_YES {
+            (next.yes_token, next.yes_sol) = (token, sol);
+        } else {
+            (next.no_token, next.no_sol) = (token, sol);
+        }
+        next
+    }
+
+    pub fn total_sol(&self) -> Option<u64> {
+        self.yes_sol.checked_add(self.no_sol)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SellResult {
+    pub token_amount: u64,
+    pub change_amount: u64,
+    pub current_yes_reserves: u64,
+    pub current_no_reserves: u64,
+    pub new_yes_reserves: u64,
+    pub new_no_reserves: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuyResult {
+    pub token_amount: u64,
+    pub change_amount: u64,
+    pub current_yes_reserves: u64,
+    pub current_no_reserves: u64,
+    pub new_yes_reserves: u64,
+    pub new_no_reserves: u64,
+}
+
+impl BuyResult {
+    //  reserves left by the curve, fees never enter them
+    pub fn reserves_after(&self, before: &Reserves, token_type: u8) -> Option<Reserves> {
+        let (token, sol) = before.side(token_type);
+        Some(before.with_side(
+            token_type,
+            token.checked_sub(self.token_amount)?,
+            sol.checked_add(self.change_amount)?,
+        ))
+    }
+}
+
+impl SellResult {
+    //  reserves left by the curve, fees never enter them
+    pub fn reserves_after(&self, before: &Reserves, token_type: u8) -> Option<Reserves> {
+        let (token, sol) = before.side(token_type);
+        Some(before.with_side(
+            token_type,
+            token.checked_add(self.token_amount)?,
+            sol.checked_sub(self.change_amount)?,
+        ))
+    }
+}
+
+//  move of the traded side's marginal price (sol per token) in bps, rounds up
+pub fn price_impact_bps(before: &Reserves, after: &Reserves, token_type: u8) -> Option<u64> {
+    let (token_before, sol_before) = before.side(token_type);
+    let (token_after, sol_after) = after.side(token_type);
+
+    //  price_after / price_before = sol_after * token_before / (sol_before * token_after)
+    let scaled_after = (sol_after as u128).checked_mul(token_before as u128)?;
+    let scaled_before = (sol_before as u128).checked_mul(token_after as u128)?;
+    mul_div(
+        scaled_after.abs_diff(scaled_before),
+        BPS_DENOMINATOR as u128,
+        scaled_before,
+        Rounding::Up,
+    )?
+    .try_into()
+    .ok()
+}
+
+//  probability each outcome is priced at, the sides' marginal prices (sol per token)
+//  normalized so the two sum to one
+pub fn implied_prices(reserves: &Reserves) -> Option<(Decimal, Decimal)> {
+    //  yes = p_yes / (p_yes + p_no), cross multiplied by both token reserves
+    let yes = reserves.yes_sol as u128 * reserves.no_token as u128;
+    let no = reserves.no_sol as u128 * reserves.yes_token as u128;
+    //  keeps the sum times the scale within u128, drops bits below 2^-64 of the larger term
+    let shrink = yes.max(no) / (1 << 64) + 1;
+    let (yes, no) = (yes / shrink, no / shrink);
+
+    let yes = Decimal::from_raw(mul_div(
+        yes,
+        DECIMAL_SCALE,
+        yes.checked_add(no)?,
+        Rounding::Down,
+    )?);
+    Some((yes, Decimal::ONE.checked_sub(yes)?))
+}
+
+//  tokens received for `change_amount` lamports, constant product on the traded side
+pub fn get_tokens_for_buy_sol(
+    reserves: &Reserves,
+    change_amount: u64,
+    token_type: u8,
+) -> Option<BuyResult> {
+    if change_amount == 0 {
+        return None;
+    }
+
+    let current_yes_reserves = reserves.yes_token;
+    let current_no_reserves = reserves.no_token;
+    let (token_reserves, sol_reserves) = reserves.side(token_type);
+
+    //  output rounds down
+    let token_amount: u64 = mul_div(
+        token_reserves as u128,
+        change_amount as u128,
+        (sol_reserves as u128).checked_add(change_amount as u128)?,
+        Rounding::Down,
+    )?
+    .try_into()
+    .ok()?;
+    if token_amount == 0 {
+        return None;
+    }
+
+    let (new_yes_reserves, new_no_reserves) = if token_type == TOKEN_TYPE_YES {
+        (
+            current_yes_reserves.checked_sub(token_amount)?,
+            current_no_reserves,
+        )
+    } else {
+        (
+            current_yes_reserves,
+            current_no_reserves.checked_sub(token_amount)?,
+        )
+    };
+
+    Some(BuyResult {
+        token_amount,
+        change_amount,
+        current_yes_reserves,
+        current_no_reserves,
+        new_yes_reserves,
+        new_no_reserves,
+    })
+}
+
+//  lamports received for `change_amount` tokens, constant product on the traded side
+pub fn get_tokens_for_sell_sol(
+    reserves: &Reserves,
+    change_amount: u64,
+    token_type: u8,
+) -> Option<SellResult> {
+    if change_amount == 0 {
+        return None;
+    }
+
+    let current_yes_reserves = reserves.yes_token;
+    let current_no_reserves = reserves.no_token;
+    let (token_reserves, sol_reserves) = reserves.side(token_type);
+
+    //  output rounds down
+    let new_token_reserves = token_reserves.checked_add(change_amount)?;
+    let sol_amount: u64 = mul_div(
+        sol_reserves as u128,
+        change_amount as u128,
+        new_token_reserves as u128,
+        Rounding::Down,
+    )?
+    .try_into()
+    .ok()?;
+    if sol_amount == 0 {
+        return None;
+    }
+
+    let (new_yes_reserves, new_no_reserves) = if token_type == TOKEN_TYPE_YES {
+        (new_token_reserves, current_no_reserves)
+    } else {
+        (current_yes_reserves, new_token_reserves)
+    };
+
+    Some(SellResult {
+        token_amount: change_amount,
+        change_amount: sol_amount,
+        current_yes_reserves,
+        current_no_reserves,
+        new_yes_reserves,
+        new_no_reserves,
+    })
+}
@@ -0,0 +1,102 @@
+//  scale of `Decimal`, 1e9 matches lamports per SOL
+pub const DECIMAL_SCALE: u128 = 1_000_000_000;
+
+//  basis points denominator used by every fee
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Direction to round the result of a fixed-point operation.
+///
+/// Amounts paid out by the pool round `Down`, amounts charged to the user round `Up`,
+/// so rounding always favors the pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// Unsigned fixed-point decimal backed by a `u128` scaled by `DECIMAL_SCALE`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Decimal {
+    pub raw: u128,
+}
+
+impl Decimal {
+    pub const ZERO: Self = Self { raw: 0 };
+    pub const ONE: Self = Self { raw: DECIMAL_SCALE };
+
+    pub const fn from_raw(raw: u128) -> Self {
+        Self { raw }
+    }
+
+    pub fn from_integer(value: u64) -> Self {
+        Self {
+            raw: value as u128 * DECIMAL_SCALE,
+        }
+    }
+
+    //  1 bps = 0.0001
+    pub fn from_bps(bps: u64) -> Self {
+        Self {
+            raw: bps as u128 * DECIMAL_SCALE / BPS_DENOMINATOR as u128,
+        }
+    }
+
+    pub fn from_ratio(numerator: u64, denominator: u64, rounding: Rounding) -> Option<Self> {
+        mul_div(
+            numerator as u128,
+            DECIMAL_SCALE,
+            denominator as u128,
+            rounding,
+        )
+        .map(Self::from_raw)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.raw.checked_add(other.raw).map(Self::from_raw)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.raw.checked_sub(other.raw).map(Self::from_raw)
+    }
+
+    pub fn checked_mul(self, other: Self, rounding: Rounding) -> Option<Self> {
+        mul_div(self.raw, other.raw, DECIMAL_SCALE, rounding).map(Self::from_raw)
+    }
+
+    pub fn checked_div(self, other: Self, rounding: Rounding) -> Option<Self> {
+        mul_div(self.raw, DECIMAL_SCALE, other.raw, rounding).map(Self::from_raw)
+    }
+
+    //  apply this decimal as a rate to an integer amount, e.g. a fee to lamports
+    pub fn mul_u64(self, value: u64, rounding: Rounding) -> Option<u64> {
+        mul_div(value as u128, self.raw, DECIMAL_SCALE, rounding)?
+            .try_into()
+            .ok()
+    }
+
+    pub fn to_u64(self, rounding: Rounding) -> Option<u64> {
+        div_round(self.raw, DECIMAL_SCALE, rounding)?
+            .try_into()
+            .ok()
+    }
+}
+
+pub fn div_round(numerator: u128, denominator: u128, rounding: Rounding) -> Option<u128> {
+    let quotient = numerator.checked_div(denominator)?;
+    let remainder = numerator.checked_rem(denominator)?;
+    match rounding {
+        Rounding::Down => Some(quotient),
+        Rounding::Up if remainder != 0 => quotient.checked_add(1),
+        Rounding::Up => Some(quotient),
+    }
+}
+
+//  a * b / denominator without intermediate truncation
+pub fn mul_div(a: u128, b: u128, denominator: u128, rounding: Rounding) -> Option<u128> {
+    div_round(a.checked_mul(b)?, denominator, rounding)
+}
+
+//  `bps` basis points of `value`
+pub fn bps_of(bps: u64, value: u64, rounding: Rounding) -> Option<u64> {
+    Decimal::from_bps(bps).mul_u64(value, rounding)
+}
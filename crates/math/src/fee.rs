@@ -0,0 +1,87 @@
+use crate::curve::{
+    get_tokens_for_buy_sol, get_tokens_for_sell_sol, BuyResult, Reserves, SellResult,
+};
+use crate::decimal::{bps_of, Rounding};
+
+//  fee rates of one trade direction, in basis points
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeRates {
+    pub platform_bps: u64,
+    pub lp_bps: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    //  paid to the team wallet
+    pub platform_fee: u64,
+    //  kept in the pool on the traded side
+    pub lp_fee: u64,
+}
+
+impl FeeBreakdown {
+    //  fees charged on `amount` lamports, rounding up
+    pub fn charged_on(amount: u64, rates: FeeRates) -> Option<Self> {
+        Some(Self {
+            platform_fee: bps_of(rates.platform_bps, amount, Rounding::Up)?,
+            lp_fee: bps_of(rates.lp_bps, amount, Rounding::Up)?,
+        })
+    }
+
+    pub fn total(&self) -> Option<u64> {
+        self.platform_fee.checked_add(self.lp_fee)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuyQuote {
+    //  lamports paid by the user, fees included
+    pub amount_in: u64,
+    pub fees: FeeBreakdown,
+    //  curve result for the lamports left after fees
+    pub result: BuyResult,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SellQuote {
+    //  lamports received by the user, fees deducted
+    pub amount_out: u64,
+    pub fees: FeeBreakdown,
+    //  curve result before fees
+    pub result: SellResult,
+}
+
+//  buy with `amount` lamports, fees are taken from the SOL paid in
+pub fn quote_buy(
+    reserves: &Reserves,
+    amount: u64,
+    token_type: u8,
+    rates: FeeRates,
+) -> Option<BuyQuote> {
+    let fees = FeeBreakdown::charged_on(amount, rates)?;
+    let sol_amount = amount.checked_sub(fees.total()?)?;
+    let result = get_tokens_for_buy_sol(reserves, sol_amount, token_type)?;
+
+    Some(BuyQuote {
+        amount_in: amount,
+        fees,
+        result,
+    })
+}
+
+//  sell `token_amount` tokens, fees are taken from the SOL paid out
+pub fn quote_sell(
+    reserves: &Reserves,
+    token_amount: u64,
+    token_type: u8,
+    rates: FeeRates,
+) -> Option<SellQuote> {
+    let result = get_tokens_for_sell_sol(reserves, token_amount, token_type)?;
+    let fees = FeeBreakdown::charged_on(result.change_amount, rates)?;
+    let amount_out = result.change_amount.checked_sub(fees.total()?)?;
+
+    Some(SellQuote {
+        amount_out,
+        fees,
+        result,
+    })
+}
@@ -0,0 +1,16 @@
+//! Curve, fee and payout math of the prediction market.
+//!
+//! The on-chain program and off-chain clients both depend on this crate so a
+//! quote computed off-chain matches what `swap` executes.
+
+#![no_std]
+
+pub mod curve;
+pub mod decimal;
+pub mod fee;
+pub mod payout;
+
+pub use curve::*;
+pub use decimal::*;
+pub use fee::*;
+pub use payout::*;
@@ -0,0 +1,32 @@
+use crate::decimal::{mul_div, Decimal, Rounding};
+
+//  SOL in the pool that is not owed back to liquidity providers
+pub fn prize_pool(total_sol_reserves: u64, total_lp_amount: u64) -> Option<u64> {
+    total_sol_reserves.checked_sub(total_lp_amount)
+}
+
+//  SOL paid for `token_amount` winning tokens, pro rata of the prize pool, rounding down
+pub fn get_payout(token_amount: u64, prize_pool: u64, circulating: u64) -> Option<u64> {
+    if token_amount == 0 || circulating == 0 {
+        return Some(0);
+    }
+    mul_div(
+        token_amount as u128,
+        prize_pool as u128,
+        circulating as u128,
+        Rounding::Down,
+    )?
+    .try_into()
+    .ok()
+}
+
+//  SOL paid for a `stake` on every leg winning at fair odds, the product of the leg prices,
+//  rounding the probability up and the payout down
+pub fn parlay_payout(stake: u64, prices: &[Decimal]) -> Option<u64> {
+    let probability = prices.iter().try_fold(Decimal::ONE, |probability, price| {
+        probability.checked_mul(*price, Rounding::Up)
+    })?;
+    Decimal::from_integer(stake)
+        .checked_div(probability, Rounding::Down)?
+        .to_u64(Rounding::Down)
+}
@@ -0,0 +1,89 @@
+//! Rounding and overflow of the fixed-point helpers every fee and payout goes through.
+
+use prediction_market_math::*;
+
+#[test]
+fn mul_div_rounds_in_the_requested_direction() {
+    assert_eq!(mul_div(10, 1, 3, Rounding::Down), Some(3));
+    assert_eq!(mul_div(10, 1, 3, Rounding::Up), Some(4));
+
+    //  exact quotients are the same either way
+    assert_eq!(mul_div(9, 1, 3, Rounding::Down), Some(3));
+    assert_eq!(mul_div(9, 1, 3, Rounding::Up), Some(3));
+
+    //  the product is not truncated before the division
+    assert_eq!(
+        mul_div(
+            u64::MAX as u128,
+            DECIMAL_SCALE,
+            DECIMAL_SCALE,
+            Rounding::Down
+        ),
+        Some(u64::MAX as u128)
+    );
+}
+
+#[test]
+fn mul_div_fails_instead_of_wrapping() {
+    assert_eq!(mul_div(u128::MAX, 2, 2, Rounding::Down), None);
+    assert_eq!(mul_div(1, 1, 0, Rounding::Down), None);
+    assert_eq!(div_round(u128::MAX, 1, Rounding::Up), Some(u128::MAX));
+}
+
+#[test]
+fn decimal_operations_round_in_the_requested_direction() {
+    let third = Decimal::from_ratio(1, 3, Rounding::Down).unwrap();
+    assert_eq!(third.raw, 333_333_333);
+    assert_eq!(
+        Decimal::from_ratio(1, 3, Rounding::Up).unwrap().raw,
+        333_333_334
+    );
+
+    let two = Decimal::from_integer(2);
+    assert_eq!(
+        Decimal::ONE.checked_div(Decimal::from_integer(3), Rounding::Down),
+        Some(third)
+    );
+    assert_eq!(
+        third.checked_mul(third, Rounding::Down).unwrap().raw,
+        111_111_110
+    );
+    assert_eq!(
+        third.checked_mul(third, Rounding::Up).unwrap().raw,
+        111_111_111
+    );
+    assert_eq!(two.checked_div(Decimal::ZERO, Rounding::Up), None);
+
+    assert_eq!(third.mul_u64(10, Rounding::Down), Some(3));
+    assert_eq!(third.mul_u64(10, Rounding::Up), Some(4));
+    assert_eq!(third.to_u64(Rounding::Down), Some(0));
+    assert_eq!(third.to_u64(Rounding::Up), Some(1));
+
+    assert_eq!(Decimal::ZERO.checked_sub(Decimal::ONE), None);
+    assert_eq!(Decimal::from_raw(u128::MAX).checked_add(Decimal::ONE), None);
+}
+
+#[test]
+fn bps_of_rounds_fees_up_and_payouts_down() {
+    assert_eq!(Decimal::from_bps(BPS_DENOMINATOR), Decimal::ONE);
+    assert_eq!(bps_of(100, 1_000_000_000, Rounding::Down), Some(10_000_000));
+
+    //  1% of 199 lamports is 1.99
+    assert_eq!(bps_of(100, 199, Rounding::Down), Some(1));
+    assert_eq!(bps_of(100, 199, Rounding::Up), Some(2));
+    assert_eq!(bps_of(0, 199, Rounding::Up), Some(0));
+}
+
+#[test]
+fn bps_of_fails_instead_of_overflowing() {
+    assert_eq!(
+        bps_of(BPS_DENOMINATOR, u64::MAX, Rounding::Up),
+        Some(u64::MAX)
+    );
+
+    //  the result does not fit a u64
+    assert_eq!(bps_of(BPS_DENOMINATOR + 1, u64::MAX, Rounding::Down), None);
+
+    //  the intermediate product does not fit a u128
+    assert_eq!(bps_of(u64::MAX, u64::MAX, Rounding::Down), None);
+}
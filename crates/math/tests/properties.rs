@@ -0,0 +1,186 @@
+//! Properties of the curve, fee and payout math. Failing inputs are shrunk by
+//! proptest and persisted next to this file so they rerun first.
+
+use prediction_market_math::*;
+use proptest::prelude::*;
+
+const SUPPLY: u64 = 1_000_000_000_000_000;
+const MAX_LAMPORTS: u64 = 1_000_000 * 1_000_000_000;
+
+fn reserves() -> impl Strategy<Value = Reserves> {
+    (1..=SUPPLY, 1..=MAX_LAMPORTS, 1..=SUPPLY, 1..=MAX_LAMPORTS).prop_map(
+        |(yes_token, yes_sol, no_token, no_sol)| Reserves {
+            yes_token,
+            yes_sol,
+            no_token,
+            no_sol,
+        },
+    )
+}
+
+fn token_type() -> impl Strategy<Value = u8> {
+    prop_oneof![Just(TOKEN_TYPE_NO), Just(TOKEN_TYPE_YES)]
+}
+
+fn fee_rates() -> impl Strategy<Value = FeeRates> {
+    (0..=1_000u64, 0..=1_000u64).prop_map(|(platform_bps, lp_bps)| FeeRates {
+        platform_bps,
+        lp_bps,
+    })
+}
+
+//  reserves after a buy, the way `apply_buy` updates the market, fees are held apart
+fn after_buy(reserves: &Reserves, quote: &BuyQuote, token_type: u8) -> Reserves {
+    let added = quote.result.change_amount;
+    let mut next = *reserves;
+    next.yes_token = quote.result.new_yes_reserves;
+    next.no_token = quote.result.new_no_reserves;
+    if token_type == TOKEN_TYPE_YES {
+        next.yes_sol += added;
+    } else {
+        next.no_sol += added;
+    }
+    next
+}
+
+fn side_product(reserves: &Reserves, token_type: u8) -> u128 {
+    let (token, sol) = reserves.side(token_type);
+    token as u128 * sol as u128
+}
+
+//  `bps` of `amount`, exact, scaled by the bps denominator
+fn exact_fee(bps: u64, amount: u64) -> u128 {
+    bps as u128 * amount as u128
+}
+
+proptest! {
+    #[test]
+    fn buy_then_sell_never_returns_more_than_paid(
+        reserves in reserves(),
+        amount in 1..=MAX_LAMPORTS,
+        token_type in token_type(),
+        buy_rates in fee_rates(),
+        sell_rates in fee_rates(),
+    ) {
+        let Some(buy) = quote_buy(&reserves, amount, token_type, buy_rates) else {
+            return Ok(());
+        };
+        let next = after_buy(&reserves, &buy, token_type);
+        if let Some(sell) = quote_sell(&next, buy.result.token_amount, token_type, sell_rates) {
+            prop_assert!(sell.amount_out <= amount, "paid {}, got back {}", amount, sell.amount_out);
+        }
+    }
+
+    #[test]
+    fn buy_never_underflows_reserves(
+        reserves in reserves(),
+        amount in 1..=MAX_LAMPORTS,
+        token_type in token_type(),
+        rates in fee_rates(),
+    ) {
+        if let Some(quote) = quote_buy(&reserves, amount, token_type, rates) {
+            let (token_reserves, _) = reserves.side(token_type);
+            prop_assert!(quote.result.token_amount < token_reserves);
+            prop_assert_eq!(quote.result.change_amount + quote.fees.total().unwrap(), amount);
+        }
+    }
+
+    #[test]
+    fn sell_never_pays_out_more_than_the_side_holds(
+        reserves in reserves(),
+        token_amount in 1..=SUPPLY,
+        token_type in token_type(),
+        rates in fee_rates(),
+    ) {
+        if let Some(quote) = quote_sell(&reserves, token_amount, token_type, rates) {
+            let (_, sol_reserves) = reserves.side(token_type);
+            prop_assert!(quote.result.change_amount < sol_reserves);
+            prop_assert!(quote.amount_out <= quote.result.change_amount);
+        }
+    }
+
+    #[test]
+    fn trades_never_shrink_the_traded_side_product(
+        reserves in reserves(),
+        amount in 1..=MAX_LAMPORTS,
+        token_amount in 1..=SUPPLY,
+        token_type in token_type(),
+    ) {
+        let before = side_product(&reserves, token_type);
+
+        if let Some(buy) = quote_buy(&reserves, amount, token_type, FeeRates::default()) {
+            let next = after_buy(&reserves, &buy, token_type);
+            prop_assert!(side_product(&next, token_type) >= before);
+        }
+
+        if let Some(sell) = get_tokens_for_sell_sol(&reserves, token_amount, token_type) {
+            let (token, sol) = reserves.side(token_type);
+            let after = (token + token_amount) as u128 * (sol - sell.change_amount) as u128;
+            prop_assert!(after >= before);
+        }
+    }
+
+    #[test]
+    fn larger_buys_never_move_the_price_less(
+        reserves in reserves(),
+        amount in 1..=MAX_LAMPORTS / 2,
+        extra in 0..=MAX_LAMPORTS / 2,
+        token_type in token_type(),
+    ) {
+        let impact = |amount| {
+            let buy = get_tokens_for_buy_sol(&reserves, amount, token_type)?;
+            price_impact_bps(&reserves, &buy.reserves_after(&reserves, token_type)?, token_type)
+        };
+
+        if let (Some(small), Some(large)) = (impact(amount), impact(amount + extra)) {
+            prop_assert!(small <= large);
+        }
+    }
+
+    #[test]
+    fn fees_round_up_by_less_than_a_lamport(
+        amount in 0..=u64::MAX / BPS_DENOMINATOR,
+        rates in fee_rates(),
+    ) {
+        let fees = FeeBreakdown::charged_on(amount, rates).unwrap();
+        let denominator = BPS_DENOMINATOR as u128;
+        for (fee, bps) in [(fees.platform_fee, rates.platform_bps), (fees.lp_fee, rates.lp_bps)] {
+            let charged = fee as u128 * denominator;
+            prop_assert!(charged >= exact_fee(bps, amount));
+            prop_assert!(charged < exact_fee(bps, amount) + denominator);
+        }
+    }
+
+    #[test]
+    fn payouts_never_exceed_the_prize_pool(
+        prize_pool in 0..=MAX_LAMPORTS,
+        holdings in prop::collection::vec(1..=SUPPLY / 16, 1..16),
+    ) {
+        let mut remaining_pool = prize_pool;
+        let mut remaining_circulating: u64 = holdings.iter().sum();
+        //  claims are paid one by one, each leaving circulation
+        for amount in holdings {
+            let payout = get_payout(amount, remaining_pool, remaining_circulating).unwrap();
+            prop_assert!(payout <= remaining_pool);
+            remaining_pool -= payout;
+            remaining_circulating -= amount;
+        }
+    }
+
+    #[test]
+    fn parlay_legs_never_lower_the_payout(
+        stake in 1..=MAX_LAMPORTS,
+        prices in prop::collection::vec(1..=DECIMAL_SCALE, 1..8),
+        price in 1..=DECIMAL_SCALE,
+    ) {
+        let mut prices: Vec<Decimal> = prices.into_iter().map(Decimal::from_raw).collect();
+        //  long shots overflow a u64 payout, the program rejects those parlays
+        let payout = parlay_payout(stake, &prices);
+        prop_assume!(payout.is_some());
+        let payout = payout.unwrap();
+        prop_assert!(payout >= stake);
+        prices.push(Decimal::from_raw(price));
+        //  a longer parlay is never more likely, so it never pays less
+        prop_assert!(parlay_payout(stake, &prices).is_none_or(|longer| longer >= payout));
+    }
+}
@@ -0,0 +1,25 @@
+//! In-process test runtime for the prediction-market program.
+//!
+//! `solana-program-test` and LiteSVM cannot be built against this workspace's
+//! Solana dependency pins, and both need the SBF toolchain to meter real
+//! bytecode, so `Svm` executes native builds of the program, SPL Token, the
+//! associated token program and a Metaplex stand-in through `solana_program`'s
+//! syscall stubs. `Scenario` drives it from a small text DSL.
+//!
+//! The runtime limits a native build can observe are enforced: the
+//! transaction's compute meter, charged for invocations, PDA signers, logs,
+//! sysvars and return data at the runtime's prices, the invocation depth of
+//! five and the rule that a program may only reenter itself directly. Compute
+//! spent on bytecode and the SBF stack frame limit are not modeled, so the
+//! meter is a lower bound and new instructions still need a run against a
+//! validator before release.
+
+mod metadata;
+mod runtime;
+pub mod scenario;
+mod svm;
+mod system;
+
+pub use runtime::Processor;
+pub use scenario::Scenario;
+pub use svm::{Account, InstructionError, Svm, TransactionError, TransactionMeta};
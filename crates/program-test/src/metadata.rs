@@ -0,0 +1,160 @@
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey, Rent},
+    solana_program::{
+        entrypoint::ProgramResult, program::invoke_signed, program_error::ProgramError,
+        program_pack::Pack, system_instruction, sysvar::Sysvar,
+    },
+    AnchorDeserialize, AnchorSerialize,
+};
+use anchor_spl::metadata::{
+    mpl_token_metadata::{
+        accounts::Metadata,
+        instructions::{
+            CreateMetadataAccountV3InstructionArgs, UpdateMetadataAccountV2InstructionArgs,
+        },
+        types::{DataV2, Key, TokenStandard},
+        MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH,
+    },
+    ID,
+};
+use spl_token::state::Mint;
+
+const UPDATE_METADATA_ACCOUNT_V2: u8 = 15;
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+
+//  Metaplex allocates the largest metadata up front so updates never resize the account
+const MAX_METADATA_LEN: usize = 679;
+
+//  stand-in for Metaplex token metadata: no program binary is available to load, so this
+//  checks the same accounts and records name, symbol and uri in a Metaplex layout account
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (&discriminator, args) = data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    match discriminator {
+        CREATE_METADATA_ACCOUNT_V3 => create_metadata_account_v3(
+            program_id,
+            accounts,
+            CreateMetadataAccountV3InstructionArgs::deserialize(&mut &args[..])?,
+        ),
+        UPDATE_METADATA_ACCOUNT_V2 => update_metadata_account_v2(
+            program_id,
+            accounts,
+            UpdateMetadataAccountV2InstructionArgs::deserialize(&mut &args[..])?,
+        ),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn create_metadata_account_v3(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: CreateMetadataAccountV3InstructionArgs,
+) -> ProgramResult {
+    let [metadata, mint, mint_authority, payer, update_authority, system_program, ..] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (address, bump) = Metadata::find_pda(mint.key);
+    if metadata.key != &address {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let mint_state = Mint::unpack(&mint.try_borrow_data()?)?;
+    if !mint_authority.is_signer || mint_state.mint_authority != Some(*mint_authority.key).into() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    check_data(&args.data)?;
+
+    let data = Metadata {
+        key: Key::MetadataV1,
+        update_authority: *update_authority.key,
+        mint: *mint.key,
+        name: args.data.name,
+        symbol: args.data.symbol,
+        uri: args.data.uri,
+        seller_fee_basis_points: args.data.seller_fee_basis_points,
+        creators: args.data.creators,
+        primary_sale_happened: false,
+        is_mutable: args.is_mutable,
+        edition_nonce: None,
+        token_standard: Some(TokenStandard::Fungible),
+        collection: args.data.collection,
+        uses: args.data.uses,
+        collection_details: args.collection_details,
+        programmable_config: None,
+    }
+    .try_to_vec()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            metadata.key,
+            Rent::get()?.minimum_balance(MAX_METADATA_LEN),
+            MAX_METADATA_LEN as u64,
+            program_id,
+        ),
+        &[payer.clone(), metadata.clone(), system_program.clone()],
+        &[&[Metadata::PREFIX, ID.as_ref(), mint.key.as_ref(), &[bump]]],
+    )?;
+    metadata.try_borrow_mut_data()?[..data.len()].copy_from_slice(&data);
+    Ok(())
+}
+
+fn update_metadata_account_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: UpdateMetadataAccountV2InstructionArgs,
+) -> ProgramResult {
+    let [metadata, update_authority, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if metadata.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut state = Metadata::safe_deserialize(&metadata.try_borrow_data()?)?;
+    if !update_authority.is_signer || state.update_authority != *update_authority.key {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !state.is_mutable {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if let Some(data) = args.data {
+        check_data(&data)?;
+        state.name = data.name;
+        state.symbol = data.symbol;
+        state.uri = data.uri;
+        state.seller_fee_basis_points = data.seller_fee_basis_points;
+        state.creators = data.creators;
+        state.collection = data.collection;
+        state.uses = data.uses;
+    }
+    if let Some(new_update_authority) = args.new_update_authority {
+        state.update_authority = new_update_authority;
+    }
+    //  a primary sale cannot be undone, nor can immutable metadata become mutable again
+    if let Some(primary_sale_happened) = args.primary_sale_happened {
+        state.primary_sale_happened |= primary_sale_happened;
+    }
+    if let Some(is_mutable) = args.is_mutable {
+        state.is_mutable &= is_mutable;
+    }
+
+    let data = state.try_to_vec()?;
+    let mut account = metadata.try_borrow_mut_data()?;
+    account.fill(0);
+    account[..data.len()].copy_from_slice(&data);
+    Ok(())
+}
+
+fn check_data(data: &DataV2) -> ProgramResult {
+    if data.name.len() > MAX_NAME_LENGTH
+        || data.symbol.len() > MAX_SYMBOL_LENGTH
+        || data.uri.len() > MAX_URI_LENGTH
+    {
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(())
+}
@@ -0,0 +1,309 @@
+use std::{cell::RefCell, collections::HashMap, mem::size_of, sync::Once};
+
+use anchor_lang::{
+    event::EVENT_IX_TAG_LE,
+    prelude::{AccountInfo, Clock, Pubkey, Rent},
+    solana_program::{
+        entrypoint::{ProgramResult, SUCCESS},
+        epoch_schedule::EpochSchedule,
+        instruction::Instruction,
+        program_error::ProgramError,
+        program_stubs::{self, SyscallStubs},
+        system_program,
+    },
+};
+use anchor_spl::{associated_token, metadata, token};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::{metadata as metadata_program, system, InstructionError};
+
+pub type Processor = fn(&Pubkey, &[AccountInfo], &[u8]) -> ProgramResult;
+
+//  compute budget defaults of the 1.18 runtime, the meter is shared by the whole transaction
+const INSTRUCTION_COMPUTE_UNITS: u64 = 200_000;
+const MAX_COMPUTE_UNITS: u64 = 1_400_000;
+//  top level instruction plus four nested invocations
+const MAX_STACK_HEIGHT: usize = 5;
+
+//  syscall costs, as charged by the runtime
+const INVOKE_UNITS: u64 = 1_000;
+const SYSCALL_BASE_COST: u64 = 100;
+const CREATE_PROGRAM_ADDRESS_UNITS: u64 = 1_500;
+const SYSVAR_BASE_COST: u64 = 100;
+const CPI_BYTES_PER_UNIT: u64 = 250;
+
+//  state of the transaction being executed on this thread
+#[derive(Default)]
+struct Context {
+    programs: HashMap<Pubkey, Processor>,
+    clock: Clock,
+    rent: Rent,
+    stack: Vec<Pubkey>,
+    logs: Vec<String>,
+    events: Vec<Vec<u8>>,
+    return_data: Option<(Pubkey, Vec<u8>)>,
+    compute_units: u64,
+    consumed: u64,
+    //  a runtime limit hit inside the program, reported instead of the program's own result
+    fault: Option<InstructionError>,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Context> = RefCell::new(Context::default());
+}
+
+fn with<R>(f: impl FnOnce(&mut Context) -> R) -> R {
+    CONTEXT.with(|context| f(&mut context.borrow_mut()))
+}
+
+fn log(message: String) {
+    with(|context| context.logs.push(message));
+}
+
+//  charges the meter, the first overrun is kept as the fault of the instruction
+fn consume(units: u64) -> ProgramResult {
+    with(|context| {
+        context.consumed = context.consumed.saturating_add(units);
+        if context.consumed <= context.compute_units {
+            return Ok(());
+        }
+        context.consumed = context.compute_units;
+        context
+            .fault
+            .get_or_insert(InstructionError::ComputationalBudgetExceeded);
+        Err(ProgramError::Custom(u32::MAX))
+    })
+}
+
+fn fault(error: InstructionError) -> ProgramError {
+    with(|context| {
+        context.fault.get_or_insert(error);
+    });
+    ProgramError::Custom(u32::MAX)
+}
+
+pub fn builtins() -> Vec<(Pubkey, Processor)> {
+    vec![
+        (system_program::ID, system::process as Processor),
+        (token::ID, spl_token::processor::Processor::process),
+        (
+            associated_token::ID,
+            spl_associated_token_account::processor::process_instruction,
+        ),
+        (metadata::ID, metadata_program::process),
+        (prediction_market::ID, prediction_market),
+    ]
+}
+
+fn prediction_market(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    //  Anchor ties the slice lifetime to the account lifetime
+    let accounts: &[AccountInfo] = unsafe { std::mem::transmute(accounts) };
+    prediction_market::entry(program_id, accounts, data)
+}
+
+pub(crate) fn begin(
+    programs: HashMap<Pubkey, Processor>,
+    clock: Clock,
+    rent: Rent,
+    instruction_count: usize,
+) {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        program_stubs::set_syscall_stubs(Box::new(Stubs));
+    });
+    with(|context| {
+        *context = Context {
+            programs,
+            clock,
+            rent,
+            compute_units: (instruction_count as u64 * INSTRUCTION_COMPUTE_UNITS)
+                .min(MAX_COMPUTE_UNITS),
+            ..Context::default()
+        }
+    });
+}
+
+pub(crate) fn clear_return_data() {
+    with(|context| context.return_data = None);
+}
+
+//  drops the call stack left behind by a panicking program
+pub(crate) fn unwind() {
+    with(|context| context.stack.clear());
+}
+
+//  the runtime limit the last instruction ran into, if any
+pub(crate) fn take_fault() -> Option<InstructionError> {
+    with(|context| context.fault.take())
+}
+
+pub(crate) fn finish() -> crate::TransactionMeta {
+    with(|context| crate::TransactionMeta {
+        logs: std::mem::take(&mut context.logs),
+        events: std::mem::take(&mut context.events),
+        return_data: context.return_data.take(),
+        compute_units_consumed: context.consumed,
+    })
+}
+
+pub(crate) fn invoke(
+    processor: Processor,
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    //  a program may only be reentered by itself, directly
+    let reentered = with(|context| {
+        context.stack.contains(program_id) && context.stack.last() != Some(program_id)
+    });
+    if reentered {
+        return Err(fault(InstructionError::ReentrancyNotAllowed));
+    }
+    let depth = with(|context| {
+        context.stack.push(*program_id);
+        context.stack.len()
+    });
+    if depth > MAX_STACK_HEIGHT {
+        with(|context| context.stack.pop());
+        return Err(fault(InstructionError::CallDepth));
+    }
+    log(format!("Program {program_id} invoke [{depth}]"));
+    let consumed_before = with(|context| context.consumed);
+
+    let result = processor(program_id, accounts, data);
+
+    let consumed = with(|context| {
+        context.stack.pop();
+        context.consumed - consumed_before
+    });
+    log(format!(
+        "Program {program_id} consumed {consumed} of {} compute units",
+        with(|context| context.compute_units - consumed_before)
+    ));
+    match &result {
+        Ok(()) => log(format!("Program {program_id} success")),
+        Err(error) => log(format!("Program {program_id} failed: {error}")),
+    }
+    result
+}
+
+struct Stubs;
+
+impl Stubs {
+    fn write_sysvar<T: Clone>(var_addr: *mut u8, value: &T) -> u64 {
+        if consume(SYSVAR_BASE_COST + size_of::<T>() as u64).is_err() {
+            return u64::MAX;
+        }
+        unsafe { *(var_addr as *mut T) = value.clone() };
+        SUCCESS
+    }
+}
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        let _ = consume(SYSCALL_BASE_COST.max(message.len() as u64));
+        log(format!("Program log: {message}"));
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let bytes: u64 = fields.iter().map(|field| field.len() as u64).sum();
+        let _ = consume(SYSCALL_BASE_COST * (1 + fields.len() as u64) + bytes);
+        let fields: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+        log(format!("Program data: {}", fields.join(" ")));
+    }
+
+    fn sol_remaining_compute_units(&self) -> u64 {
+        with(|context| context.compute_units - context.consumed)
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let (caller, processor) = with(|context| {
+            (
+                context.stack.last().copied().unwrap_or_default(),
+                context.programs.get(&instruction.program_id).copied(),
+            )
+        });
+        let processor = processor.ok_or(ProgramError::IncorrectProgramId)?;
+
+        consume(
+            INVOKE_UNITS
+                + instruction.data.len() as u64 / CPI_BYTES_PER_UNIT
+                + CREATE_PROGRAM_ADDRESS_UNITS * signers_seeds.len() as u64,
+        )?;
+        let pda_signers = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+
+        let mut accounts = Vec::with_capacity(instruction.accounts.len());
+        for meta in &instruction.accounts {
+            let info = account_infos
+                .iter()
+                .find(|info| info.key == &meta.pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            if meta.is_signer && !info.is_signer && !pda_signers.contains(&meta.pubkey) {
+                log(format!("{} signer privilege escalated", meta.pubkey));
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if meta.is_writable && !info.is_writable {
+                log(format!("{} writable privilege escalated", meta.pubkey));
+                return Err(ProgramError::InvalidArgument);
+            }
+            let mut info = info.clone();
+            info.is_signer = meta.is_signer;
+            info.is_writable = meta.is_writable;
+            accounts.push(info);
+        }
+
+        if instruction.program_id == prediction_market::ID
+            && instruction.data.starts_with(&EVENT_IX_TAG_LE)
+        {
+            with(|context| {
+                context
+                    .events
+                    .push(instruction.data[EVENT_IX_TAG_LE.len()..].to_vec())
+            });
+        }
+
+        invoke(
+            processor,
+            &instruction.program_id,
+            &accounts,
+            &instruction.data,
+        )
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        Self::write_sysvar(var_addr, &with(|context| context.clock.clone()))
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        Self::write_sysvar(var_addr, &with(|context| context.rent))
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        Self::write_sysvar(var_addr, &EpochSchedule::default())
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        with(|context| context.return_data.clone())
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        let _ = consume(SYSCALL_BASE_COST + data.len() as u64 / CPI_BYTES_PER_UNIT);
+        with(|context| {
+            let program_id = context.stack.last().copied().unwrap_or_default();
+            context.return_data = Some((program_id, data.to_vec()));
+        });
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        with(|context| context.stack.len() as u64)
+    }
+}
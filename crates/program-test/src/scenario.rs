@@ -0,0 +1,1425 @@
+//! Text DSL over [`Svm`], one step per line or `;` separated:
+//!
+//! ```text
+//! configure
+//! create market end +100
+//! admin adds liquidity 10 SOL
+//! alice buys 1 SOL YES
+//! bob buys 2 SOL YES min 999999999999999 fails ReturnAmountTooSmall
+//! bob buys 2 SOL YES until +10 max_impact 5000
+//! carol registers as referrer
+//! dave buys 1 SOL NO via carol
+//! dave sends all NO to erin
+//! expect carol referrer unclaimed > 0
+//! expect dave stats volume = 1 SOL
+//! warp past ending_slot
+//! resolve YES
+//! alice redeems
+//! expect alice sol > 1000 SOL
+//! observe 100
+//! expect twap yes_price > 500000000
+//! carol quotes buying 1 SOL NO
+//! carol batch buys 1 SOL YES, sells all NO on 0 min 1 SOL
+//! admin funds parlay pool 50 SOL
+//! dave parlays 1 SOL on 0 YES, 1 NO min 3 SOL
+//! dave settles parlay 0
+//! expect parlay pool reserved = 0
+//! expect quote amount_out > 0
+//! expect compute_units < 200000
+//! expect market status = Resolved
+//! update outcome metadata https://won.json https://lost.json
+//! expect metadata YES name = agree WON
+//! expect metadata NO uri = https://lost/{mint}
+//! create market end +100 if 0 YES
+//! void conditional market
+//! expect event payout sol_amount > 0
+//! alice closes position
+//! ```
+//!
+//! Actors are created and funded on first mention. Transaction steps must
+//! succeed unless they end with `fails <AnchorError>`. Token amounts are raw
+//! units, SOL amounts take a `SOL` suffix.
+
+use std::collections::BTreeMap;
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::Instruction, native_token::LAMPORTS_PER_SOL, program_pack::Pack,
+    },
+    AccountDeserialize,
+};
+use anchor_spl::metadata::MetadataAccount;
+use anyhow::{anyhow, bail, ensure, Context as _, Result};
+use prediction_market::state::market::{
+    MarketStatus, DIRECTION_BUY, DIRECTION_SELL, TOKEN_TYPE_NO, TOKEN_TYPE_YES,
+};
+use prediction_market_client::{
+    decode_return_data, instructions, parse_amount_config, parse_fee_tier, pda, AmountConfig,
+    Category, CategoryPage, Config, CreateMarketParams, Market, MarketCondition, MarketEntry,
+    MarketKeys, MarketMetadata, MarketMetadataParams, Parlay, ParlayOutcome, ParlayPick,
+    ParlayPool, Referrer, RegistrySlot, SwapLeg, SwapQuote, TradeLimits, Twap, UserInfo, UserStats,
+};
+use prediction_market_indexer::{decode_event, ProgramEvent};
+
+use crate::{Svm, TransactionError, TransactionMeta};
+
+const ACTOR_AIRDROP: u64 = 1_000 * LAMPORTS_PER_SOL;
+
+//  defaults of `configure`, 1B tokens with 6 decimals
+const DEFAULT_TOKEN_SUPPLY: u64 = 1_000_000_000_000_000;
+const DEFAULT_TOKEN_DECIMALS: u8 = 6;
+const DEFAULT_PLATFORM_FEE_BPS: u64 = 100;
+const DEFAULT_LP_FEE_BPS: u64 = 20;
+const DEFAULT_MIN_SOL_LIQUIDITY: u64 = 5 * LAMPORTS_PER_SOL;
+const UNBOUNDED: AmountConfig<u64> = AmountConfig::Range {
+    min: None,
+    max: None,
+};
+
+//  arguments shared by `swap` and `quote_swap`
+struct SwapArgs {
+    amount: u64,
+    token_type: u8,
+    minimum_receive_amount: u64,
+    valid_until_slot: Option<u64>,
+    max_price_impact_bps: Option<u64>,
+    referrer: Option<Pubkey>,
+}
+
+pub struct Scenario {
+    pub svm: Svm,
+    authority: Pubkey,
+    actors: BTreeMap<String, Pubkey>,
+    market: Option<MarketKeys>,
+    //  events of the last successful transaction
+    events: Vec<ProgramEvent>,
+    //  return data of the last successful transaction
+    return_data: Option<Vec<u8>>,
+    //  compute units the last successful transaction consumed
+    compute_units: u64,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        let mut scenario = Self {
+            svm: Svm::new(),
+            authority: Pubkey::default(),
+            actors: BTreeMap::new(),
+            market: None,
+            events: vec![],
+            return_data: None,
+            compute_units: 0,
+        };
+        scenario.authority = scenario.actor("admin");
+        scenario
+    }
+
+    //  address of a named actor, funded on first use
+    pub fn actor(&mut self, name: &str) -> Pubkey {
+        if let Some(address) = self.actors.get(name) {
+            return *address;
+        }
+        let address = Pubkey::new_unique();
+        self.svm.airdrop(&address, ACTOR_AIRDROP);
+        self.actors.insert(name.to_string(), address);
+        address
+    }
+
+    pub fn market_keys(&self) -> Result<MarketKeys> {
+        self.market
+            .ok_or_else(|| anyhow!("no market has been created"))
+    }
+
+    pub fn events(&self) -> &[ProgramEvent] {
+        &self.events
+    }
+
+    fn decode<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        let account = self
+            .svm
+            .account(address)
+            .ok_or_else(|| anyhow!("account {address} does not exist"))?;
+        Ok(T::try_deserialize(&mut account.data.as_slice())?)
+    }
+
+    pub fn config(&self) -> Result<Config> {
+        self.decode(&pda::config().0)
+    }
+
+    pub fn market(&self) -> Result<Market> {
+        self.decode(&self.market_keys()?.market)
+    }
+
+    pub fn market_metadata(&self) -> Result<MarketMetadata> {
+        self.decode(&pda::market_metadata(&self.market_keys()?.market).0)
+    }
+
+    pub fn market_entry(&self, market_id: u64) -> Result<MarketEntry> {
+        self.decode(&pda::market_entry(market_id).0)
+    }
+
+    pub fn category(&self, name: &str) -> Result<Category> {
+        self.decode(&pda::category(name).0)
+    }
+
+    //  open markets of a category, page by page
+    pub fn category_markets(&self, name: &str) -> Result<Vec<Pubkey>> {
+        let category = self.category(name)?;
+        let mut markets = vec![];
+        for index in 0..category.page_count {
+            let page: CategoryPage = self.decode(&pda::category_page(name, index).0)?;
+            markets.extend(page.markets);
+        }
+        Ok(markets)
+    }
+
+    pub fn position(&self, user: &Pubkey) -> Result<UserInfo> {
+        self.decode(&pda::user_info(user, &self.market_keys()?.market).0)
+    }
+
+    pub fn referrer(&self, authority: &Pubkey) -> Result<Referrer> {
+        self.decode(&pda::referrer(authority).0)
+    }
+
+    pub fn user_stats(&self, user: &Pubkey) -> Result<UserStats> {
+        self.decode(&pda::user_stats(user).0)
+    }
+
+    pub fn parlay_pool(&self) -> Result<ParlayPool> {
+        self.decode(&pda::parlay_pool().0)
+    }
+
+    pub fn parlay(&self, parlay_id: u64) -> Result<Parlay> {
+        self.decode(&pda::parlay(parlay_id).0)
+    }
+
+    //  Metaplex metadata of the current market's YES or NO token
+    pub fn token_metadata(&self, token_type: u8) -> Result<MetadataAccount> {
+        self.decode(&pda::metadata(&self.outcome_mint(token_type)?).0)
+    }
+
+    pub fn token_balance(&self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        self.svm
+            .account(&pda::user_ata(owner, mint))
+            .and_then(|account| spl_token::state::Account::unpack(&account.data).ok())
+            .map_or(0, |account| account.amount)
+    }
+
+    fn outcome_mint(&self, token_type: u8) -> Result<Pubkey> {
+        Ok(self.market_keys()?.outcome_mint(token_type))
+    }
+
+    pub fn execute(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[Pubkey],
+    ) -> Result<TransactionMeta, TransactionError> {
+        let meta = self.svm.process_transaction(instructions, signers)?;
+        self.events = meta
+            .events
+            .iter()
+            .filter_map(|data| decode_event(data))
+            .collect();
+        self.return_data = meta.return_data.as_ref().map(|(_, data)| data.clone());
+        self.compute_units = meta.compute_units_consumed;
+        Ok(meta)
+    }
+
+    pub fn run(&mut self, script: &str) -> Result<()> {
+        for (number, line) in script.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            for step in line.split(';') {
+                let words: Vec<&str> = step.split_whitespace().collect();
+                if words.is_empty() {
+                    continue;
+                }
+                self.step(&words)
+                    .with_context(|| format!("line {}: `{}`", number + 1, step.trim()))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn step(&mut self, words: &[&str]) -> Result<()> {
+        let (words, expected_error) = match words {
+            [rest @ .., "fails", error] => (rest, Some(*error)),
+            _ => (words, None),
+        };
+
+        let (instructions, signers) = match words {
+            ["expect", rest @ ..] => return self.expect(rest),
+            ["warp", rest @ ..] => return self.warp(rest),
+            ["use", "market", id] => {
+                let entry = self.market_entry(id.parse()?)?;
+                let market: Market = self.decode(&entry.market)?;
+                self.market = Some(MarketKeys::new(market.yes_token_mint, market.no_token_mint));
+                return Ok(());
+            }
+            ["resolve", outcome, rest @ ..] => {
+                let authority = self.signer(rest)?;
+                let parent = self.market()?.condition.map(|condition| condition.parent);
+                let ix = instructions::propose_resolution(
+                    &authority,
+                    &self.market_keys()?,
+                    parse_outcome(outcome)?,
+                    parent.as_ref(),
+                );
+                (vec![ix], vec![authority])
+            }
+            ["void", "conditional", "market", rest @ ..] => {
+                let signer = self.signer(rest)?;
+                let parent = self
+                    .market()?
+                    .condition
+                    .ok_or_else(|| anyhow!("the market is not conditional"))?
+                    .parent;
+                let ix = instructions::void_conditional_market(
+                    &signer,
+                    &self.market_keys()?.market,
+                    &parent,
+                );
+                (vec![ix], vec![signer])
+            }
+            ["update", "outcome", "metadata", rest @ ..] => {
+                let signer = self.signer(rest)?;
+                let ix = instructions::update_outcome_metadata(&signer, &self.market_keys()?);
+                (vec![ix], vec![signer])
+            }
+            ["void", "market", rest @ ..] => {
+                let authority = self.signer(rest)?;
+                let ix = instructions::void_market(&authority, &self.market_keys()?);
+                (vec![ix], vec![authority])
+            }
+            ["observe", window_slots] => (
+                vec![instructions::observe(
+                    &self.market_keys()?,
+                    window_slots.parse()?,
+                )],
+                vec![],
+            ),
+            ["configure", options @ ..] => self.configure(options)?,
+            ["create", "market", options @ ..] => self.create_market(options)?,
+            ["nominate", name] => {
+                let new_admin = self.actor(name);
+                (
+                    vec![instructions::nominate_authority(
+                        &self.authority,
+                        &new_admin,
+                    )],
+                    vec![self.authority],
+                )
+            }
+            [name, "accepts", "authority"] => {
+                let new_admin = self.actor(name);
+                (
+                    vec![instructions::accept_authority(&new_admin)],
+                    vec![new_admin],
+                )
+            }
+            [name, "adds", "liquidity", amount @ ..] => {
+                let user = self.actor(name);
+                let amount = parse_amount(amount)?;
+                let team_wallet = self.config()?.team_wallet;
+                let ix =
+                    instructions::add_liquidity(&user, &self.market_keys()?, &team_wallet, amount);
+                (vec![ix], vec![user])
+            }
+            [name, "funds", "parlay", "pool", amount @ ..] => {
+                let authority = self.actor(name);
+                let ix = instructions::fund_parlay_pool(&authority, parse_amount(amount)?);
+                (vec![ix], vec![authority])
+            }
+            [name, "withdraws", "parlay", "pool", amount @ ..] => {
+                let authority = self.actor(name);
+                let ix = instructions::withdraw_parlay_pool(&authority, parse_amount(amount)?);
+                (vec![ix], vec![authority])
+            }
+            [name, "parlays", rest @ ..] => self.open_parlay(name, rest)?,
+            [name, "settles", "parlay", id] => {
+                let signer = self.actor(name);
+                let ix = instructions::settle_parlay(&self.parlay(id.parse()?)?);
+                (vec![ix], vec![signer])
+            }
+            [name, "withdraws", "liquidity", amount @ ..] => {
+                let user = self.actor(name);
+                let amount = parse_amount(amount)?;
+                let team_wallet = self.config()?.team_wallet;
+                let ix = instructions::withdraw_liquidity(
+                    &user,
+                    &self.market_keys()?,
+                    &team_wallet,
+                    amount,
+                );
+                (vec![ix], vec![user])
+            }
+            [name, "updates", "question", question @ ..] => {
+                let creator = self.actor(name);
+                let current = self.market_metadata()?;
+                let params = MarketMetadataParams {
+                    question: question.join(" "),
+                    rules: current.rules,
+                    resolution_source: current.resolution_source,
+                    category: current.category,
+                    tags: current.tags,
+                };
+                let ix =
+                    instructions::update_market_metadata(&creator, &self.market_keys()?, params);
+                (vec![ix], vec![creator])
+            }
+            [name, "updates", "category", category] => {
+                let creator = self.actor(name);
+                let current = self.market_metadata()?;
+                let params = MarketMetadataParams {
+                    question: current.question,
+                    rules: current.rules,
+                    resolution_source: current.resolution_source,
+                    category: category.to_string(),
+                    tags: current.tags,
+                };
+                let ix =
+                    instructions::update_market_metadata(&creator, &self.market_keys()?, params);
+                (vec![ix], vec![creator])
+            }
+            [name, "sets", "trade", "limits", options @ ..] => {
+                let creator = self.actor(name);
+                let mut trade_limits = self.market()?.trade_limits;
+                for pair in options.chunks(2) {
+                    let [key, value] = pair else {
+                        bail!("expected `<limit> <amount>` pairs");
+                    };
+                    ensure!(
+                        set_trade_limit(&mut trade_limits, key, value)?,
+                        "unknown trade limit {key}"
+                    );
+                }
+                let ix =
+                    instructions::update_trade_limits(&creator, &self.market_keys()?, trade_limits);
+                (vec![ix], vec![creator])
+            }
+            [name, "archives", "market"] => {
+                let signer = self.actor(name);
+                let entry = self.market_entry(self.market()?.id)?;
+                (
+                    vec![instructions::archive_market(&signer, &entry)],
+                    vec![signer],
+                )
+            }
+            [name, "closes", "position"] => {
+                let user = self.actor(name);
+                let ix = instructions::close_user_info(&user, &self.market_keys()?.market);
+                (vec![ix], vec![user])
+            }
+            [name, "closes", "market"] => {
+                let signer = self.actor(name);
+                let team_wallet = self.config()?.team_wallet;
+                let ix =
+                    instructions::close_market(&self.market_keys()?, &self.market()?, &team_wallet);
+                (vec![ix], vec![signer])
+            }
+            [name, "batch", rest @ ..] => self.batch_swap(name, rest)?,
+            [name, "buys", rest @ ..] => self.swap(name, DIRECTION_BUY, rest)?,
+            [name, "sells", rest @ ..] => self.swap(name, DIRECTION_SELL, rest)?,
+            [name, "quotes", "buying", rest @ ..] => self.quote(name, DIRECTION_BUY, rest)?,
+            [name, "quotes", "selling", rest @ ..] => self.quote(name, DIRECTION_SELL, rest)?,
+            [name, "redeems", rest @ ..] => self.redeem(name, rest)?,
+            [name, "registers", "as", "referrer", rest @ ..] => {
+                let referrer_authority = self.actor(name);
+                let authority = self.signer(rest)?;
+                (
+                    vec![instructions::register_referrer(
+                        &authority,
+                        &referrer_authority,
+                    )],
+                    vec![authority],
+                )
+            }
+            [name, "claims", "referral", "fees"] => {
+                let authority = self.actor(name);
+                (
+                    vec![instructions::claim_referral_fees(&authority)],
+                    vec![authority],
+                )
+            }
+            [name, "sends", amount, outcome, "to", recipient] => {
+                self.send(name, amount, outcome, recipient)?
+            }
+            [name, "refunds"] => {
+                let user = self.actor(name);
+                let ix = instructions::refund(&user, &self.market_keys()?);
+                (vec![ix], vec![user])
+            }
+            _ => bail!("unknown step"),
+        };
+
+        match (self.execute(&instructions, &signers), expected_error) {
+            (Ok(_), None) => Ok(()),
+            (Ok(_), Some(expected)) => bail!("expected the transaction to fail with {expected}"),
+            (Err(error), None) => Err(anyhow!(
+                "transaction failed: {:?}\n{}",
+                error.error,
+                error.logs.join("\n")
+            )),
+            (Err(error), Some(expected)) => {
+                let matches = error.anchor_error() == Some(expected)
+                    || format!("{:?}", error.error).starts_with(expected);
+                ensure!(
+                    matches,
+                    "expected {expected}, got {:?}\n{}",
+                    error.error,
+                    error.logs.join("\n")
+                );
+                Ok(())
+            }
+        }
+    }
+
+    fn configure(&mut self, options: &[&str]) -> Result<(Vec<Instruction>, Vec<Pubkey>)> {
+        let authority = self.authority;
+        let mut config = match self.config() {
+            Ok(config) => config,
+            Err(_) => Config {
+                authority,
+                pending_authority: Pubkey::default(),
+                team_wallet: self.actor("team"),
+                platform_buy_fee: DEFAULT_PLATFORM_FEE_BPS,
+                platform_sell_fee: DEFAULT_PLATFORM_FEE_BPS,
+                lp_buy_fee: DEFAULT_LP_FEE_BPS,
+                lp_sell_fee: DEFAULT_LP_FEE_BPS,
+                referral_fee_bps: 0,
+                fee_tiers: Vec::new(),
+                token_supply_config: DEFAULT_TOKEN_SUPPLY,
+                token_decimals_config: DEFAULT_TOKEN_DECIMALS,
+                initial_real_token_reserves_config: DEFAULT_TOKEN_SUPPLY,
+                min_sol_liquidity: DEFAULT_MIN_SOL_LIQUIDITY,
+                max_position_per_wallet_config: UNBOUNDED,
+                min_trade_lamports_config: UNBOUNDED,
+                max_trade_lamports_config: UNBOUNDED,
+                won_uri: String::new(),
+                lost_uri: String::new(),
+                initialized: true,
+            },
+        };
+
+        for option in options {
+            let (key, value) = option
+                .split_once('=')
+                .ok_or_else(|| anyhow!("expected key=value, got {option}"))?;
+            let amount = || parse_amount(&[value]);
+            match key {
+                "platform_buy_fee" => config.platform_buy_fee = amount()?,
+                "platform_sell_fee" => config.platform_sell_fee = amount()?,
+                "lp_buy_fee" => config.lp_buy_fee = amount()?,
+                "lp_sell_fee" => config.lp_sell_fee = amount()?,
+                "referral_fee_bps" => config.referral_fee_bps = amount()?,
+                //  `MIN_VOLUME:BUY_BPS:SELL_BPS,...`, `none` for flat fees
+                "fee_tiers" => {
+                    config.fee_tiers = match value {
+                        "none" => Vec::new(),
+                        _ => value
+                            .split(',')
+                            .map(parse_fee_tier)
+                            .collect::<Result<_>>()?,
+                    }
+                }
+                "token_supply" => config.token_supply_config = amount()?,
+                "token_decimals" => config.token_decimals_config = value.parse()?,
+                "initial_real_token_reserves" => {
+                    config.initial_real_token_reserves_config = amount()?
+                }
+                "min_sol_liquidity" => config.min_sol_liquidity = amount()?,
+                "max_position_per_wallet" => {
+                    config.max_position_per_wallet_config = parse_amount_config(value)?
+                }
+                "min_trade_lamports" => {
+                    config.min_trade_lamports_config = parse_amount_config(value)?
+                }
+                "max_trade_lamports" => {
+                    config.max_trade_lamports_config = parse_amount_config(value)?
+                }
+                "team_wallet" => config.team_wallet = self.actor(value),
+                "won_uri" => config.won_uri = value.to_string(),
+                "lost_uri" => config.lost_uri = value.to_string(),
+                _ => bail!("unknown config field {key}"),
+            }
+        }
+
+        Ok((
+            vec![instructions::configure(&authority, config)],
+            vec![authority],
+        ))
+    }
+
+    //  `[start <slot>] [end <slot>] [category <name>] [if <market id> YES|NO]
+    //  [no_token <market id>]` and trade limits, slots may be relative as `+N`,
+    //  `no_token` reuses the NO mint of another market instead of minting one
+    fn create_market(&mut self, options: &[&str]) -> Result<(Vec<Instruction>, Vec<Pubkey>)> {
+        let mut options = options.to_vec();
+        let condition = match options.iter().position(|word| *word == "if") {
+            Some(at) => {
+                let [id, outcome] = options.get(at + 1..at + 3).unwrap_or_default() else {
+                    bail!("expected `if <market id> YES|NO`");
+                };
+                let condition = MarketCondition {
+                    parent: self.market_entry(id.parse()?)?.market,
+                    outcome: parse_outcome(outcome)?,
+                };
+                options.drain(at..at + 3);
+                Some(condition)
+            }
+            None => None,
+        };
+        let mut start_slot = None;
+        let mut ending_slot = None;
+        let mut start_time = None;
+        let mut end_time = None;
+        let mut trade_limits = TradeLimits::default();
+        let mut category = "testing".to_string();
+        let mut no_token = None;
+        for pair in options.chunks(2) {
+            let [key, value] = pair else {
+                bail!("expected `start <slot>`, `end <slot>`, `start_time <unix>`, `end_time <unix>` or `category <name>`");
+            };
+            if set_trade_limit(&mut trade_limits, key, value)? {
+                continue;
+            }
+            let slot = || -> Result<u64> {
+                Ok(match value.strip_prefix('+') {
+                    Some(delta) => self.svm.clock().slot + delta.parse::<u64>()?,
+                    None => value.parse()?,
+                })
+            };
+            //  `+N` is N seconds from now
+            let time = || -> Result<i64> {
+                Ok(match value.strip_prefix('+') {
+                    Some(delta) => self.svm.clock().unix_timestamp + delta.parse::<i64>()?,
+                    None => value.parse()?,
+                })
+            };
+            match *key {
+                "start" => start_slot = Some(slot()?),
+                "end" => ending_slot = Some(slot()?),
+                "start_time" => start_time = Some(time()?),
+                "end_time" => end_time = Some(time()?),
+                "category" => category = value.to_string(),
+                "no_token" => {
+                    let market: Market = self.decode(&self.market_entry(value.parse()?)?.market)?;
+                    no_token = Some(market.no_token_mint);
+                }
+                _ => bail!("unknown market option {key}"),
+            }
+        }
+        let registry_slot = RegistrySlot::next(
+            self.decode(&pda::registry().0).ok().as_ref(),
+            self.category(&category).ok().as_ref(),
+        );
+
+        let creator = self.authority;
+        let team_wallet = self.config()?.team_wallet;
+        let keys = MarketKeys::new(
+            Pubkey::new_unique(),
+            no_token.unwrap_or_else(Pubkey::new_unique),
+        );
+        self.market = Some(keys);
+
+        let mint_no_token = no_token.is_none().then(|| {
+            instructions::mint_no_token(
+                &creator,
+                &keys.no_token,
+                "NO".to_string(),
+                "https://example.com/no.json".to_string(),
+            )
+        });
+        Ok((
+            mint_no_token
+                .into_iter()
+                .chain([instructions::create_market(
+                    &creator,
+                    &keys,
+                    &team_wallet,
+                    registry_slot,
+                    CreateMarketParams {
+                        yes_symbol: "YES".to_string(),
+                        yes_uri: "https://example.com/yes.json".to_string(),
+                        start_slot,
+                        ending_slot,
+                        start_time,
+                        end_time,
+                        trade_limits,
+                        metadata: MarketMetadataParams {
+                            question: "Will the scenario pass?".to_string(),
+                            rules: "Resolves YES if every step succeeds.".to_string(),
+                            resolution_source: "cargo test".to_string(),
+                            category,
+                            tags: vec!["scenario".to_string()],
+                        },
+                        condition,
+                    },
+                )])
+                .collect(),
+            [creator, keys.yes_token]
+                .into_iter()
+                .chain(no_token.is_none().then_some(keys.no_token))
+                .collect(),
+        ))
+    }
+
+    //  `<amount> [SOL] YES|NO [min <amount>] [until <slot>] [max_impact <bps>] [via <referrer>]`,
+    //  sells accept `all` and `until` may be relative as `+N`
+    fn swap_args(&mut self, user: &Pubkey, keys: &MarketKeys, words: &[&str]) -> Result<SwapArgs> {
+        let outcome_at = words
+            .iter()
+            .position(|word| parse_outcome(word).is_ok())
+            .ok_or_else(|| anyhow!("missing YES or NO"))?;
+        let token_type = parse_outcome(words[outcome_at])?;
+        let amount = match &words[..outcome_at] {
+            ["all"] => self.token_balance(user, &keys.outcome_mint(token_type)),
+            amount => parse_amount(amount)?,
+        };
+        let mut args = SwapArgs {
+            amount,
+            token_type,
+            minimum_receive_amount: 0,
+            valid_until_slot: None,
+            max_price_impact_bps: None,
+            referrer: None,
+        };
+        let mut options = &words[outcome_at + 1..];
+        loop {
+            options = match options {
+                [] => break,
+                ["min", amount, unit, rest @ ..] if unit.eq_ignore_ascii_case("sol") => {
+                    args.minimum_receive_amount = parse_amount(&[*amount, *unit])?;
+                    rest
+                }
+                ["min", amount, rest @ ..] => {
+                    args.minimum_receive_amount = parse_amount(&[*amount])?;
+                    rest
+                }
+                ["until", slot, rest @ ..] => {
+                    args.valid_until_slot = Some(match slot.strip_prefix('+') {
+                        Some(delta) => self.svm.clock().slot + delta.parse::<u64>()?,
+                        None => slot.parse()?,
+                    });
+                    rest
+                }
+                ["max_impact", bps, rest @ ..] => {
+                    args.max_price_impact_bps = Some(bps.parse()?);
+                    rest
+                }
+                ["via", name, rest @ ..] => {
+                    args.referrer = Some(self.actor(name));
+                    rest
+                }
+                rest => bail!("unexpected {}", rest.join(" ")),
+            };
+        }
+        Ok(args)
+    }
+
+    fn swap(
+        &mut self,
+        name: &str,
+        direction: u8,
+        words: &[&str],
+    ) -> Result<(Vec<Instruction>, Vec<Pubkey>)> {
+        let user = self.actor(name);
+        let args = self.swap_args(&user, &self.market_keys()?, words)?;
+        let team_wallet = self.config()?.team_wallet;
+        Ok((
+            vec![instructions::swap(
+                &user,
+                &self.market_keys()?,
+                &team_wallet,
+                args.amount,
+                direction,
+                args.token_type,
+                args.minimum_receive_amount,
+                args.valid_until_slot,
+                args.max_price_impact_bps,
+                args.referrer.as_ref(),
+            )],
+            vec![user],
+        ))
+    }
+
+    //  `buys|sells <swap words> [on <market id>], ...`, legs without a market id trade the
+    //  current one, `until` and `via` apply to the whole batch and are given on one leg
+    fn batch_swap(
+        &mut self,
+        name: &str,
+        words: &[&str],
+    ) -> Result<(Vec<Instruction>, Vec<Pubkey>)> {
+        let user = self.actor(name);
+        let mut legs = vec![];
+        let mut valid_until_slot = None;
+        let mut referrer = None;
+        for leg in words.join(" ").split(',') {
+            let mut words: Vec<&str> = leg.split_whitespace().collect();
+            let keys = match words.iter().position(|word| *word == "on") {
+                Some(at) => {
+                    let id = words
+                        .get(at + 1)
+                        .ok_or_else(|| anyhow!("expected a market id after `on`"))?
+                        .parse()?;
+                    words.drain(at..at + 2);
+                    let market: Market = self.decode(&self.market_entry(id)?.market)?;
+                    MarketKeys::new(market.yes_token_mint, market.no_token_mint)
+                }
+                None => self.market_keys()?,
+            };
+            let (direction, words) = match &words[..] {
+                ["buys", rest @ ..] => (DIRECTION_BUY, rest),
+                ["sells", rest @ ..] => (DIRECTION_SELL, rest),
+                _ => bail!("expected `buys` or `sells`, got `{}`", leg.trim()),
+            };
+            let args = self.swap_args(&user, &keys, words)?;
+            if args.valid_until_slot.is_some() {
+                ensure!(valid_until_slot.is_none(), "`until` is given once per batch");
+                valid_until_slot = args.valid_until_slot;
+            }
+            if args.referrer.is_some() {
+                ensure!(referrer.is_none(), "`via` is given once per batch");
+                referrer = args.referrer;
+            }
+            legs.push((
+                keys,
+                SwapLeg {
+                    market: keys.market,
+                    token_type: args.token_type,
+                    direction,
+                    amount: args.amount,
+                    minimum_receive_amount: args.minimum_receive_amount,
+                    max_price_impact_bps: args.max_price_impact_bps,
+                },
+            ));
+        }
+        let team_wallet = self.config()?.team_wallet;
+        Ok((
+            vec![instructions::batch_swap(
+                &user,
+                &team_wallet,
+                &legs,
+                valid_until_slot,
+                referrer.as_ref(),
+            )],
+            vec![user],
+        ))
+    }
+
+    //  `<amount> [SOL] on <market id> YES|NO, ... [min <amount> [SOL]]`
+    fn open_parlay(
+        &mut self,
+        name: &str,
+        words: &[&str],
+    ) -> Result<(Vec<Instruction>, Vec<Pubkey>)> {
+        let owner = self.actor(name);
+        let on = words
+            .iter()
+            .position(|word| *word == "on")
+            .ok_or_else(|| anyhow!("expected `on <market id> YES|NO, ...`"))?;
+        let stake = parse_amount(&words[..on])?;
+        let (picks, min_payout) = match words.iter().position(|word| *word == "min") {
+            Some(at) => (&words[on + 1..at], parse_amount(&words[at + 1..])?),
+            None => (&words[on + 1..], 0),
+        };
+        let picks = picks
+            .join(" ")
+            .split(',')
+            .map(
+                |pick| match pick.split_whitespace().collect::<Vec<_>>()[..] {
+                    [id, outcome] => Ok(ParlayPick {
+                        market: self.market_entry(id.parse()?)?.market,
+                        token_type: parse_outcome(outcome)?,
+                    }),
+                    _ => bail!("expected `<market id> YES|NO`, got `{}`", pick.trim()),
+                },
+            )
+            .collect::<Result<Vec<_>>>()?;
+        //  the first parlay if the pool is not funded yet, `open_parlay` then fails
+        let parlay_id = self.parlay_pool().map_or(0, |pool| pool.parlay_count);
+        let team_wallet = self.config()?.team_wallet;
+        Ok((
+            vec![instructions::open_parlay(
+                &owner,
+                &team_wallet,
+                parlay_id,
+                &picks,
+                stake,
+                min_payout,
+            )],
+            vec![owner],
+        ))
+    }
+
+    //  the same words as the swap, read back with `expect quote <field> ...`
+    fn quote(
+        &mut self,
+        name: &str,
+        direction: u8,
+        words: &[&str],
+    ) -> Result<(Vec<Instruction>, Vec<Pubkey>)> {
+        let user = self.actor(name);
+        let args = self.swap_args(&user, &self.market_keys()?, words)?;
+        //  at the user's fee tier once they have traded
+        let trader = self.user_stats(&user).ok().map(|_| user);
+        Ok((
+            vec![instructions::quote_swap(
+                &self.market_keys()?,
+                args.amount,
+                direction,
+                args.token_type,
+                args.minimum_receive_amount,
+                args.valid_until_slot,
+                args.max_price_impact_bps,
+                trader.as_ref(),
+            )],
+            vec![],
+        ))
+    }
+
+    //  `[by <authority>|without authority]`, pays out every outcome token the user holds,
+    //  the authority co-signs by default while the outcome is only proposed
+    fn redeem(&mut self, name: &str, words: &[&str]) -> Result<(Vec<Instruction>, Vec<Pubkey>)> {
+        let user = self.actor(name);
+        let market = self.market()?;
+        let authority = match words {
+            ["without", "authority"] => None,
+            [] if market.status != MarketStatus::ResolutionProposed => None,
+            _ => Some(self.signer(words)?),
+        };
+        let winner = market
+            .winning_token_type
+            .ok_or_else(|| anyhow!("`resolve YES|NO` has to come before redeeming"))?;
+        let keys = self.market_keys()?;
+
+        Ok((
+            vec![instructions::resolution(
+                authority.as_ref(),
+                &user,
+                &keys,
+                self.token_balance(&user, &keys.outcome_mint(TOKEN_TYPE_YES)),
+                self.token_balance(&user, &keys.outcome_mint(TOKEN_TYPE_NO)),
+                winner,
+            )],
+            authority.into_iter().chain([user]).collect(),
+        ))
+    }
+
+    //  `<amount|all> YES|NO to <name>`, a plain token transfer outside of the program
+    fn send(
+        &mut self,
+        name: &str,
+        amount: &str,
+        outcome: &str,
+        recipient: &str,
+    ) -> Result<(Vec<Instruction>, Vec<Pubkey>)> {
+        let user = self.actor(name);
+        let recipient = self.actor(recipient);
+        let mint = self.outcome_mint(parse_outcome(outcome)?)?;
+        let amount = match amount {
+            "all" => self.token_balance(&user, &mint),
+            amount => parse_amount(&[amount])?,
+        };
+        Ok((
+            vec![
+                spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                    &user,
+                    &recipient,
+                    &mint,
+                    &spl_token::ID,
+                ),
+                spl_token::instruction::transfer(
+                    &spl_token::ID,
+                    &pda::user_ata(&user, &mint),
+                    &pda::user_ata(&recipient, &mint),
+                    &user,
+                    &[],
+                    amount,
+                )?,
+            ],
+            vec![user],
+        ))
+    }
+
+    //  `[by <name>]`, the config authority by default
+    fn signer(&mut self, words: &[&str]) -> Result<Pubkey> {
+        match words {
+            [] => Ok(self.authority),
+            ["by", name] => Ok(self.actor(name)),
+            _ => bail!("unexpected {}", words.join(" ")),
+        }
+    }
+
+    fn warp(&mut self, words: &[&str]) -> Result<()> {
+        //  wall clock only, as if slots drifted
+        let unix_timestamp = match words {
+            ["past", "claim_deadline"] => Some(
+                self.market()?
+                    .claim_deadline()
+                    .ok_or_else(|| anyhow!("market is not resolved"))?,
+            ),
+            ["to", "start_time"] => Some(
+                self.market()?
+                    .start_time
+                    .ok_or_else(|| anyhow!("market has no start time"))?,
+            ),
+            ["to", "end_time"] => Some(
+                self.market()?
+                    .end_time
+                    .ok_or_else(|| anyhow!("market has no end time"))?,
+            ),
+            ["past", "end_time"] => Some(
+                self.market()?
+                    .end_time
+                    .ok_or_else(|| anyhow!("market has no end time"))?
+                    + 1,
+            ),
+            [delta] if delta.starts_with('+') && delta.ends_with('s') => {
+                Some(self.svm.clock().unix_timestamp + delta[1..delta.len() - 1].parse::<i64>()?)
+            }
+            _ => None,
+        };
+        if let Some(unix_timestamp) = unix_timestamp {
+            self.svm.set_unix_timestamp(unix_timestamp);
+            return Ok(());
+        }
+        let slot = match words {
+            ["to", "slot", slot] => slot.parse()?,
+            ["to", "start_slot"] => self
+                .market()?
+                .start_slot
+                .ok_or_else(|| anyhow!("market has no start slot"))?,
+            ["to", "ending_slot"] => self
+                .market()?
+                .ending_slot
+                .ok_or_else(|| anyhow!("market has no ending slot"))?,
+            ["past", "ending_slot"] => {
+                self.market()?
+                    .ending_slot
+                    .ok_or_else(|| anyhow!("market has no ending slot"))?
+                    + 1
+            }
+            [delta] if delta.starts_with('+') => {
+                self.svm.clock().slot + delta[1..].parse::<u64>()?
+            }
+            _ => bail!("unknown warp target"),
+        };
+        self.svm.warp_to_slot(slot);
+        Ok(())
+    }
+
+    fn return_data(&self) -> Result<&[u8]> {
+        self.return_data
+            .as_deref()
+            .ok_or_else(|| anyhow!("the last transaction returned no data"))
+    }
+
+    fn expect(&mut self, words: &[&str]) -> Result<()> {
+        let (actual, op, expected) = match words {
+            ["event", kind, fields @ ..] => return self.expect_event(kind, fields),
+            ["market", "status", "=", expected] => {
+                let status = format!("{:?}", self.market()?.status);
+                ensure!(
+                    status == *expected,
+                    "expected status {expected}, got {status}"
+                );
+                return Ok(());
+            }
+            //  names may contain spaces, so the rest of the line is the expected text
+            ["metadata", outcome, field, "=", expected @ ..] => {
+                let token_type = parse_outcome(outcome)?;
+                let metadata = self.token_metadata(token_type)?;
+                let actual = match *field {
+                    "name" => &metadata.name,
+                    "symbol" => &metadata.symbol,
+                    "uri" => &metadata.uri,
+                    _ => bail!("unknown metadata field {field}"),
+                };
+                let expected = expected
+                    .join(" ")
+                    .replace("{mint}", &self.outcome_mint(token_type)?.to_string());
+                ensure!(
+                    actual.trim_end_matches('\0') == expected,
+                    "expected {field} {expected}, got {actual}"
+                );
+                return Ok(());
+            }
+            ["quote", field, op, expected @ ..] => {
+                let quote: SwapQuote = decode_return_data(self.return_data()?)?;
+                (quote_field(&quote, field)?, *op, expected)
+            }
+            ["twap", field, op, expected @ ..] => {
+                let twap: Twap = decode_return_data(self.return_data()?)?;
+                (twap_field(&twap, field)?, *op, expected)
+            }
+            ["market", field, op, expected @ ..] => {
+                (market_field(&self.market()?, field)?, *op, expected)
+            }
+            ["parlay", "pool", field, op, expected @ ..] => {
+                let pool = self.parlay_pool()?;
+                let actual = match *field {
+                    "liquidity" => pool.liquidity,
+                    "reserved" => pool.reserved,
+                    "parlay_count" => pool.parlay_count,
+                    "sol" => self.svm.lamports(&pda::parlay_pool().0),
+                    _ => bail!("unknown parlay pool field {field}"),
+                };
+                (actual, *op, expected)
+            }
+            ["parlay", id, field, op, expected @ ..] => {
+                let parlay = self.parlay(id.parse()?)?;
+                let actual = match *field {
+                    "stake" => parlay.stake,
+                    "payout" => parlay.payout,
+                    "legs" => parlay.legs.len() as u64,
+                    _ => bail!("unknown parlay field {field}"),
+                };
+                (actual, *op, expected)
+            }
+            ["compute_units", op, expected @ ..] => (self.compute_units, *op, expected),
+            ["vault", "sol", op, expected @ ..] => {
+                (self.svm.lamports(&pda::global_vault().0), *op, expected)
+            }
+            [name, "sol", op, expected @ ..] => {
+                let address = self.actor(name);
+                (self.svm.lamports(&address), *op, expected)
+            }
+            [name, "referrer", field, op, expected @ ..] => {
+                let authority = self.actor(name);
+                (
+                    referrer_field(&self.referrer(&authority)?, field)?,
+                    *op,
+                    expected,
+                )
+            }
+            [name, "stats", field, op, expected @ ..] => {
+                let user = self.actor(name);
+                let stats = self.user_stats(&user)?;
+                let actual = match *field {
+                    "volume" => stats.volume(self.svm.clock().unix_timestamp),
+                    "total_volume" => stats.total_volume,
+                    _ => bail!("unknown stats field {field}"),
+                };
+                (actual, *op, expected)
+            }
+            [name, "position", field, op, expected @ ..] => {
+                let address = self.actor(name);
+                let actual = position_field(&self.position(&address)?, field)?;
+                return compare(actual, op, parse_signed_amount(expected)?);
+            }
+            [name, outcome, op, expected @ ..] => {
+                let address = self.actor(name);
+                let mint = self.outcome_mint(parse_outcome(outcome)?)?;
+                (self.token_balance(&address, &mint), *op, expected)
+            }
+            _ => bail!("unknown expectation"),
+        };
+        compare(actual, op, parse_amount(expected)?)
+    }
+
+    //  `<kind> [<field> <op> <amount> [SOL]]...` against the last transaction's events
+    fn expect_event(&self, kind: &str, fields: &[&str]) -> Result<()> {
+        let candidates: Vec<&ProgramEvent> = self
+            .events
+            .iter()
+            .filter(|event| event.kind() == kind)
+            .collect();
+        ensure!(!candidates.is_empty(), "no {kind} event was emitted");
+
+        let mut conditions = vec![];
+        let mut rest = fields;
+        while !rest.is_empty() {
+            let [field, op, amount, tail @ ..] = rest else {
+                bail!("expected `<field> <op> <amount>`");
+            };
+            let (expected, tail) = match tail {
+                [unit, tail @ ..] if unit.eq_ignore_ascii_case("sol") => {
+                    (parse_amount(&[*amount, *unit])?, tail)
+                }
+                _ => (parse_amount(&[*amount])?, tail),
+            };
+            conditions.push((*field, *op, expected));
+            rest = tail;
+        }
+
+        let mut last_error = None;
+        'events: for event in candidates {
+            let values = event_fields(event);
+            for (field, op, expected) in &conditions {
+                let actual = values
+                    .iter()
+                    .find(|(name, _)| name == field)
+                    .map(|(_, value)| *value)
+                    .ok_or_else(|| anyhow!("{kind} has no numeric field {field}"))?;
+                if let Err(error) = compare(actual, op, *expected) {
+                    last_error = Some(error.context(format!("{kind}.{field}")));
+                    continue 'events;
+                }
+            }
+            return Ok(());
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("no matching {kind} event")))
+    }
+}
+
+fn parse_outcome(word: &str) -> Result<u8> {
+    match word.to_ascii_uppercase().as_str() {
+        "YES" => Ok(TOKEN_TYPE_YES),
+        "NO" => Ok(TOKEN_TYPE_NO),
+        _ => bail!("expected YES or NO, got {word}"),
+    }
+}
+
+//  `123`, `1_000` or `<decimal> SOL`
+fn parse_amount(words: &[&str]) -> Result<u64> {
+    match words {
+        [amount] => Ok(amount.replace('_', "").parse()?),
+        [amount, unit] if unit.eq_ignore_ascii_case("sol") => {
+            let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+            ensure!(fraction.len() <= 9, "more than 9 decimals in {amount}");
+            let fraction = format!("{fraction:0<9}");
+            let whole: u64 = if whole.is_empty() { 0 } else { whole.parse()? };
+            whole
+                .checked_mul(LAMPORTS_PER_SOL)
+                .and_then(|lamports| lamports.checked_add(fraction.parse().ok()?))
+                .ok_or_else(|| anyhow!("{amount} SOL overflows"))
+        }
+        _ => bail!("expected an amount, got `{}`", words.join(" ")),
+    }
+}
+
+//  `parse_amount` with an optional leading `-`
+fn parse_signed_amount(words: &[&str]) -> Result<i128> {
+    match words {
+        [amount, unit @ ..] if amount.starts_with('-') => {
+            let mut words = vec![&amount[1..]];
+            words.extend(unit);
+            Ok(-(parse_amount(&words)? as i128))
+        }
+        _ => Ok(parse_amount(words)? as i128),
+    }
+}
+
+fn compare<T: PartialOrd + std::fmt::Display>(actual: T, op: &str, expected: T) -> Result<()> {
+    let holds = match op {
+        "=" | "==" => actual == expected,
+        "!=" => actual != expected,
+        "<" => actual < expected,
+        "<=" => actual <= expected,
+        ">" => actual > expected,
+        ">=" => actual >= expected,
+        _ => bail!("unknown operator {op}"),
+    };
+    ensure!(holds, "expected {actual} {op} {expected}");
+    Ok(())
+}
+
+//  `max_position`, `min_trade` or `max_trade` with a raw amount, false for other keys
+fn set_trade_limit(trade_limits: &mut TradeLimits, key: &str, value: &str) -> Result<bool> {
+    let limit = match key {
+        "max_position" => &mut trade_limits.max_position_per_wallet,
+        "min_trade" => &mut trade_limits.min_trade_lamports,
+        "max_trade" => &mut trade_limits.max_trade_lamports,
+        _ => return Ok(false),
+    };
+    *limit = parse_amount(&[value])?;
+    Ok(true)
+}
+
+fn market_field(market: &Market, field: &str) -> Result<u64> {
+    Ok(match field {
+        "initial_yes_token_reserves" => market.initial_yes_token_reserves,
+        "real_yes_token_reserves" => market.real_yes_token_reserves,
+        "real_yes_sol_reserves" => market.real_yes_sol_reserves,
+        "token_yes_total_supply" => market.token_yes_total_supply,
+        "initial_no_token_reserves" => market.initial_no_token_reserves,
+        "real_no_token_reserves" => market.real_no_token_reserves,
+        "real_no_sol_reserves" => market.real_no_sol_reserves,
+        "token_no_total_supply" => market.token_no_total_supply,
+        "total_lp_amount" => market.total_lp_amount,
+        "lp_fees" => market.lp_fees,
+        "lp_count" => market.lps.len() as u64,
+        "open_cost_basis" => market.open_cost_basis,
+        "open_parlays" => market.open_parlays,
+        "open_conditional_markets" => market.open_conditional_markets,
+        "id" => market.id,
+        "max_position_per_wallet" => market.trade_limits.max_position_per_wallet,
+        "min_trade_lamports" => market.trade_limits.min_trade_lamports,
+        "max_trade_lamports" => market.trade_limits.max_trade_lamports,
+        _ => bail!("unknown market field {field}"),
+    })
+}
+
+fn quote_field(quote: &SwapQuote, field: &str) -> Result<u64> {
+    Ok(match field {
+        "token_amount" => quote.token_amount,
+        "change_amount" => quote.change_amount,
+        "new_yes_reserves" => quote.new_yes_reserves,
+        "new_no_reserves" => quote.new_no_reserves,
+        "platform_fee" => quote.platform_fee,
+        "lp_fee" => quote.lp_fee,
+        "amount_out" => quote.amount_out,
+        "price_impact_bps" => quote.price_impact_bps,
+        _ => bail!("unknown quote field {field}"),
+    })
+}
+
+fn referrer_field(referrer: &Referrer, field: &str) -> Result<u64> {
+    Ok(match field {
+        "unclaimed" => referrer.unclaimed,
+        "total_earned" => referrer.total_earned,
+        _ => bail!("unknown referrer field {field}"),
+    })
+}
+
+fn twap_field(twap: &Twap, field: &str) -> Result<u64> {
+    Ok(match field {
+        "start_slot" => twap.start_slot,
+        "end_slot" => twap.end_slot,
+        "yes_price" => twap.yes_price,
+        "no_price" => twap.no_price,
+        _ => bail!("unknown twap field {field}"),
+    })
+}
+
+fn position_field(position: &UserInfo, field: &str) -> Result<i128> {
+    Ok(match field {
+        "yes_balance" => position.yes_balance.into(),
+        "no_balance" => position.no_balance.into(),
+        "lp_amount" => position.lp_amount.into(),
+        "is_lp" => position.is_lp.into(),
+        "yes_cost_basis" => position.yes_cost_basis.into(),
+        "no_cost_basis" => position.no_cost_basis.into(),
+        "total_sol_spent" => position.total_sol_spent.into(),
+        "total_sol_received" => position.total_sol_received.into(),
+        "realized_pnl" => position.realized_pnl.into(),
+        "fees_paid" => position.fees_paid.into(),
+        _ => bail!("unknown position field {field}"),
+    })
+}
+
+//  numeric fields of an event, booleans as 0 or 1
+fn event_fields(event: &ProgramEvent) -> Vec<(&'static str, u64)> {
+    match event {
+        ProgramEvent::ConfigUpdate(e) => vec![
+            ("platform_buy_fee", e.platform_buy_fee),
+            ("platform_sell_fee", e.platform_sell_fee),
+            ("lp_buy_fee", e.lp_buy_fee),
+            ("lp_sell_fee", e.lp_sell_fee),
+            ("referral_fee_bps", e.referral_fee_bps),
+            ("token_supply_config", e.token_supply_config),
+            ("token_decimals_config", e.token_decimals_config as u64),
+            (
+                "initial_real_token_reserves_config",
+                e.initial_real_token_reserves_config,
+            ),
+            ("min_sol_liquidity", e.min_sol_liquidity),
+        ],
+        ProgramEvent::NominateAuthority(_)
+        | ProgramEvent::AcceptAuthority(_)
+        | ProgramEvent::MarketMetadata(_)
+        | ProgramEvent::RegisterReferrer(_) => vec![],
+        ProgramEvent::MarketRegistry(e) => vec![
+            ("market_id", e.market_id),
+            ("page", e.page as u64),
+            ("archived", e.archived as u64),
+        ],
+        ProgramEvent::TradeLimits(e) => vec![
+            ("max_position_per_wallet", e.max_position_per_wallet),
+            ("min_trade_lamports", e.min_trade_lamports),
+            ("max_trade_lamports", e.max_trade_lamports),
+        ],
+        ProgramEvent::MintNoToken(e) => vec![("token_no_total_supply", e.token_no_total_supply)],
+        ProgramEvent::CloseUserInfo(e) => vec![("rent", e.rent)],
+        ProgramEvent::CloseMarket(e) => vec![("rent", e.rent), ("swept", e.swept)],
+        ProgramEvent::Create(e) => vec![
+            ("token_yes_total_supply", e.token_yes_total_supply),
+            ("real_yes_sol_reserves", e.real_yes_sol_reserves),
+            ("token_no_total_supply", e.token_no_total_supply),
+            ("real_no_sol_reserves", e.real_no_sol_reserves),
+            ("start_slot", e.start_slot),
+            ("ending_slot", e.ending_slot),
+        ],
+        ProgramEvent::Trade(e) => vec![
+            ("sol_amount", e.sol_amount),
+            ("token_amount", e.token_amount),
+            ("fee_lamports", e.fee_lamports),
+            ("is_buy", e.is_buy as u64),
+            ("is_yes_no", e.is_yes_no as u64),
+            ("real_sol_reserves", e.real_sol_reserves),
+            ("real_token_yes_reserves", e.real_token_yes_reserves),
+            ("real_token_no_reserves", e.real_token_no_reserves),
+            ("referral_fee", e.referral_fee),
+        ],
+        ProgramEvent::ReferralClaim(e) => {
+            vec![("amount", e.amount), ("total_earned", e.total_earned)]
+        }
+        ProgramEvent::AddLiquidity(e) => vec![
+            ("sol_amount", e.sol_amount),
+            ("user_lp_amount", e.user_lp_amount),
+            ("total_lp_amount", e.total_lp_amount),
+            ("real_yes_sol_reserves", e.real_yes_sol_reserves),
+            ("real_no_sol_reserves", e.real_no_sol_reserves),
+        ],
+        ProgramEvent::WithdrawLiquidity(e) => vec![
+            ("sol_amount", e.sol_amount),
+            ("fee_amount", e.fee_amount),
+            ("user_lp_amount", e.user_lp_amount),
+            ("total_lp_amount", e.total_lp_amount),
+            ("real_yes_sol_reserves", e.real_yes_sol_reserves),
+            ("real_no_sol_reserves", e.real_no_sol_reserves),
+        ],
+        ProgramEvent::ResolutionProposed(e) => {
+            vec![("winning_token_type", e.winning_token_type as u64)]
+        }
+        ProgramEvent::MarketVoided(_) => vec![],
+        ProgramEvent::OutcomeMetadata(e) => {
+            vec![("winning_token_type", e.winning_token_type as u64)]
+        }
+        ProgramEvent::Refund(e) => vec![
+            ("yes_amount", e.yes_amount),
+            ("no_amount", e.no_amount),
+            ("sol_amount", e.sol_amount),
+        ],
+        ProgramEvent::Resolution(e) => vec![
+            ("winning_token_type", e.winning_token_type as u64),
+            ("real_yes_sol_reserves", e.real_yes_sol_reserves),
+            ("real_no_sol_reserves", e.real_no_sol_reserves),
+            ("total_lp_amount", e.total_lp_amount),
+        ],
+        ProgramEvent::Payout(e) => vec![
+            ("winning_token_type", e.winning_token_type as u64),
+            ("yes_amount", e.yes_amount),
+            ("no_amount", e.no_amount),
+            ("sol_amount", e.sol_amount),
+        ],
+        ProgramEvent::ParlayPool(e) => vec![
+            ("amount", e.amount),
+            ("is_withdrawal", e.is_withdrawal as u64),
+            ("liquidity", e.liquidity),
+            ("reserved", e.reserved),
+        ],
+        ProgramEvent::ParlayOpen(e) => vec![
+            ("parlay_id", e.parlay_id),
+            ("legs", e.legs.len() as u64),
+            ("stake", e.stake),
+            ("fee_lamports", e.fee_lamports),
+            ("payout", e.payout),
+        ],
+        //  the outcome as one flag per variant
+        ProgramEvent::ParlaySettle(e) => vec![
+            ("parlay_id", e.parlay_id),
+            ("won", (e.outcome == ParlayOutcome::Won) as u64),
+            ("lost", (e.outcome == ParlayOutcome::Lost) as u64),
+            ("voided", (e.outcome == ParlayOutcome::Voided) as u64),
+            ("paid", e.paid),
+        ],
+    }
+}
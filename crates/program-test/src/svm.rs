@@ -0,0 +1,373 @@
+use std::{
+    collections::{HashMap, HashSet},
+    mem::size_of,
+    panic::{self, AssertUnwindSafe},
+};
+
+use anchor_lang::{
+    prelude::{Clock, Pubkey, Rent},
+    solana_program::{
+        bpf_loader_upgradeable,
+        entrypoint::{self, BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER},
+        instruction::Instruction,
+        program_error::ProgramError,
+        system_program, sysvar,
+    },
+};
+
+use crate::runtime::{self, Processor};
+
+//  unix timestamp of slot 0, slots advance the clock by 400ms
+const GENESIS_TIMESTAMP: i64 = 1_700_000_000;
+const SLOT_MS: i64 = 400;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Account {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+impl Default for Account {
+    fn default() -> Self {
+        Self {
+            lamports: 0,
+            data: vec![],
+            owner: system_program::ID,
+            executable: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InstructionError {
+    Program(ProgramError),
+    //  the program panicked, e.g. on an arithmetic overflow
+    Panic(String),
+    MissingSigner(Pubkey),
+    ReadonlyModified(Pubkey),
+    UnbalancedLamports,
+    InsufficientFundsForRent(Pubkey),
+    UnknownProgram(Pubkey),
+    //  runtime limits, see `runtime`
+    ComputationalBudgetExceeded,
+    CallDepth,
+    ReentrancyNotAllowed,
+}
+
+#[derive(Debug)]
+pub struct TransactionError {
+    //  position of the failing instruction
+    pub index: usize,
+    pub error: InstructionError,
+    pub logs: Vec<String>,
+}
+
+impl TransactionError {
+    //  name of the Anchor error the program reported, e.g. `ReturnAmountTooSmall`
+    pub fn anchor_error(&self) -> Option<&str> {
+        self.logs.iter().find_map(|log| {
+            let (_, rest) = log.split_once("Error Code: ")?;
+            rest.split('.').next()
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TransactionMeta {
+    pub logs: Vec<String>,
+    //  payloads of the program's `emit_cpi!` self invocations, discriminator included
+    pub events: Vec<Vec<u8>>,
+    pub return_data: Option<(Pubkey, Vec<u8>)>,
+    //  syscall and invocation costs charged to the transaction's compute meter
+    pub compute_units_consumed: u64,
+}
+
+//  runs native builds of the program and its dependencies against an in-memory account store
+pub struct Svm {
+    accounts: HashMap<Pubkey, Account>,
+    programs: HashMap<Pubkey, Processor>,
+    clock: Clock,
+    rent: Rent,
+}
+
+impl Default for Svm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Svm {
+    pub fn new() -> Self {
+        let mut svm = Self {
+            accounts: HashMap::new(),
+            programs: HashMap::new(),
+            clock: Clock {
+                unix_timestamp: GENESIS_TIMESTAMP,
+                epoch_start_timestamp: GENESIS_TIMESTAMP,
+                ..Clock::default()
+            },
+            rent: Rent::default(),
+        };
+        for (program_id, processor) in runtime::builtins() {
+            svm.add_program(program_id, processor);
+        }
+        svm.set_sysvars();
+        svm
+    }
+
+    pub fn add_program(&mut self, program_id: Pubkey, processor: Processor) {
+        self.programs.insert(program_id, processor);
+        self.accounts.insert(
+            program_id,
+            Account {
+                lamports: 1,
+                data: vec![],
+                owner: bpf_loader_upgradeable::ID,
+                executable: true,
+            },
+        );
+    }
+
+    fn set_sysvars(&mut self) {
+        let sysvars = [
+            (sysvar::clock::ID, bincode::serialize(&self.clock)),
+            (sysvar::rent::ID, bincode::serialize(&self.rent)),
+        ];
+        for (address, data) in sysvars {
+            let data = data.expect("sysvars serialize");
+            self.accounts.insert(
+                address,
+                Account {
+                    lamports: self.rent.minimum_balance(data.len()),
+                    data,
+                    owner: sysvar::ID,
+                    executable: false,
+                },
+            );
+        }
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    pub fn rent(&self) -> &Rent {
+        &self.rent
+    }
+
+    pub fn warp_to_slot(&mut self, slot: u64) {
+        self.clock.unix_timestamp += (slot as i64 - self.clock.slot as i64) * SLOT_MS / 1000;
+        self.clock.slot = slot;
+        self.set_sysvars();
+    }
+
+    pub fn set_unix_timestamp(&mut self, unix_timestamp: i64) {
+        self.clock.unix_timestamp = unix_timestamp;
+        self.set_sysvars();
+    }
+
+    pub fn account(&self, address: &Pubkey) -> Option<&Account> {
+        self.accounts.get(address)
+    }
+
+    pub fn set_account(&mut self, address: Pubkey, account: Account) {
+        self.accounts.insert(address, account);
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.accounts
+            .get(address)
+            .map_or(0, |account| account.lamports)
+    }
+
+    pub fn airdrop(&mut self, address: &Pubkey, lamports: u64) {
+        self.accounts.entry(*address).or_default().lamports += lamports;
+    }
+
+    //  runs the instructions atomically, `signers` stand in for signatures
+    pub fn process_transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[Pubkey],
+    ) -> Result<TransactionMeta, TransactionError> {
+        let signers: HashSet<Pubkey> = signers.iter().copied().collect();
+        let mut working = self.accounts.clone();
+
+        runtime::begin(
+            self.programs.clone(),
+            self.clock.clone(),
+            self.rent,
+            instructions.len(),
+        );
+        for (index, instruction) in instructions.iter().enumerate() {
+            runtime::clear_return_data();
+            if let Err(error) = self.process_instruction(&mut working, instruction, &signers) {
+                let logs = runtime::finish().logs;
+                return Err(TransactionError { index, error, logs });
+            }
+        }
+
+        self.accounts = working;
+        Ok(runtime::finish())
+    }
+
+    fn process_instruction(
+        &self,
+        working: &mut HashMap<Pubkey, Account>,
+        instruction: &Instruction,
+        signers: &HashSet<Pubkey>,
+    ) -> Result<(), InstructionError> {
+        if !self.programs.contains_key(&instruction.program_id) {
+            return Err(InstructionError::UnknownProgram(instruction.program_id));
+        }
+
+        //  duplicated accounts share the union of their privileges
+        let mut keys: Vec<Pubkey> = vec![];
+        let mut privileges: HashMap<Pubkey, (bool, bool)> = HashMap::new();
+        for meta in &instruction.accounts {
+            if meta.is_signer && !signers.contains(&meta.pubkey) {
+                return Err(InstructionError::MissingSigner(meta.pubkey));
+            }
+            let entry = privileges.entry(meta.pubkey).or_insert_with(|| {
+                keys.push(meta.pubkey);
+                (false, false)
+            });
+            entry.0 |= meta.is_signer;
+            entry.1 |= meta.is_writable;
+        }
+
+        let pre: Vec<Account> = keys
+            .iter()
+            .map(|key| working.get(key).cloned().unwrap_or_default())
+            .collect();
+        let (mut buffer, offsets) = serialize(instruction, &keys, &pre, &privileges);
+
+        let processor = self.programs[&instruction.program_id];
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let (program_id, accounts, data) =
+                unsafe { entrypoint::deserialize(buffer.as_mut_ptr() as *mut u8) };
+            runtime::invoke(processor, program_id, &accounts, data)
+        }));
+        if let Some(fault) = runtime::take_fault() {
+            runtime::unwind();
+            return Err(fault);
+        }
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(error)) => return Err(InstructionError::Program(error)),
+            Err(panic) => {
+                runtime::unwind();
+                let message = panic
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+                    .unwrap_or_default();
+                return Err(InstructionError::Panic(message));
+            }
+        }
+
+        let post = deserialize_accounts(&buffer, &offsets);
+        let pre_lamports: u128 = pre.iter().map(|account| account.lamports as u128).sum();
+        let post_lamports: u128 = post.iter().map(|account| account.lamports as u128).sum();
+        if pre_lamports != post_lamports {
+            return Err(InstructionError::UnbalancedLamports);
+        }
+
+        for ((key, before), after) in keys.iter().zip(pre).zip(post) {
+            if before == after {
+                continue;
+            }
+            if !privileges[key].1 {
+                return Err(InstructionError::ReadonlyModified(*key));
+            }
+            //  an account may not newly become rent paying
+            let minimum = self.rent.minimum_balance(after.data.len());
+            let was_rent_paying = before.lamports > 0
+                && before.lamports < self.rent.minimum_balance(before.data.len());
+            if after.lamports > 0 && after.lamports < minimum && !was_rent_paying {
+                return Err(InstructionError::InsufficientFundsForRent(*key));
+            }
+            if after.lamports == 0 {
+                working.remove(key);
+            } else {
+                working.insert(*key, after);
+            }
+        }
+        Ok(())
+    }
+}
+
+//  lays the instruction out like the BPF loader's aligned input, returning the offset of each account's key
+fn serialize(
+    instruction: &Instruction,
+    keys: &[Pubkey],
+    accounts: &[Account],
+    privileges: &HashMap<Pubkey, (bool, bool)>,
+) -> (Vec<u64>, Vec<usize>) {
+    let mut bytes: Vec<u8> = vec![];
+    let mut offsets = vec![];
+    let mut positions: HashMap<Pubkey, usize> = HashMap::new();
+
+    bytes.extend_from_slice(&(instruction.accounts.len() as u64).to_le_bytes());
+    for (position, meta) in instruction.accounts.iter().enumerate() {
+        if let Some(first) = positions.get(&meta.pubkey) {
+            bytes.push(*first as u8);
+            bytes.extend_from_slice(&[0; 7]);
+            continue;
+        }
+        positions.insert(meta.pubkey, position);
+
+        let index = keys.iter().position(|key| key == &meta.pubkey).unwrap();
+        let account = &accounts[index];
+        let (is_signer, is_writable) = privileges[&meta.pubkey];
+
+        bytes.push(NON_DUP_MARKER);
+        bytes.push(is_signer as u8);
+        bytes.push(is_writable as u8);
+        bytes.push(account.executable as u8);
+        bytes.extend_from_slice(&(account.data.len() as u32).to_le_bytes());
+        offsets.push(bytes.len());
+        bytes.extend_from_slice(meta.pubkey.as_ref());
+        bytes.extend_from_slice(account.owner.as_ref());
+        bytes.extend_from_slice(&account.lamports.to_le_bytes());
+        bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&account.data);
+        bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        bytes.resize(bytes.len().next_multiple_of(BPF_ALIGN_OF_U128), 0);
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+    }
+    bytes.extend_from_slice(&(instruction.data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&instruction.data);
+    bytes.extend_from_slice(instruction.program_id.as_ref());
+
+    //  `deserialize` reads u64s in place, so the buffer has to be 8 byte aligned
+    let mut buffer = vec![0u64; bytes.len().div_ceil(size_of::<u64>())];
+    for (word, chunk) in buffer.iter_mut().zip(bytes.chunks(size_of::<u64>())) {
+        let mut padded = [0u8; 8];
+        padded[..chunk.len()].copy_from_slice(chunk);
+        *word = u64::from_le_bytes(padded);
+    }
+    (buffer, offsets)
+}
+
+fn deserialize_accounts(buffer: &[u64], offsets: &[usize]) -> Vec<Account> {
+    let bytes: Vec<u8> = buffer.iter().flat_map(|word| word.to_le_bytes()).collect();
+    let read_u64 = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+
+    offsets
+        .iter()
+        .map(|&key| {
+            let owner = Pubkey::try_from(&bytes[key + 32..key + 64]).unwrap();
+            let lamports = read_u64(key + 64);
+            let data_len = read_u64(key + 72) as usize;
+            Account {
+                lamports,
+                data: bytes[key + 80..key + 80 + data_len].to_vec(),
+                owner,
+                executable: bytes[key - 5] != 0,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,91 @@
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::{
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        system_instruction::{SystemError, SystemInstruction},
+        system_program,
+    },
+};
+
+//  the subset of the system program used by the program and its dependencies
+pub fn process(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let instruction: SystemInstruction =
+        bincode::deserialize(data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let account = |index: usize| {
+        accounts
+            .get(index)
+            .ok_or(ProgramError::NotEnoughAccountKeys)
+    };
+
+    match instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let (from, to) = (account(0)?, account(1)?);
+            if to.lamports() > 0 || !is_unallocated(to) {
+                return Err(error(SystemError::AccountAlreadyInUse));
+            }
+            transfer(from, to, lamports)?;
+            allocate(to, space)?;
+            assign(to, &owner)
+        }
+        SystemInstruction::Transfer { lamports } => transfer(account(0)?, account(1)?, lamports),
+        SystemInstruction::Allocate { space } => allocate(account(0)?, space),
+        SystemInstruction::Assign { owner } => assign(account(0)?, &owner),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn error(error: SystemError) -> ProgramError {
+    ProgramError::Custom(error as u32)
+}
+
+fn is_unallocated(account: &AccountInfo) -> bool {
+    account.data_is_empty() && account.owner == &system_program::ID
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if !from.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !from.data_is_empty() || from.owner != &system_program::ID {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let balance = from.lamports();
+    if balance < lamports {
+        return Err(error(SystemError::ResultWithNegativeLamports));
+    }
+    **from.try_borrow_mut_lamports()? = balance - lamports;
+    let to_balance = to.lamports();
+    **to.try_borrow_mut_lamports()? = to_balance
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+fn allocate(account: &AccountInfo, space: u64) -> ProgramResult {
+    if !account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !is_unallocated(account) {
+        return Err(error(SystemError::AccountAlreadyInUse));
+    }
+    account.realloc(space as usize, true)
+}
+
+fn assign(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if account.owner == owner {
+        return Ok(());
+    }
+    if !account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if account.owner != &system_program::ID {
+        return Err(error(SystemError::InvalidAccountDataLength));
+    }
+    account.assign(owner);
+    Ok(())
+}
@@ -0,0 +1,274 @@
+//! Composing with the program from another program, through the
+//! `examples/cpi-consumer` vault. The vault is a system-owned PDA of the
+//! consumer, so it can only sign through `invoke_signed`: every trade and
+//! payout here goes through `cpi_swap` and `cpi_redeem` rather than the
+//! wallet-facing `swap` and `resolution`.
+
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::{entrypoint::ProgramResult, instruction::Instruction, system_program},
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::token;
+use prediction_market::state::market::{DIRECTION_BUY, TOKEN_TYPE_NO, TOKEN_TYPE_YES};
+use prediction_market_client::{instructions, pda, MarketKeys};
+use prediction_market_program_test::Scenario;
+
+const VAULT_FUNDS: u64 = 10_000_000_000;
+
+fn consumer(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    //  Anchor ties the slice lifetime to the account lifetime
+    let accounts: &[AccountInfo] = unsafe { std::mem::transmute(accounts) };
+    cpi_consumer::entry(program_id, accounts, data)
+}
+
+struct Fund {
+    scenario: Scenario,
+    keys: MarketKeys,
+    authority: Pubkey,
+    vault: Pubkey,
+}
+
+impl Fund {
+    //  a seeded market, plus a funded vault holding token accounts for both outcomes
+    fn new() -> Self {
+        let mut scenario = Scenario::new();
+        scenario
+            .run(
+                "
+                configure
+                create market end +100
+                admin adds liquidity 10 SOL
+                ",
+            )
+            .unwrap();
+        scenario.svm.add_program(cpi_consumer::ID, consumer);
+
+        let keys = scenario.market_keys().unwrap();
+        let authority = scenario.actor("fund");
+        let vault = Pubkey::find_program_address(
+            &[cpi_consumer::VAULT, authority.as_ref()],
+            &cpi_consumer::ID,
+        )
+        .0;
+        scenario.svm.airdrop(&vault, VAULT_FUNDS);
+        let create_atas: Vec<Instruction> = [keys.yes_token, keys.no_token]
+            .iter()
+            .map(|mint| {
+                spl_associated_token_account::instruction::create_associated_token_account(
+                    &authority,
+                    &vault,
+                    mint,
+                    &token::ID,
+                )
+            })
+            .collect();
+        scenario.execute(&create_atas, &[authority]).unwrap();
+
+        Self {
+            scenario,
+            keys,
+            authority,
+            vault,
+        }
+    }
+
+    fn mint(&self, token_type: u8) -> Pubkey {
+        if token_type == TOKEN_TYPE_YES {
+            self.keys.yes_token
+        } else {
+            self.keys.no_token
+        }
+    }
+
+    fn trade_accounts(&self, token_type: u8) -> cpi_consumer::accounts::Trade {
+        cpi_consumer::accounts::Trade {
+            authority: self.authority,
+            vault: self.vault,
+            token_account: pda::user_ata(&self.vault, &self.mint(token_type)),
+            global_config: pda::config().0,
+            team_wallet: self.scenario.config().unwrap().team_wallet,
+            market: self.keys.market,
+            global_vault: pda::global_vault().0,
+            yes_token: self.keys.yes_token,
+            no_token: self.keys.no_token,
+            global_yes_ata: pda::vault_ata(&self.keys.yes_token),
+            global_no_ata: pda::vault_ata(&self.keys.no_token),
+            user_info: pda::user_info(&self.vault, &self.keys.market).0,
+            user_stats: pda::user_stats(&self.vault).0,
+            event_authority: pda::event_authority().0,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            prediction_market: prediction_market::ID,
+        }
+    }
+
+    fn call(
+        &mut self,
+        accounts: impl ToAccountMetas,
+        data: impl InstructionData,
+    ) -> Result<(), String> {
+        let ix = Instruction {
+            program_id: cpi_consumer::ID,
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        };
+        self.scenario
+            .execute(&[ix], &[self.authority])
+            .map(|_| ())
+            .map_err(|error| {
+                error
+                    .anchor_error()
+                    .map_or_else(|| format!("{:?}", error.error), str::to_string)
+            })
+    }
+
+    fn buy(&mut self, amount: u64, token_type: u8, max_price: u64) -> Result<(), String> {
+        self.call(
+            self.trade_accounts(token_type),
+            cpi_consumer::instruction::Buy {
+                amount,
+                token_type,
+                max_price,
+            },
+        )
+    }
+
+    fn sell(&mut self, amount: u64, token_type: u8) -> Result<(), String> {
+        self.call(
+            self.trade_accounts(token_type),
+            cpi_consumer::instruction::Sell {
+                amount,
+                token_type,
+                minimum_receive_amount: 0,
+            },
+        )
+    }
+
+    fn redeem(&mut self) -> Result<(), String> {
+        self.call(
+            cpi_consumer::accounts::Redeem {
+                authority: self.authority,
+                vault: self.vault,
+                market: self.keys.market,
+                global_vault: pda::global_vault().0,
+                user_info: pda::user_info(&self.vault, &self.keys.market).0,
+                yes_token: self.keys.yes_token,
+                no_token: self.keys.no_token,
+                yes_token_account: pda::user_ata(&self.vault, &self.keys.yes_token),
+                no_token_account: pda::user_ata(&self.vault, &self.keys.no_token),
+                event_authority: pda::event_authority().0,
+                system_program: system_program::ID,
+                token_program: token::ID,
+                prediction_market: prediction_market::ID,
+            },
+            cpi_consumer::instruction::Redeem {},
+        )
+    }
+}
+
+#[test]
+fn a_pda_vault_trades_and_redeems_through_cpi() {
+    let mut fund = Fund::new();
+    let vault_ata = pda::user_ata(&fund.vault, &fund.keys.yes_token);
+
+    //  the quote read through return data prices 1 SOL at about 6000 lamports per 1e9 tokens
+    assert_eq!(
+        fund.buy(1_000_000_000, TOKEN_TYPE_YES, 5_000),
+        Err("PriceAboveLimit".to_string())
+    );
+    fund.buy(1_000_000_000, TOKEN_TYPE_YES, 10_000).unwrap();
+
+    let position = fund.scenario.position(&fund.vault).unwrap();
+    assert_eq!(position.user, fund.vault);
+    assert_eq!(position.yes_balance, 164_996_659_986_639);
+    assert_eq!(
+        fund.scenario
+            .token_balance(&fund.vault, &fund.keys.yes_token),
+        position.yes_balance
+    );
+    assert!(fund.scenario.svm.account(&vault_ata).is_some());
+    assert_eq!(
+        fund.scenario.svm.lamports(&fund.vault),
+        VAULT_FUNDS - 1_000_000_000
+    );
+
+    let lamports = fund.scenario.svm.lamports(&fund.vault);
+    fund.sell(position.yes_balance / 2, TOKEN_TYPE_YES).unwrap();
+    assert!(fund.scenario.svm.lamports(&fund.vault) > lamports);
+
+    fund.scenario
+        .run(
+            "
+            alice buys 1 SOL YES
+            warp past ending_slot
+            resolve YES
+            ",
+        )
+        .unwrap();
+    //  the authority confirms the outcome with the first payout
+    assert_eq!(fund.redeem(), Err("InvalidMarketStatus".to_string()));
+    fund.scenario.run("alice redeems").unwrap();
+
+    let authority_lamports = fund.scenario.svm.lamports(&fund.authority);
+    fund.redeem().unwrap();
+    assert!(fund.scenario.svm.lamports(&fund.authority) > authority_lamports);
+    let position = fund.scenario.position(&fund.vault).unwrap();
+    assert_eq!(position.yes_balance, 0);
+    //  the redeemed tokens are burned, not left to be sold back
+    assert_eq!(
+        fund.scenario
+            .token_balance(&fund.vault, &fund.keys.yes_token),
+        0
+    );
+    fund.scenario
+        .run("expect event payout winning_token_type = 1 sol_amount > 0")
+        .unwrap();
+}
+
+#[test]
+fn cpi_swap_only_accepts_token_accounts_of_the_traded_outcome() {
+    let mut fund = Fund::new();
+    let no_account = pda::user_ata(&fund.vault, &fund.keys.no_token);
+    let team_wallet = fund.scenario.config().unwrap().team_wallet;
+
+    //  called directly, a wallet owner can trade into any token account it names
+    let owner = fund.scenario.actor("owner");
+    let payer = fund.scenario.actor("payer");
+    let ix = instructions::cpi_swap(
+        &owner,
+        &payer,
+        &no_account,
+        &fund.keys,
+        &team_wallet,
+        1_000_000_000,
+        DIRECTION_BUY,
+        TOKEN_TYPE_YES,
+        0,
+        None,
+        None,
+    );
+    let error = fund.scenario.execute(&[ix], &[owner, payer]).unwrap_err();
+    assert_eq!(error.anchor_error(), Some("InvalidTokenAccount"));
+
+    let ix = instructions::cpi_swap(
+        &owner,
+        &payer,
+        &no_account,
+        &fund.keys,
+        &team_wallet,
+        1_000_000_000,
+        DIRECTION_BUY,
+        TOKEN_TYPE_NO,
+        0,
+        None,
+        None,
+    );
+    fund.scenario.execute(&[ix], &[owner, payer]).unwrap();
+    assert!(fund.scenario.position(&owner).unwrap().no_balance > 0);
+    assert_eq!(
+        fund.scenario
+            .token_balance(&fund.vault, &fund.keys.no_token),
+        fund.scenario.position(&owner).unwrap().no_balance
+    );
+}
@@ -0,0 +1,303 @@
+//! Solvency invariants over random trade sequences run through the program.
+//! Failing sequences are shrunk by proptest and persisted next to this file
+//! so they rerun first.
+
+use anchor_lang::prelude::Pubkey;
+use prediction_market::state::market::{
+    DIRECTION_BUY, DIRECTION_SELL, TOKEN_TYPE_NO, TOKEN_TYPE_YES,
+};
+use prediction_market_client::{instructions, pda, MarketKeys};
+use prediction_market_program_test::Scenario;
+use proptest::prelude::*;
+
+const TRADERS: [&str; 3] = ["alice", "bob", "carol"];
+const LP_AMOUNT: u64 = 10_000_000_000;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Buy {
+        trader: usize,
+        token_type: u8,
+        lamports: u64,
+    },
+    //  sells `percent` of the trader's balance
+    Sell {
+        trader: usize,
+        token_type: u8,
+        percent: u64,
+    },
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let token_type = prop_oneof![Just(TOKEN_TYPE_NO), Just(TOKEN_TYPE_YES)];
+    prop_oneof![
+        (0..TRADERS.len(), token_type.clone(), 1..=50_000_000_000u64).prop_map(
+            |(trader, token_type, lamports)| Op::Buy {
+                trader,
+                token_type,
+                lamports,
+            }
+        ),
+        (0..TRADERS.len(), token_type, 1..=100u64).prop_map(|(trader, token_type, percent)| {
+            Op::Sell {
+                trader,
+                token_type,
+                percent,
+            }
+        }),
+    ]
+}
+
+struct Harness {
+    scenario: Scenario,
+    keys: MarketKeys,
+    team_wallet: Pubkey,
+    traders: Vec<Pubkey>,
+    //  vault lamports before liquidity was added
+    vault_base: u64,
+    //  trader lamports before the first trade
+    traders_base: u64,
+}
+
+impl Harness {
+    fn new(fees: [u64; 4]) -> Self {
+        let [platform_buy, platform_sell, lp_buy, lp_sell] = fees;
+        let mut scenario = Scenario::new();
+        scenario
+            .run(&format!(
+                "configure platform_buy_fee={platform_buy} platform_sell_fee={platform_sell} \
+                 lp_buy_fee={lp_buy} lp_sell_fee={lp_sell}
+                 create market"
+            ))
+            .unwrap();
+        let vault_base = scenario.svm.lamports(&pda::global_vault().0);
+        scenario
+            .run(&format!("admin adds liquidity {LP_AMOUNT}"))
+            .unwrap();
+
+        let traders: Vec<Pubkey> = TRADERS.iter().map(|name| scenario.actor(name)).collect();
+        let traders_base = traders.iter().map(|t| scenario.svm.lamports(t)).sum();
+        Self {
+            keys: scenario.market_keys().unwrap(),
+            team_wallet: scenario.config().unwrap().team_wallet,
+            scenario,
+            traders,
+            vault_base,
+            traders_base,
+        }
+    }
+
+    fn mint(&self, token_type: u8) -> Pubkey {
+        if token_type == TOKEN_TYPE_YES {
+            self.keys.yes_token
+        } else {
+            self.keys.no_token
+        }
+    }
+
+    fn apply(&mut self, op: &Op) {
+        let (user, amount, direction, token_type) = match *op {
+            Op::Buy {
+                trader,
+                token_type,
+                lamports,
+            } => (self.traders[trader], lamports, DIRECTION_BUY, token_type),
+            Op::Sell {
+                trader,
+                token_type,
+                percent,
+            } => {
+                let user = self.traders[trader];
+                let balance = self.scenario.token_balance(&user, &self.mint(token_type));
+                (user, balance * percent / 100, DIRECTION_SELL, token_type)
+            }
+        };
+        let ix = instructions::swap(
+            &user,
+            &self.keys,
+            &self.team_wallet,
+            amount,
+            direction,
+            token_type,
+            0,
+            None,
+            None,
+            None,
+        );
+        //  rejected trades are fine, the invariants must hold either way
+        let _ = self.scenario.execute(&[ix], &[user]);
+    }
+
+    //  tokens the program will pay out on, redeemed tokens stay in the wallet
+    fn claimable(&self, trader: &Pubkey, token_type: u8) -> u64 {
+        self.scenario.position(trader).map_or(0, |position| {
+            if token_type == TOKEN_TYPE_YES {
+                position.yes_balance
+            } else {
+                position.no_balance
+            }
+        })
+    }
+
+    fn traders_lamports(&self) -> u64 {
+        self.traders
+            .iter()
+            .map(|trader| self.scenario.svm.lamports(trader))
+            .sum()
+    }
+
+    //  token conservation is checked on `sides`, losing tokens leave the books on redemption
+    fn check(&self, sides: &[u8]) -> Result<(), TestCaseError> {
+        let market = self.scenario.market().unwrap();
+        let vault = self.scenario.svm.lamports(&pda::global_vault().0);
+        let reserves = market.real_yes_sol_reserves + market.real_no_sol_reserves;
+
+        //  reserves never underflow and tokens are conserved
+        for &token_type in sides {
+            let (real, supply) = if token_type == TOKEN_TYPE_YES {
+                (
+                    market.real_yes_token_reserves,
+                    market.token_yes_total_supply,
+                )
+            } else {
+                (market.real_no_token_reserves, market.token_no_total_supply)
+            };
+            prop_assert!(real <= supply);
+            let held: u64 = self
+                .traders
+                .iter()
+                .map(|trader| self.claimable(trader, token_type))
+                .sum();
+            prop_assert_eq!(
+                held,
+                supply - real,
+                "circulating tokens of side {}",
+                token_type
+            );
+        }
+
+        //  the vault covers every winning claim plus LP principal and fees, whichever side wins
+        prop_assert!(vault >= self.vault_base + reserves + market.lp_fees);
+        prop_assert!(reserves >= market.total_lp_amount);
+        for token_type in [TOKEN_TYPE_NO, TOKEN_TYPE_YES] {
+            let claims: u64 = self
+                .traders
+                .iter()
+                .map(|trader| {
+                    let balance = self.claimable(trader, token_type);
+                    market.get_payout(balance, token_type).unwrap()
+                })
+                .sum();
+            prop_assert!(
+                vault >= self.vault_base + market.total_lp_amount + market.lp_fees + claims
+            );
+        }
+
+        //  SOL flows of a position are either realized or still carried as cost basis
+        let mut open_cost_basis = 0;
+        for trader in &self.traders {
+            if let Ok(position) = self.scenario.position(trader) {
+                let cost_basis = position.yes_cost_basis as i128 + position.no_cost_basis as i128;
+                prop_assert_eq!(
+                    position.total_sol_received as i128 - position.total_sol_spent as i128,
+                    position.realized_pnl as i128 - cost_basis
+                );
+                open_cost_basis += cost_basis;
+            }
+        }
+        prop_assert_eq!(open_cost_basis, market.open_cost_basis as i128);
+
+        //  traders as a whole never extract SOL from the pool
+        prop_assert!(self.traders_lamports() <= self.traders_base);
+        Ok(())
+    }
+
+    //  resolve, pay every trader, then return the LP principal
+    fn settle(&mut self, winner: u8) -> Result<(), TestCaseError> {
+        let outcome = if winner == TOKEN_TYPE_YES {
+            "YES"
+        } else {
+            "NO"
+        };
+        self.scenario.run(&format!("resolve {outcome}")).unwrap();
+        for name in TRADERS {
+            let user = self.scenario.actor(name);
+            if self.scenario.position(&user).is_ok() {
+                self.scenario.run(&format!("{name} redeems")).unwrap();
+                self.check(&[winner])?;
+            }
+        }
+        self.close()
+    }
+
+    //  void, refund every trader at most their cost, then return the LP principal
+    fn void(&mut self) -> Result<(), TestCaseError> {
+        self.scenario.run("void market").unwrap();
+        for name in TRADERS {
+            let user = self.scenario.actor(name);
+            if let Ok(position) = self.scenario.position(&user) {
+                let before = self.scenario.svm.lamports(&user);
+                self.scenario.run(&format!("{name} refunds")).unwrap();
+                let refund = self.scenario.svm.lamports(&user) - before;
+                prop_assert!(refund <= position.yes_cost_basis + position.no_cost_basis);
+                self.check(&[])?;
+            }
+        }
+        self.close()
+    }
+
+    fn close(&mut self) -> Result<(), TestCaseError> {
+        let lp = self.scenario.actor("admin");
+        let lp_before = self.scenario.svm.lamports(&lp);
+        let lp_fees = self.scenario.market().unwrap().lp_fees;
+        self.scenario
+            .run(&format!("admin withdraws liquidity {LP_AMOUNT}"))
+            .unwrap();
+        let lp_after = self.scenario.svm.lamports(&lp);
+        prop_assert_eq!(lp_after - lp_before, LP_AMOUNT + lp_fees);
+
+        //  only rounding dust is left for the team wallet, a proposal nobody
+        //  redeemed on is never confirmed
+        let vault = self.scenario.svm.lamports(&pda::global_vault().0);
+        prop_assert!(vault >= self.vault_base);
+        if self.scenario.market().unwrap().status.is_final() {
+            self.scenario
+                .run("admin archives market; admin closes market")
+                .unwrap();
+            let vault = self.scenario.svm.lamports(&pda::global_vault().0);
+            prop_assert_eq!(vault, self.vault_base);
+        }
+        prop_assert!(self.traders_lamports() <= self.traders_base);
+        Ok(())
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(48))]
+
+    #[test]
+    fn trading_and_settlement_stay_solvent(
+        fees in [0..=500u64, 0..=500u64, 0..=500u64, 0..=500u64],
+        ops in prop::collection::vec(op(), 1..24),
+        winner in prop_oneof![Just(TOKEN_TYPE_NO), Just(TOKEN_TYPE_YES)],
+    ) {
+        let mut harness = Harness::new(fees);
+        for op in &ops {
+            harness.apply(op);
+            harness.check(&[TOKEN_TYPE_NO, TOKEN_TYPE_YES])?;
+        }
+        harness.settle(winner)?;
+    }
+
+    #[test]
+    fn voided_markets_refund_at_most_cost(
+        fees in [0..=500u64, 0..=500u64, 0..=500u64, 0..=500u64],
+        ops in prop::collection::vec(op(), 1..24),
+    ) {
+        let mut harness = Harness::new(fees);
+        for op in &ops {
+            harness.apply(op);
+        }
+        harness.void()?;
+    }
+}
@@ -0,0 +1,94 @@
+//! Runtime limits of `Svm`: the compute meter, the invocation depth and the
+//! reentrancy rule, driven by small native programs that misbehave on purpose.
+
+use anchor_lang::{
+    prelude::{msg, AccountInfo, Pubkey},
+    solana_program::{
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction},
+        program::invoke,
+    },
+};
+use prediction_market_program_test::{InstructionError, Svm};
+
+//  invokes the program named by the first account with the remaining ones, until none are left
+fn relay(_program_id: &Pubkey, accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let Some((next, rest)) = accounts.split_first() else {
+        return Ok(());
+    };
+    let instruction = Instruction::new_with_bytes(
+        *next.key,
+        &[],
+        rest.iter()
+            .map(|account| AccountMeta::new_readonly(*account.key, false))
+            .collect(),
+    );
+    invoke(&instruction, rest)
+}
+
+//  logs until the meter runs dry
+fn chatty(_program_id: &Pubkey, _accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    for _ in 0..10_000 {
+        msg!("still here");
+    }
+    Ok(())
+}
+
+//  a top level call into `first`, passing each of `hops` as the next program to relay to
+fn run(svm: &mut Svm, first: Pubkey, hops: &[Pubkey]) -> Result<u64, InstructionError> {
+    let instruction = Instruction::new_with_bytes(
+        first,
+        &[],
+        hops.iter()
+            .map(|program| AccountMeta::new_readonly(*program, false))
+            .collect(),
+    );
+    svm.process_transaction(&[instruction], &[])
+        .map(|meta| meta.compute_units_consumed)
+        .map_err(|error| error.error)
+}
+
+#[test]
+fn nested_invocations_stop_at_the_stack_height() {
+    let mut svm = Svm::new();
+    let [a, b, c, d, e, f] = [(); 6].map(|_| Pubkey::new_unique());
+    for program in [a, b, c, d, e, f] {
+        svm.add_program(program, relay);
+    }
+
+    let consumed = run(&mut svm, a, &[b, c, d, e]).unwrap();
+    assert_eq!(consumed, 4 * 1_000);
+    assert!(matches!(
+        run(&mut svm, a, &[b, c, d, e, f]),
+        Err(InstructionError::CallDepth)
+    ));
+}
+
+#[test]
+fn programs_are_only_reentered_by_themselves() {
+    let mut svm = Svm::new();
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    svm.add_program(a, relay);
+    svm.add_program(b, relay);
+
+    run(&mut svm, a, &[a]).unwrap();
+    assert!(matches!(
+        run(&mut svm, a, &[b, a]),
+        Err(InstructionError::ReentrancyNotAllowed)
+    ));
+}
+
+#[test]
+fn the_compute_meter_is_shared_by_the_transaction() {
+    let mut svm = Svm::new();
+    let program = Pubkey::new_unique();
+    svm.add_program(program, chatty);
+
+    let error = svm
+        .process_transaction(&[Instruction::new_with_bytes(program, &[], vec![])], &[])
+        .unwrap_err();
+    assert!(matches!(
+        error.error,
+        InstructionError::ComputationalBudgetExceeded
+    ));
+}
@@ -0,0 +1,1077 @@
+use prediction_market_client::pda;
+use prediction_market_program_test::Scenario;
+
+fn run(script: &str) -> Scenario {
+    let mut scenario = Scenario::new();
+    if let Err(error) = scenario.run(script) {
+        panic!("{error:?}");
+    }
+    scenario
+}
+
+//  a configured, seeded market that alice and bob can trade on
+const SEEDED: &str = "
+    configure
+    create market end +100
+    admin adds liquidity 10 SOL
+";
+
+#[test]
+fn configure_and_hand_over_authority() {
+    run("
+        configure platform_buy_fee=200 lp_sell_fee=50
+        expect event config_update platform_buy_fee = 200 lp_sell_fee = 50
+
+        carol accepts authority fails IncorrectAuthority
+        nominate carol
+        expect event nominate_authority
+        carol accepts authority
+        expect event accept_authority
+
+        # the old authority can no longer reconfigure
+        configure lp_buy_fee=10 fails IncorrectAuthority
+    ");
+}
+
+#[test]
+fn configure_rejects_reserves_above_supply() {
+    run("configure initial_real_token_reserves=1000000000000001 fails InvalidAmount");
+}
+
+#[test]
+fn configure_rejects_fees_above_the_traded_amount() {
+    run("
+        configure platform_buy_fee=10001 lp_buy_fee=0 fails InvalidAmount
+        configure platform_sell_fee=9990 lp_sell_fee=11 fails InvalidAmount
+        configure lp_buy_fee=18446744073709551615 fails InvalidAmount
+        configure platform_buy_fee=9990 lp_buy_fee=10
+    ");
+}
+
+#[test]
+fn create_market_emits_both_mints() {
+    run("
+        configure
+        create market start +10 end +100
+        expect event mint_no_token token_no_total_supply = 1000000000000000
+        expect event create token_yes_total_supply = 1000000000000000 ending_slot > 0
+        # two instructions, so the transaction gets twice the default budget
+        expect compute_units < 400000
+        expect market total_lp_amount = 0
+        create market start +10 end +5 fails InvalidEndTime
+    ");
+}
+
+#[test]
+fn each_no_mint_backs_a_single_market() {
+    run("
+        configure
+        create market end +100
+        # the NO side of market 0 cannot be shared by a second market
+        create market end +100 no_token 0 fails InvalidNoToken
+    ");
+}
+
+#[test]
+fn trading_waits_for_liquidity_and_start_slot() {
+    run("
+        configure
+        create market start +10 end +100
+        alice buys 1 SOL YES fails InvalidMarketStatus
+        admin adds liquidity 10 SOL
+        alice buys 1 SOL YES fails InvalidMarketStatus
+        warp to start_slot
+        alice buys 1 SOL YES
+        expect event trade is_buy = 1 is_yes_no = 1 sol_amount = 1 SOL
+    ");
+}
+
+#[test]
+fn buy_and_sell_both_outcomes() {
+    run(&format!(
+        "{SEEDED}
+        alice buys 1 SOL YES
+        expect alice yes > 0
+        expect alice position yes_balance > 0
+        expect event trade is_buy = 1 fee_lamports = 0.012 SOL
+        bob buys 2 SOL NO
+        expect bob no > 0
+        expect event trade is_buy = 1 is_yes_no = 0
+
+        alice sells all YES
+        expect alice yes = 0
+        expect alice position yes_balance = 0
+        expect alice sol < 1000 SOL
+        expect event trade is_buy = 0 is_yes_no = 1
+        bob sells all NO
+        expect bob no = 0
+        expect team sol > 1000 SOL
+    "
+    ));
+}
+
+#[test]
+fn slippage_protection() {
+    run(&format!(
+        "{SEEDED}
+        alice buys 1 SOL YES min 1000000000000000 fails ReturnAmountTooSmall
+        alice buys 1 SOL YES
+        alice sells all YES min 1 SOL fails ReturnAmountTooSmall
+        expect alice position yes_balance > 0
+    "
+    ));
+}
+
+#[test]
+fn trade_limits_stay_within_config_bounds() {
+    run("
+        configure max_position_per_wallet=1..1000000000000000 max_trade_lamports=..5000000000
+        # an unlimited market is out of bounds once the config caps it
+        create market end +100 fails ValueTooLarge
+        create market end +100 max_position 1000000000000000 max_trade 6000000000 fails ValueTooLarge
+        create market end +100 max_position 1000 min_trade 2000000000 max_trade 1000000000 fails InvalidTradeLimits
+        create market end +100 max_position 1000000000000000 min_trade 100000000 max_trade 2000000000
+        expect market max_trade_lamports = 2 SOL
+        admin adds liquidity 10 SOL
+
+        alice buys 0.05 SOL YES fails TradeTooSmall
+        alice buys 3 SOL YES fails TradeTooLarge
+        alice buys 1 SOL YES
+
+        bob sets trade limits max_position 1000 fails IncorrectAuthority
+        admin sets trade limits max_trade 9000000000 fails ValueTooLarge
+        admin sets trade limits max_position 1000
+        expect event trade_limits max_position_per_wallet = 1000 max_trade_lamports = 2 SOL
+        alice buys 1 SOL YES fails PositionLimitExceeded
+        bob buys 0.1 SOL NO fails PositionLimitExceeded
+
+        # positions above a lowered cap can still be sold, dust included
+        alice sells 1000000000 YES fails TradeTooSmall
+        alice sells all YES
+        expect alice position yes_balance = 0
+    ");
+}
+
+#[test]
+fn swaps_reject_late_landing_and_high_price_impact() {
+    run("
+        configure
+        create market end +100
+        admin adds liquidity 10 SOL
+        warp +10
+
+        alice buys 1 SOL YES until 5 fails TransactionExpired
+        alice buys 1 SOL YES until +0 max_impact 100 fails PriceImpactTooHigh
+        alice buys 0.01 SOL YES until +0 max_impact 100
+        alice buys 1 SOL YES max_impact 5000
+        alice sells 1000000000 YES max_impact 0 fails PriceImpactTooHigh
+        alice sells 1000000000 YES until +1 max_impact 100
+    ");
+}
+
+#[test]
+fn twap_weights_prices_by_the_slots_they_held() {
+    run("
+        configure
+        create market end +1000
+        admin adds liquidity 10 SOL
+        observe 10 fails ObservationTooOld
+        warp +10
+
+        alice buys 1 SOL YES
+        warp +100
+        bob buys 0.01 SOL NO
+        observe 100
+        expect twap yes_price = 589195135
+        expect twap no_price = 410804865
+
+        # a price pushed within the current slot has not held for any slot yet
+        carol buys 50 SOL YES
+        observe 100
+        expect twap yes_price = 589195135
+        warp +10
+        observe 100
+        expect twap yes_price > 600000000
+        observe 111 fails ObservationTooOld
+    ");
+}
+
+#[test]
+fn twap_history_is_a_ring_buffer() {
+    let mut script = String::from("configure; create market end +1000; admin adds liquidity 10 SOL");
+    //  trades spaced apart each keep an observation, the first ones get overwritten
+    for _ in 0..20 {
+        script.push_str("\nwarp +11; alice buys 0.01 SOL YES");
+    }
+    script.push_str("\nobserve 165\nobserve 166 fails ObservationTooOld");
+    run(&script);
+}
+
+#[test]
+fn quotes_match_the_swap_that_follows() {
+    run("
+        configure
+        create market end +100
+        alice quotes buying 1 SOL YES fails InvalidMarketStatus
+        admin adds liquidity 10 SOL
+
+        alice quotes buying 1 SOL YES
+        expect quote token_amount = 164996659986639
+        expect quote amount_out = 164996659986639
+        expect quote platform_fee = 0.01 SOL
+        expect quote lp_fee = 0.002 SOL
+        expect market real_yes_sol_reserves = 5 SOL
+        alice buys 1 SOL YES
+        expect event trade token_amount = 164996659986639 fee_lamports = 0.012 SOL
+
+        alice quotes selling 164996659986639 YES min 2 SOL fails ReturnAmountTooSmall
+        alice quotes selling 164996659986639 YES max_impact 10 fails PriceImpactTooHigh
+        alice quotes selling 164996659986639 YES
+        expect quote amount_out = 976143999
+        alice sells 164996659986639 YES
+        expect event trade sol_amount = 976143999
+    ");
+}
+
+#[test]
+fn referrers_earn_a_share_of_the_platform_fee() {
+    run("
+        configure referral_fee_bps=2500
+        create market end +100
+        admin adds liquidity 10 SOL
+        alice buys 1 SOL YES via frontend fails AccountNotInitialized
+
+        # only the config authority approves referrers, a trader cannot name themselves
+        alice registers as referrer by alice fails IncorrectAuthority
+        alice buys 1 SOL YES via alice fails AccountNotInitialized
+        frontend registers as referrer
+        expect event register_referrer
+
+        # a quarter of the 0.01 SOL platform fee
+        alice buys 1 SOL YES via frontend
+        expect event trade fee_lamports = 0.012 SOL referral_fee = 0.0025 SOL
+        expect frontend referrer unclaimed = 0.0025 SOL
+        alice sells all YES via frontend
+        expect event trade referral_fee > 0
+        bob buys 1 SOL NO
+        expect event trade referral_fee = 0
+
+        frontend claims referral fees
+        expect event referral_claim amount > 0.0025 SOL
+        expect frontend referrer unclaimed = 0
+        expect frontend referrer total_earned > 0.0025 SOL
+        expect frontend sol > 1000 SOL
+        frontend claims referral fees fails InvalidAmount
+        configure referral_fee_bps=10001 fails InvalidAmount
+    ");
+}
+
+#[test]
+fn high_volume_traders_pay_the_fees_of_their_tier() {
+    run("
+        configure fee_tiers=5000000000:50:40,5000000000:20:20 fails InvalidFeeTiers
+        configure fee_tiers=5000000000:9990:40 fails InvalidFeeTiers
+        configure fee_tiers=5000000000:50:40,20000000000:20:20
+        create market end +100
+        admin adds liquidity 10 SOL
+
+        # the tier follows the volume before the swap
+        alice buys 3 SOL YES
+        expect event trade fee_lamports = 0.036 SOL
+        alice buys 3 SOL NO
+        expect event trade fee_lamports = 0.036 SOL
+        expect alice stats volume = 6 SOL
+        alice quotes buying 1 SOL YES
+        expect quote platform_fee = 0.005 SOL
+        bob quotes buying 1 SOL YES
+        expect quote platform_fee = 0.01 SOL
+        alice buys 1 SOL YES
+        expect event trade fee_lamports = 0.007 SOL
+        alice sells all YES
+        expect alice stats volume > 7 SOL
+
+        # counted for 30 days, the day of the trade included
+        warp +2505600s
+        expect alice stats volume > 7 SOL
+        warp +86400s
+        expect alice stats volume = 0
+        expect alice stats total_volume > 7 SOL
+        alice buys 1 SOL NO
+        expect event trade fee_lamports = 0.012 SOL
+
+        configure fee_tiers=none
+        bob buys 1 SOL YES
+        expect event trade fee_lamports = 0.012 SOL
+    ");
+}
+
+#[test]
+fn batch_swaps_open_positions_at_prefunded_addresses() {
+    let mut scenario = run(SEEDED);
+    let market = scenario.market_keys().unwrap().market;
+    let alice = scenario.actor("alice");
+
+    //  lamports sent to the position address ahead of the first trade do not block it
+    scenario
+        .svm
+        .airdrop(&pda::user_info(&alice, &market).0, 1_000_000);
+    scenario
+        .run(
+            "
+            alice batch buys 1 SOL YES on 0
+            expect alice position yes_balance > 0
+            ",
+        )
+        .unwrap();
+}
+
+#[test]
+fn batch_swaps_trade_several_markets_atomically() {
+    run("
+        configure
+        create market end +100
+        admin adds liquidity 10 SOL
+        create market end +100
+        admin adds liquidity 10 SOL
+        create market end +100
+
+        alice batch buys 1 SOL YES on 0, buys 2 SOL NO on 1, buys 1 SOL NO on 1
+        expect event trade sol_amount = 1 SOL is_yes_no = 1
+        expect event trade sol_amount = 2 SOL is_yes_no = 0
+        expect event trade sol_amount = 1 SOL is_yes_no = 0
+        expect alice stats volume = 4 SOL
+        use market 0
+        expect alice position yes_balance > 0
+        use market 1
+        expect alice position no_balance > 0
+
+        # one failing leg reverts the others
+        alice batch sells all NO on 1, buys 1 SOL YES on 0 min 999999999999999 fails ReturnAmountTooSmall
+        alice batch sells all NO on 1, buys 1 SOL YES on 2 fails InvalidMarketStatus
+        alice batch sells all NO on 1, buys 5 SOL YES on 0 max_impact 10 fails PriceImpactTooHigh
+        expect alice position no_balance > 0
+        expect alice stats volume = 4 SOL
+
+        alice batch sells all NO on 1, sells all YES on 0
+        expect event trade is_buy = 0 is_yes_no = 0
+        expect event trade is_buy = 0 is_yes_no = 1
+        expect alice position no_balance = 0
+        use market 0
+        expect alice position yes_balance = 0
+    ");
+}
+
+#[test]
+fn batch_swaps_take_the_deadline_and_referrer_of_swap() {
+    run("
+        configure referral_fee_bps=2500
+        create market end +100
+        admin adds liquidity 10 SOL
+        create market end +100
+        admin adds liquidity 10 SOL
+        frontend registers as referrer
+        warp +10
+
+        alice batch buys 1 SOL YES on 0 until 5, buys 1 SOL NO on 1 fails TransactionExpired
+        alice batch buys 1 SOL YES on 0 via alice, buys 1 SOL NO on 1 fails AccountNotInitialized
+        alice batch buys 1 SOL YES on 0 until +1, buys 1 SOL NO on 1 via frontend
+        expect event trade referral_fee = 0.0025 SOL is_yes_no = 1
+        expect event trade referral_fee = 0.0025 SOL is_yes_no = 0
+        expect frontend referrer unclaimed = 0.005 SOL
+    ");
+}
+
+#[test]
+fn parlays_pay_out_only_if_every_leg_wins() {
+    run("
+        configure
+        create market end +100
+        admin adds liquidity 10 SOL
+        create market end +100
+        admin adds liquidity 10 SOL
+        create market end +100
+        admin adds liquidity 10 SOL
+        create market end +100
+
+        alice parlays 1 SOL on 0 YES, 1 NO fails AccountNotInitialized
+        admin funds parlay pool 2 SOL
+        alice parlays 1 SOL on 0 YES fails InvalidParlay
+        alice parlays 1 SOL on 0 YES, 0 NO fails InvalidParlay
+        alice parlays 1 SOL on 0 YES, 3 NO fails InvalidMarketStatus
+        alice parlays 1 SOL on 0 YES, 1 NO min 5 SOL fails ReturnAmountTooSmall
+        alice parlays 1 SOL on 0 YES, 1 NO fails InsufficientParlayLiquidity
+        bob funds parlay pool 1 SOL fails IncorrectAuthority
+        admin funds parlay pool 11 SOL
+        admin withdraws parlay pool 1 SOL
+        expect parlay pool liquidity = 12 SOL
+
+        # 50/50 markets pay four times the stake on two legs, the fee comes on top
+        alice parlays 1 SOL on 0 YES, 1 NO min 4 SOL
+        expect event parlay_open parlay_id = 0 payout = 4 SOL fee_lamports = 0.01 SOL
+        expect parlay 0 legs = 2
+        bob parlays 1 SOL on 0 YES, 1 YES
+        carol parlays 1 SOL on 0 YES, 2 NO
+        erin parlays 1 SOL on 0 NO, 2 YES
+        expect parlay pool reserved = 16 SOL
+        expect parlay pool liquidity = 0
+        admin withdraws parlay pool 1 SOL fails InsufficientParlayLiquidity
+        alice settles parlay 0 fails ParlayNotFinal
+
+        use market 0
+        dave buys 1 SOL YES
+        use market 1
+        dave buys 1 SOL NO
+        warp +200
+        use market 0
+        resolve YES
+        dave redeems
+        use market 1
+        resolve NO
+        dave redeems
+
+        # settlement reads the outcome from the market, which stays open until then
+        use market 0
+        admin withdraws liquidity 10 SOL
+        zed archives market
+        expect market open_parlays = 4
+        zed closes market fails ParlaysOutstanding
+
+        carol settles parlay 2 fails ParlayNotFinal
+        zed settles parlay 0
+        expect event parlay_settle won = 1 paid = 4 SOL
+        expect alice sol > 1002 SOL
+        zed settles parlay 1
+        expect event parlay_settle lost = 1 paid = 0
+
+        use market 2
+        void market
+        zed settles parlay 2
+        expect event parlay_settle voided = 1 paid = 1 SOL
+        zed settles parlay 3
+        expect event parlay_settle lost = 1 paid = 0
+        expect parlay pool reserved = 0
+        expect parlay pool liquidity = 11 SOL
+        admin withdraws parlay pool 11 SOL
+        # only the rent is left
+        expect parlay pool sol < 0.01 SOL
+        use market 0
+        expect market open_parlays = 0
+        zed closes market
+    ");
+}
+
+#[test]
+fn parlays_are_priced_no_better_than_the_twap() {
+    let scenario = run("
+        configure
+        create market end +1000
+        admin adds liquidity 10 SOL
+        create market end +1000
+        admin adds liquidity 10 SOL
+        admin funds parlay pool 20 SOL
+
+        alice buys 1 SOL YES
+        warp +200
+        alice parlays 1 SOL on 0 YES, 1 NO
+        expect event parlay_open payout > 4 SOL payout < 5 SOL
+
+        # pushing NO down right before opening leaves the odds at the average
+        bob buys 5 SOL YES
+        carol parlays 1 SOL on 0 YES, 1 NO
+        expect event parlay_open payout > 4 SOL payout < 5 SOL
+    ");
+    assert_eq!(
+        scenario.parlay(1).unwrap().payout,
+        scenario.parlay(0).unwrap().payout
+    );
+}
+
+#[test]
+fn parlays_open_on_young_and_busy_markets() {
+    let mut script = String::from(
+        "configure
+        create market end +1000
+        admin adds liquidity 10 SOL
+        create market end +1000
+        admin adds liquidity 10 SOL
+        admin funds parlay pool 20 SOL
+        bob buys 1 SOL NO
+        warp +1
+        # averaged since the first trade on a market younger than the window
+        alice parlays 0.1 SOL on 0 YES, 1 NO
+        use market 0
+        alice buys 0.01 SOL YES
+        warp +200",
+    );
+    //  more trades in distinct slots than the ring holds, all inside one window
+    for _ in 0..17 {
+        script.push_str("\nwarp +1; alice buys 0.01 SOL YES");
+    }
+    script.push_str("\nalice parlays 1 SOL on 0 YES, 1 NO\nobserve 150");
+    run(&script);
+}
+
+#[test]
+fn conditional_markets_follow_their_parent() {
+    run("
+        configure
+        create market end +100
+        admin adds liquidity 10 SOL
+        dave buys 1 SOL YES
+        create market end +100 if 0 YES
+        admin adds liquidity 10 SOL
+        alice buys 1 SOL YES
+        create market end +100 if 0 NO
+        admin adds liquidity 10 SOL
+        bob buys 1 SOL NO
+
+        # children trade normally and wait for the parent's final outcome
+        use market 1
+        void conditional market fails ConditionPending
+        warp +200
+        resolve YES fails ConditionPending
+        use market 0
+        resolve YES
+        use market 1
+        resolve YES fails ConditionPending
+        use market 0
+        dave redeems
+        expect market status = Resolved
+        create market end +100 if 0 YES fails InvalidCondition
+
+        use market 1
+        void conditional market fails ConditionMet
+        resolve YES
+        alice redeems
+        expect market status = Resolved
+
+        use market 2
+        resolve NO fails ConditionFailed
+        void conditional market by zed
+        expect event market_voided
+        expect market status = Invalid
+        bob refunds
+        expect event refund sol_amount > 0.9 SOL
+
+        # the parent outlives its children, which read its outcome until they are closed
+        use market 0
+        expect market open_conditional_markets = 2
+        admin withdraws liquidity 10 SOL
+        zed archives market
+        zed closes market fails ConditionalMarketsOutstanding
+        use market 1
+        admin withdraws liquidity 10 SOL
+        zed archives market
+        zed closes market
+        use market 0
+        expect market open_conditional_markets = 1
+        use market 2
+        admin withdraws liquidity 10 SOL
+        zed archives market
+        zed closes market
+        use market 0
+        zed closes market
+    ");
+}
+
+#[test]
+fn outcome_tokens_are_renamed_after_resolution() {
+    run("
+        configure won_uri=https://won/ lost_uri=https://lost/
+        create market end +100
+        admin adds liquidity 10 SOL
+        alice buys 1 SOL NO
+        expect metadata YES name = agree
+        expect metadata NO name = disagree
+        update outcome metadata fails InvalidMarketStatus
+
+        # only a confirmed outcome renames the tokens, anyone may crank it
+        warp +200
+        resolve NO
+        update outcome metadata fails InvalidMarketStatus
+        alice redeems
+        update outcome metadata by zed
+        expect event outcome_metadata winning_token_type = 0
+        expect metadata YES name = agree LOST
+        expect metadata YES uri = https://lost/{mint}
+        expect metadata YES symbol = YES
+        expect metadata NO name = disagree WON
+        expect metadata NO uri = https://won/{mint}
+
+        # new config uris reach the tokens on the next crank
+        configure won_uri=https://won2/
+        update outcome metadata
+        expect metadata NO uri = https://won2/{mint}
+        expect metadata YES uri = https://lost/{mint}
+    ");
+
+    run("
+        configure
+        create market end +100
+        admin adds liquidity 10 SOL
+        void market
+        update outcome metadata fails InvalidMarketStatus
+    ");
+
+    //  without config uris there is nothing to rename the tokens with
+    run("
+        configure
+        create market end +100
+        admin adds liquidity 10 SOL
+        alice buys 1 SOL YES
+        warp +200
+        resolve YES
+        alice redeems
+        update outcome metadata fails OutcomeUrisNotConfigured
+        configure won_uri=https://won/
+        update outcome metadata fails OutcomeUrisNotConfigured
+        expect metadata YES name = agree
+        expect metadata YES uri = https://example.com/yes.json
+    ");
+}
+
+#[test]
+fn trading_follows_the_wall_clock_window() {
+    run("
+        configure
+        create market start_time +999999999 fails InvalidStartTime
+        create market start_time +3600 end_time +3600 fails InvalidEndTime
+        create market start_time +3600 end_time +7200
+        admin adds liquidity 10 SOL
+        alice buys 1 SOL YES fails InvalidMarketStatus
+        admin updates question Will it pass on time?
+
+        warp to start_time
+        admin updates question Too late fails MarketAlreadyStarted
+        alice buys 1 SOL YES
+        warp +3599s
+        alice buys 1 SOL YES
+        # the end time is the last second of trading, like the ending slot
+        warp to end_time
+        alice buys 1 SOL YES
+        warp past end_time
+        alice buys 1 SOL YES fails InvalidMarketStatus
+    ");
+}
+
+#[test]
+fn slot_and_time_windows_both_apply() {
+    run("
+        configure
+        create market end +100 end_time +3600
+        admin adds liquidity 10 SOL
+        alice buys 1 SOL YES
+        # 101 slots is well before the end time
+        warp +101
+        alice buys 1 SOL YES fails InvalidMarketStatus
+    ");
+}
+
+#[test]
+fn trading_closes_after_ending_slot() {
+    run(&format!(
+        "{SEEDED}
+        # the ending slot is the last slot of trading
+        warp to ending_slot
+        alice buys 1 SOL YES
+        warp past ending_slot
+        alice buys 1 SOL YES fails InvalidMarketStatus
+    "
+    ));
+}
+
+#[test]
+fn liquidity_is_locked_in_once_trading_starts() {
+    run(&format!(
+        "{SEEDED}
+        lp adds liquidity 4 SOL
+        expect market total_lp_amount = 14 SOL
+        expect lp position lp_amount = 4 SOL
+        expect event add_liquidity user_lp_amount = 4 SOL total_lp_amount = 14 SOL
+
+        lp withdraws liquidity 1 SOL
+        expect lp position lp_amount = 3 SOL
+        expect event withdraw_liquidity sol_amount = 1 SOL total_lp_amount = 13 SOL
+        lp withdraws liquidity 4 SOL fails WITHDRAWLIQUIDITYSOLAMOUNTERROR
+        alice withdraws liquidity 1 SOL fails AccountNotInitialized
+
+        alice buys 1 SOL YES
+        alice withdraws liquidity 1 SOL fails WITHDRAWNOTLPERROR
+        lp adds liquidity 1 SOL fails IncorrectLaunchPhase
+        lp withdraws liquidity 1 SOL fails IncorrectLaunchPhase
+
+        # withdrawals reopen once trading closes
+        warp past ending_slot
+        lp withdraws liquidity 1 SOL
+        expect market status = Closed
+    "
+    ));
+}
+
+#[test]
+fn lp_fees_are_paid_to_liquidity_providers() {
+    run("
+        configure lp_buy_fee=100 lp_sell_fee=100
+        create market end +100
+        admin adds liquidity 6 SOL
+        lp adds liquidity 4 SOL
+        alice buys 1 SOL YES
+        expect market lp_fees = 0.01 SOL
+        alice sells all YES
+        expect market lp_fees > 0.01 SOL
+
+        # fees stay out of the reserves, the winners do not share them
+        carol buys 2 SOL NO
+        warp past ending_slot
+        resolve NO
+        carol redeems
+
+        # each share follows the principal withdrawn
+        lp withdraws liquidity 4 SOL
+        expect event withdraw_liquidity sol_amount = 4 SOL fee_amount > 0.0124 SOL
+        admin withdraws liquidity 6 SOL
+        expect event withdraw_liquidity sol_amount = 6 SOL fee_amount > 0.0186 SOL
+        expect market lp_fees = 0
+        expect market status = Settled
+    ");
+}
+
+#[test]
+fn winners_redeem_pro_rata() {
+    let scenario = run(&format!(
+        "{SEEDED}
+        alice buys 1 SOL YES
+        bob buys 1 SOL YES
+        carol buys 3 SOL NO
+        warp past ending_slot
+        resolve YES
+
+        # only the authority confirms the outcome it proposed
+        alice redeems by bob fails InvalidMigrationAuthority
+        alice redeems without authority fails InvalidMigrationAuthority
+        alice redeems
+        expect event resolution winning_token_type = 1
+        expect event payout winning_token_type = 1 sol_amount > 1 SOL
+        expect alice position yes_balance = 0
+        expect market status = Resolved
+
+        # once confirmed, payouts need only the holder, like `cpi_redeem`
+        bob redeems without authority
+        expect event payout sol_amount > 1 SOL
+        carol redeems
+        expect event payout no_amount > 0 sol_amount = 0
+        alice buys 1 SOL YES fails InvalidMarketStatus
+    "
+    ));
+
+    //  the LP principal stays in the vault after every winner is paid
+    let vault = scenario
+        .svm
+        .lamports(&prediction_market_client::pda::global_vault().0);
+    assert!(vault >= 10_000_000_000, "vault holds {vault}");
+}
+
+#[test]
+fn payouts_burn_the_tokens_they_pay_for() {
+    run(&format!(
+        "{SEEDED}
+        alice buys 1 SOL YES
+        bob buys 1 SOL YES
+        alice sends all YES to zed
+        # tokens are sold and redeemed by whoever holds them
+        zed sells 1000000000 YES
+        expect zed position realized_pnl > 0
+
+        warp past ending_slot
+        resolve YES
+        zed redeems
+        expect event payout yes_amount > 0 sol_amount > 0.9 SOL
+        expect zed yes = 0
+        alice redeems
+        expect event payout yes_amount = 0 sol_amount = 0
+        bob redeems
+        expect bob yes = 0
+        expect market status = Resolved
+    "
+    ));
+
+    run(&format!(
+        "{SEEDED}
+        alice buys 1 SOL YES
+        alice sends 50000000000000 YES to zed
+        void market
+        # the cost of the tokens sent away is not refunded
+        alice refunds
+        expect event refund sol_amount < 0.7 SOL sol_amount > 0.6 SOL
+        expect alice yes = 0
+        expect zed yes = 50000000000000
+        expect market open_cost_basis = 0
+    "
+    ));
+}
+
+#[test]
+fn market_status_follows_the_lifecycle() {
+    run("
+        configure
+        create market start +10 end +100
+        expect market status = Pending
+        admin adds liquidity 10 SOL
+        expect market status = Pending
+        warp to start_slot
+        alice buys 1 SOL YES
+        expect market status = Trading
+
+        resolve NO by bob fails IncorrectAuthority
+        resolve NO
+        expect market status = ResolutionProposed
+        expect event resolution_proposed winning_token_type = 0
+        alice buys 1 SOL YES fails InvalidMarketStatus
+
+        # the proposal can be corrected until the first payout confirms it
+        resolve YES
+        alice redeems
+        expect market status = Resolved
+        expect event resolution winning_token_type = 1
+        resolve NO fails InvalidMarketStatus
+        void market fails InvalidMarketStatus
+
+        admin withdraws liquidity 10 SOL
+        expect market status = Settled
+    ");
+}
+
+#[test]
+fn voided_markets_refund_positions_at_cost() {
+    run(&format!(
+        "{SEEDED}
+        alice buys 1 SOL YES
+        bob buys 2 SOL NO
+        alice refunds fails InvalidMarketStatus
+        void market by alice fails IncorrectAuthority
+        void market
+        expect market status = Invalid
+        expect event market_voided
+        alice buys 1 SOL YES fails InvalidMarketStatus
+
+        # the platform fee left the pool, refunds are pro rata of what is left
+        alice refunds
+        expect event refund yes_amount > 0 sol_amount <= 1 SOL sol_amount > 0.98 SOL
+        expect alice position yes_balance = 0
+        expect alice position yes_cost_basis = 0
+        bob refunds
+        expect event refund no_amount > 0 sol_amount <= 2 SOL
+        expect market open_cost_basis = 0
+
+        admin withdraws liquidity 10 SOL
+        expect market status = Settled
+        bob archives market
+        bob closes market
+    "
+    ));
+}
+
+#[test]
+fn positions_track_cost_basis_and_realized_pnl() {
+    run("
+        configure platform_buy_fee=100 lp_buy_fee=100
+        create market end +100
+        admin adds liquidity 10 SOL
+
+        alice buys 1 SOL YES
+        expect alice position yes_cost_basis = 1 SOL
+        expect alice position total_sol_spent = 1 SOL
+        expect alice position fees_paid > 0
+        expect alice position realized_pnl = 0
+
+        # a round trip loses the fees and the spread
+        alice sells all YES
+        expect alice position yes_balance = 0
+        expect alice position yes_cost_basis = 0
+        expect alice position total_sol_received > 0
+        expect alice position realized_pnl < 0
+
+        alice buys 2 SOL YES
+        carol buys 3 SOL NO
+        expect carol position no_cost_basis = 3 SOL
+        warp past ending_slot
+        resolve YES
+
+        alice redeems
+        expect alice position yes_cost_basis = 0
+        expect alice position realized_pnl > 0
+        carol redeems
+        expect carol position no_cost_basis = 0
+        expect carol position realized_pnl = -3 SOL
+        expect carol position total_sol_received = 0
+    ");
+}
+
+#[test]
+fn positions_close_once_flat_or_settled() {
+    run(&format!(
+        "{SEEDED}
+        alice buys 1 SOL YES
+        alice closes position fails PositionNotEmpty
+        alice sells all YES
+        alice closes position
+        expect event close_user_info rent > 0
+        admin closes position fails PositionNotEmpty
+
+        bob buys 1 SOL NO
+        carol buys 1 SOL NO
+        bob closes position fails PositionNotEmpty
+        warp past ending_slot
+        resolve YES
+        bob redeems
+        bob closes position
+        # resolved is not settled yet, the position still holds its tokens
+        carol closes position fails PositionNotEmpty
+    "
+    ));
+}
+
+#[test]
+fn markets_close_once_every_claim_is_paid() {
+    run(&format!(
+        "{SEEDED}
+        alice buys 1 SOL YES
+        bob buys 1 SOL NO
+        bob closes market fails MarketNotArchived
+        admin archives market
+        bob closes market fails InvalidMarketStatus
+
+        warp past ending_slot
+        resolve YES
+        alice redeems
+        bob redeems
+        bob closes market fails ClaimsOutstanding
+        admin withdraws liquidity 10 SOL
+        bob closes market
+        expect event close_market rent > 0 swept < 1000
+    "
+    ));
+}
+
+#[test]
+fn unclaimed_winnings_are_swept_after_the_claim_deadline() {
+    let scenario = run(&format!(
+        "{SEEDED}
+        alice buys 1 SOL YES
+        bob buys 1 SOL NO
+        warp past ending_slot
+        resolve YES
+        bob redeems
+        bob archives market
+        bob closes market fails ClaimsOutstanding
+        warp past claim_deadline
+
+        # only trader winnings are swept, LP principal waits for its provider
+        bob closes market fails LiquidityOutstanding
+        admin withdraws liquidity 10 SOL
+        expect event withdraw_liquidity sol_amount = 10 SOL fee_amount > 0
+        bob closes market
+        expect event close_market swept > 1 SOL swept < 2 SOL
+        alice closes position
+    "
+    ));
+    assert!(scenario.market().is_err(), "market account still exists");
+}
+
+#[test]
+fn market_metadata_is_editable_until_start() {
+    let long_question = "why ".repeat(60);
+    let scenario = run(&format!(
+        "
+        configure
+        create market start +10 end +100
+        expect event market_metadata
+        admin updates question Will it rain in Lisbon on June 1?
+        expect event market_metadata
+        alice updates question Will alice win? fails IncorrectAuthority
+        # the registry lists the market under the category it was created with
+        admin updates category weather fails CategoryChangeNotAllowed
+        admin updates category testing
+        admin updates question {long_question} fails MarketMetadataTooLong
+        warp to start_slot
+        admin updates question Too late? fails MarketAlreadyStarted
+    "
+    ));
+
+    let metadata = scenario.market_metadata().unwrap();
+    assert_eq!(metadata.question, "Will it rain in Lisbon on June 1?");
+    assert_eq!(metadata.category, "testing");
+    assert_eq!(metadata.tags, ["scenario"]);
+    assert_eq!(scenario.category_markets("testing").unwrap().len(), 1);
+    assert!(scenario.category("weather").is_err());
+    assert_eq!(metadata.market, scenario.market_keys().unwrap().market);
+}
+
+#[test]
+fn market_metadata_is_frozen_without_a_start_slot() {
+    run("
+        configure
+        create market end +100
+        admin updates question Changed? fails MarketAlreadyStarted
+    ");
+}
+
+#[test]
+fn registry_assigns_ids_and_lists_categories() {
+    let mut scenario = run("
+        configure
+        create market category sports
+        expect market id = 0
+        expect event market_registry market_id = 0 page = 0 archived = 0
+        create market category politics
+        expect market id = 1
+        create market category sports
+        expect market id = 2
+    ");
+
+    assert_eq!(scenario.market_entry(1).unwrap().category, "politics");
+    let sports = scenario.category("sports").unwrap();
+    assert_eq!((sports.market_count, sports.open_market_count), (2, 2));
+    let third = scenario.market_keys().unwrap().market;
+    assert_eq!(
+        scenario.category_markets("sports").unwrap(),
+        [scenario.market_entry(0).unwrap().market, third]
+    );
+
+    //  only the authority may archive an open market
+    scenario
+        .run("
+            alice archives market fails IncorrectAuthority
+            admin archives market
+            expect event market_registry market_id = 2 archived = 1
+            admin archives market fails MarketArchived
+        ")
+        .unwrap();
+    assert_eq!(scenario.category_markets("sports").unwrap().len(), 1);
+    assert_eq!(scenario.category("sports").unwrap().open_market_count, 1);
+    assert!(scenario.market_entry(2).unwrap().archived);
+}
+
+#[test]
+fn completed_markets_can_be_archived_by_anyone() {
+    run(&format!("{SEEDED}
+        alice buys 1 SOL YES
+        warp past ending_slot
+        resolve YES
+        alice redeems
+        bob archives market
+        expect event market_registry archived = 1
+    "));
+}
+
+#[test]
+fn full_category_pages_roll_over() {
+    let mut scenario = run("configure");
+    for _ in 0..33 {
+        scenario.run("create market category crypto").unwrap();
+    }
+    let category = scenario.category("crypto").unwrap();
+    assert_eq!((category.page_count, category.last_page_len), (2, 1));
+    assert_eq!(scenario.category_markets("crypto").unwrap().len(), 33);
+    assert_eq!(scenario.market_entry(32).unwrap().page, 1);
+}
@@ -0,0 +1,233 @@
+//! Minimal program composing with prediction-market. A vault PDA per
+//! authority holds the SOL and the outcome tokens, and trades through
+//! `cpi_swap` and `cpi_redeem` with the vault signing by seeds. `buy` first
+//! reads `quote_swap` through return data and refuses to pay more than the
+//! authority's price limit.
+
+use anchor_lang::prelude::*;
+use prediction_market::{
+    cpi::{self, accounts as market_accounts},
+    program::PredictionMarket,
+};
+
+declare_id!("Dbn4Uxe61m7xs2LzwAmExVmjhPQ28fHZfUaJZRif8qcQ");
+
+pub const VAULT: &[u8] = b"vault";
+pub const PRICE_UNIT: u128 = 1_000_000_000;
+
+#[error_code]
+pub enum ConsumerError {
+    #[msg("The quoted price is above the limit.")]
+    PriceAboveLimit,
+}
+
+#[program]
+pub mod cpi_consumer {
+    use super::*;
+
+    //  buys `token_type` with `amount` lamports of the vault's SOL, paying at most `max_price`
+    //  lamports per `PRICE_UNIT` raw tokens
+    pub fn buy(ctx: Context<Trade>, amount: u64, token_type: u8, max_price: u64) -> Result<()> {
+        let quote = cpi::quote_swap(
+            CpiContext::new(
+                ctx.accounts.prediction_market.to_account_info(),
+                market_accounts::QuoteSwap {
+                    global_config: ctx.accounts.global_config.to_account_info(),
+                    market: ctx.accounts.market.to_account_info(),
+                    //  flat fees, the vault's tier can only lower them
+                    user_stats: None,
+                },
+            ),
+            amount,
+            prediction_market::state::market::DIRECTION_BUY,
+            token_type,
+            0,
+            None,
+            None,
+        )?
+        .get();
+        let price = amount as u128 * PRICE_UNIT / quote.amount_out.max(1) as u128;
+        require!(price <= max_price as u128, ConsumerError::PriceAboveLimit);
+
+        //  the quote holds within the transaction, so it is also the slippage bound
+        ctx.accounts.swap(
+            ctx.bumps.vault,
+            amount,
+            prediction_market::state::market::DIRECTION_BUY,
+            token_type,
+            quote.amount_out,
+        )
+    }
+
+    pub fn sell(
+        ctx: Context<Trade>,
+        amount: u64,
+        token_type: u8,
+        minimum_receive_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.swap(
+            ctx.bumps.vault,
+            amount,
+            prediction_market::state::market::DIRECTION_SELL,
+            token_type,
+            minimum_receive_amount,
+        )
+    }
+
+    //  redeems the vault's winning position straight to the authority, burning its tokens
+    pub fn redeem(ctx: Context<Redeem>) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let seeds: &[&[&[u8]]] = &[&[VAULT, authority.as_ref(), &[ctx.bumps.vault]]];
+        cpi::cpi_redeem(CpiContext::new_with_signer(
+            ctx.accounts.prediction_market.to_account_info(),
+            market_accounts::CpiRedeem {
+                market: ctx.accounts.market.to_account_info(),
+                global_vault: ctx.accounts.global_vault.to_account_info(),
+                user_info: ctx.accounts.user_info.to_account_info(),
+                yes_token: ctx.accounts.yes_token.to_account_info(),
+                no_token: ctx.accounts.no_token.to_account_info(),
+                yes_token_account: ctx.accounts.yes_token_account.to_account_info(),
+                no_token_account: ctx.accounts.no_token_account.to_account_info(),
+                owner: ctx.accounts.vault.to_account_info(),
+                recipient: ctx.accounts.authority.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                event_authority: ctx.accounts.event_authority.to_account_info(),
+                program: ctx.accounts.prediction_market.to_account_info(),
+            },
+            seeds,
+        ))
+    }
+}
+
+//  prediction-market checks its own accounts, they are passed through unchecked
+#[derive(Accounts)]
+pub struct Trade<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [VAULT, authority.key().as_ref()], bump)]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: outcome token account owned by the vault
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: prediction-market account
+    pub global_config: UncheckedAccount<'info>,
+    /// CHECK: prediction-market account
+    #[account(mut)]
+    pub team_wallet: UncheckedAccount<'info>,
+    /// CHECK: prediction-market account
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+    /// CHECK: prediction-market account
+    #[account(mut)]
+    pub global_vault: UncheckedAccount<'info>,
+    /// CHECK: prediction-market account
+    pub yes_token: UncheckedAccount<'info>,
+    /// CHECK: prediction-market account
+    pub no_token: UncheckedAccount<'info>,
+    /// CHECK: prediction-market account
+    #[account(mut)]
+    pub global_yes_ata: UncheckedAccount<'info>,
+    /// CHECK: prediction-market account
+    #[account(mut)]
+    pub global_no_ata: UncheckedAccount<'info>,
+    /// CHECK: prediction-market account
+    #[account(mut)]
+    pub user_info: UncheckedAccount<'info>,
+    /// CHECK: prediction-market account
+    #[account(mut)]
+    pub user_stats: UncheckedAccount<'info>,
+    /// CHECK: prediction-market event authority
+    pub event_authority: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    /// CHECK: spl-token, checked by prediction-market
+    pub token_program: UncheckedAccount<'info>,
+    pub prediction_market: Program<'info, PredictionMarket>,
+}
+
+impl Trade<'_> {
+    fn swap(
+        &self,
+        vault_bump: u8,
+        amount: u64,
+        direction: u8,
+        token_type: u8,
+        minimum_receive_amount: u64,
+    ) -> Result<()> {
+        let authority = self.authority.key();
+        let seeds: &[&[&[u8]]] = &[&[VAULT, authority.as_ref(), &[vault_bump]]];
+        cpi::cpi_swap(
+            CpiContext::new_with_signer(
+                self.prediction_market.to_account_info(),
+                market_accounts::CpiSwap {
+                    global_config: self.global_config.to_account_info(),
+                    team_wallet: self.team_wallet.to_account_info(),
+                    market: self.market.to_account_info(),
+                    global_vault: self.global_vault.to_account_info(),
+                    yes_token: self.yes_token.to_account_info(),
+                    no_token: self.no_token.to_account_info(),
+                    global_yes_ata: self.global_yes_ata.to_account_info(),
+                    global_no_ata: self.global_no_ata.to_account_info(),
+                    token_account: self.token_account.to_account_info(),
+                    user_info: self.user_info.to_account_info(),
+                    user_stats: self.user_stats.to_account_info(),
+                    owner: self.vault.to_account_info(),
+                    payer: self.authority.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                    event_authority: self.event_authority.to_account_info(),
+                    program: self.prediction_market.to_account_info(),
+                },
+                seeds,
+            ),
+            amount,
+            direction,
+            token_type,
+            minimum_receive_amount,
+            None,
+            None,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [VAULT, authority.key().as_ref()], bump)]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: prediction-market account
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+    /// CHECK: prediction-market account
+    #[account(mut)]
+    pub global_vault: UncheckedAccount<'info>,
+    /// CHECK: prediction-market account
+    #[account(mut)]
+    pub user_info: UncheckedAccount<'info>,
+    /// CHECK: prediction-market account
+    #[account(mut)]
+    pub yes_token: UncheckedAccount<'info>,
+    /// CHECK: prediction-market account
+    #[account(mut)]
+    pub no_token: UncheckedAccount<'info>,
+    /// CHECK: outcome token account owned by the vault
+    #[account(mut)]
+    pub yes_token_account: UncheckedAccount<'info>,
+    /// CHECK: outcome token account owned by the vault
+    #[account(mut)]
+    pub no_token_account: UncheckedAccount<'info>,
+    /// CHECK: prediction-market event authority
+    pub event_authority: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    /// CHECK: spl-token, checked by prediction-market
+    pub token_program: UncheckedAccount<'info>,
+    pub prediction_market: Program<'info, PredictionMarket>,
+}
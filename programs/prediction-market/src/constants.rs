@@ -1,10 +1,62 @@
 pub const CONFIG: &str = "config";
 pub const GLOBAL: &str = "global";
 pub const MARKET: &str = "market";
+//  marks a NO mint created by mint_no_token and the one market using it
+pub const NO_TOKEN: &str = "no_token";
 pub const USERINFO: &str = "userinfo";
 pub const METADATA: &str = "metadata";
 pub const YES_NAME: &str = "agree";
 pub const NO_NAME: &str = "disagree";
+//  appended to the token names once the market resolved
+pub const WON_SUFFIX: &str = "WON";
+pub const LOST_SUFFIX: &str = "LOST";
+//  Metaplex bound on a metadata uri
+pub const MAX_URI_LEN: usize = 200;
+//  longest base58 encoding of a pubkey
+pub const MAX_PUBKEY_STR_LEN: usize = 44;
 
 pub const MAX_START_SLOT_DELAY: u64 = 1_512_000; // ~1 week in slots (400ms each)
+pub const MAX_START_TIME_DELAY: i64 = 7 * 24 * 60 * 60; // 1 week in seconds
 
+//  seconds after resolution during which winners can claim,
+//  afterwards `close_market` sweeps the winnings left unclaimed to the team wallet
+pub const CLAIM_PERIOD: i64 = 90 * 24 * 60 * 60;
+
+pub const MARKET_METADATA: &str = "market_metadata";
+
+//  bounds of the on-chain market description, in bytes
+pub const MAX_QUESTION_LEN: usize = 200;
+pub const MAX_RULES_LEN: usize = 1000;
+pub const MAX_RESOLUTION_SOURCE_LEN: usize = 200;
+pub const MAX_CATEGORY_LEN: usize = 32;
+pub const MAX_TAGS: usize = 5;
+pub const MAX_TAG_LEN: usize = 32;
+
+pub const REGISTRY: &str = "registry";
+pub const MARKET_ENTRY: &str = "market_entry";
+pub const CATEGORY: &str = "category";
+pub const CATEGORY_PAGE: &str = "category_page";
+
+//  market addresses per category page
+pub const CATEGORY_PAGE_SIZE: usize = 32;
+
+pub const REFERRER: &str = "referrer";
+
+pub const USER_STATS: &str = "user_stats";
+
+//  days of trading volume that place a user in a fee tier
+pub const VOLUME_WINDOW_DAYS: usize = 30;
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+pub const MAX_FEE_TIERS: usize = 8;
+
+//  swaps of one `batch_swap`, bounded by the compute budget
+pub const MAX_BATCH_LEGS: usize = 16;
+
+pub const PARLAY_POOL: &str = "parlay_pool";
+pub const PARLAY: &str = "parlay";
+
+//  markets of one parlay
+pub const MIN_PARLAY_LEGS: usize = 2;
+pub const MAX_PARLAY_LEGS: usize = 8;
+//  twap window parlay legs are priced against, about a minute
+pub const PARLAY_TWAP_SLOTS: u64 = 150;
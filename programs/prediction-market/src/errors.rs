@@ -126,4 +126,106 @@ pub enum PredictionMarketError {
 
     #[msg("The withdraw: not lp error.")]
     WITHDRAWNOTLPERROR,
+
+    #[msg("A market metadata field exceeds its maximum length.")]
+    MarketMetadataTooLong,
+
+    #[msg("Market metadata can only change before trading starts.")]
+    MarketAlreadyStarted,
+
+    #[msg("The category page is full.")]
+    CategoryPageFull,
+
+    #[msg("The market is already archived.")]
+    MarketArchived,
+
+    #[msg("The position still holds tokens or liquidity.")]
+    PositionNotEmpty,
+
+    #[msg("Winners or liquidity providers have not claimed yet.")]
+    ClaimsOutstanding,
+
+    #[msg("The market must be archived first.")]
+    MarketNotArchived,
+
+    #[msg("The instruction is not allowed in the market's current status.")]
+    InvalidMarketStatus,
+
+    #[msg("The market cannot move to that status.")]
+    InvalidStatusTransition,
+
+    #[msg("The minimum trade size is above the maximum.")]
+    InvalidTradeLimits,
+
+    #[msg("The trade is below the market's minimum size.")]
+    TradeTooSmall,
+
+    #[msg("The trade is above the market's maximum size.")]
+    TradeTooLarge,
+
+    #[msg("The buy would take the position above the market's per wallet limit.")]
+    PositionLimitExceeded,
+
+    #[msg("The transaction landed after its valid until slot.")]
+    TransactionExpired,
+
+    #[msg("The trade moves the price more than the allowed impact.")]
+    PriceImpactTooHigh,
+
+    #[msg("The window starts before the oldest price observation.")]
+    ObservationTooOld,
+
+    #[msg("The token account does not hold the traded outcome.")]
+    InvalidTokenAccount,
+
+    #[msg("Fee tiers need strictly increasing volumes and may not exceed the tier limit.")]
+    InvalidFeeTiers,
+
+    #[msg("A batch needs one to MAX_BATCH_LEGS legs, each with its own accounts.")]
+    InvalidBatch,
+
+    #[msg("A parlay needs two to MAX_PARLAY_LEGS legs on distinct markets, each with its market account.")]
+    InvalidParlay,
+
+    #[msg("A parlay settles once every one of its markets has a final outcome.")]
+    ParlayNotFinal,
+
+    #[msg("The parlay pool cannot cover the payout.")]
+    InsufficientParlayLiquidity,
+
+    #[msg("The parent market does not match the condition or already has a final outcome.")]
+    InvalidCondition,
+
+    #[msg("The parent market has no final outcome yet.")]
+    ConditionPending,
+
+    #[msg("The parent market resolved against the condition, the market can only be voided.")]
+    ConditionFailed,
+
+    #[msg("The parent market resolved to the condition, the market resolves on its own outcome.")]
+    ConditionMet,
+
+    #[msg("The token metadata was created immutable and cannot be updated.")]
+    ImmutableTokenMetadata,
+
+    #[msg("The NO mint was not created by this creator through mint_no_token or already backs a market.")]
+    InvalidNoToken,
+
+    #[msg("The category is fixed at creation, the registry lists the market under it.")]
+    CategoryChangeNotAllowed,
+
+    #[msg("Liquidity providers have to withdraw before the market can be closed.")]
+    LiquidityOutstanding,
+
+    #[msg("Parlays with a leg on the market have to be settled before it can be closed.")]
+    ParlaysOutstanding,
+
+    #[msg("The picked outcome has no price, a parlay cannot pay out against it.")]
+    UnpricedParlayLeg,
+
+    #[msg("Conditional markets on the market have to be closed before it can be closed.")]
+    ConditionalMarketsOutstanding,
+
+    #[msg("The config has no won and lost uris to rename outcome tokens with.")]
+    OutcomeUrisNotConfigured,
 }
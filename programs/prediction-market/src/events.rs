@@ -1,3 +1,8 @@
+use crate::state::{
+    config::{AmountConfig, FeeTier},
+    market::MarketCondition,
+    parlay::{ParlayLeg, ParlayOutcome},
+};
 use anchor_lang::prelude::*;
 
 #[event]
@@ -8,6 +13,51 @@ pub struct GlobalUpdateEvent {
     pub mint_decimals: u8,
 }
 
+#[event]
+pub struct ConfigUpdateEvent {
+    pub authority: Pubkey,
+    pub team_wallet: Pubkey,
+
+    pub platform_buy_fee: u64,
+    pub platform_sell_fee: u64,
+    pub lp_buy_fee: u64,
+    pub lp_sell_fee: u64,
+    pub referral_fee_bps: u64,
+    pub fee_tiers: Vec<FeeTier>,
+
+    pub token_supply_config: u64,
+    pub token_decimals_config: u8,
+    pub initial_real_token_reserves_config: u64,
+    pub min_sol_liquidity: u64,
+
+    pub max_position_per_wallet_config: AmountConfig<u64>,
+    pub min_trade_lamports_config: AmountConfig<u64>,
+    pub max_trade_lamports_config: AmountConfig<u64>,
+
+    pub won_uri: String,
+    pub lost_uri: String,
+}
+
+#[event]
+pub struct NominateAuthorityEvent {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AcceptAuthorityEvent {
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct MintNoTokenEvent {
+    pub creator: Pubkey,
+    pub token_no: Pubkey,
+    pub metadata_no: Pubkey,
+    pub token_no_total_supply: u64,
+}
+
 #[event]
 pub struct CreateEvent {
     pub creator: Pubkey,
@@ -25,6 +75,46 @@ pub struct CreateEvent {
 
     pub start_slot: u64,
     pub ending_slot: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+
+    pub max_position_per_wallet: u64,
+    pub min_trade_lamports: u64,
+    pub max_trade_lamports: u64,
+
+    //  parent market and outcome of a conditional market
+    pub condition: Option<MarketCondition>,
+}
+
+#[event]
+pub struct TradeLimitsEvent {
+    pub market: Pubkey,
+    pub creator: Pubkey,
+
+    pub max_position_per_wallet: u64,
+    pub min_trade_lamports: u64,
+    pub max_trade_lamports: u64,
+}
+
+#[event]
+pub struct MarketMetadataEvent {
+    pub market: Pubkey,
+    pub creator: Pubkey,
+
+    pub question: String,
+    pub rules: String,
+    pub resolution_source: String,
+    pub category: String,
+    pub tags: Vec<String>,
+}
+
+#[event]
+pub struct MarketRegistryEvent {
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub category: String,
+    pub page: u32,
+    pub archived: bool,
 }
 
 #[event]
@@ -56,6 +146,182 @@ pub struct TradeEvent {
     pub real_token_yes_reserves: u64,
     pub real_token_no_reserves: u64,
 
+    //  authority of the referrer and its share of the platform fee
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RegisterReferrerEvent {
+    //  wallet the referral fees are claimed with
+    pub authority: Pubkey,
+    pub referrer: Pubkey,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralClaimEvent {
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub total_earned: u64,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AddLiquidityEvent {
+    pub user: Pubkey,
+    pub market: Pubkey,
+
+    pub sol_amount: u64,
+    pub user_lp_amount: u64,
+    pub total_lp_amount: u64,
+
+    pub real_yes_sol_reserves: u64,
+    pub real_no_sol_reserves: u64,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WithdrawLiquidityEvent {
+    pub user: Pubkey,
+    pub market: Pubkey,
+
+    pub sol_amount: u64,
+    //  lp fees paid along with the principal
+    pub fee_amount: u64,
+    pub user_lp_amount: u64,
+    pub total_lp_amount: u64,
+
+    pub real_yes_sol_reserves: u64,
+    pub real_no_sol_reserves: u64,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ResolutionProposedEvent {
+    pub authority: Pubkey,
+    pub market: Pubkey,
+    pub winning_token_type: u8,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ResolutionEvent {
+    pub authority: Pubkey,
+    pub market: Pubkey,
+    pub winning_token_type: u8,
+
+    pub real_yes_sol_reserves: u64,
+    pub real_no_sol_reserves: u64,
+    pub total_lp_amount: u64,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PayoutEvent {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub winning_token_type: u8,
+
+    pub yes_amount: u64,
+    pub no_amount: u64,
+    pub sol_amount: u64,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MarketVoidedEvent {
+    pub authority: Pubkey,
+    pub market: Pubkey,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OutcomeMetadataEvent {
+    pub authority: Pubkey,
+    pub market: Pubkey,
+    pub winning_token_type: u8,
+    pub yes_name: String,
+    pub no_name: String,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RefundEvent {
+    pub user: Pubkey,
+    pub market: Pubkey,
+
+    pub yes_amount: u64,
+    pub no_amount: u64,
+    pub sol_amount: u64,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CloseUserInfoEvent {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub rent: u64,
+}
+
+#[event]
+pub struct CloseMarketEvent {
+    pub market: Pubkey,
+    pub creator: Pubkey,
+    pub team_wallet: Pubkey,
+
+    pub rent: u64,
+    pub swept: u64,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ParlayPoolEvent {
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub is_withdrawal: bool,
+    pub liquidity: u64,
+    pub reserved: u64,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ParlayOpenEvent {
+    pub parlay: Pubkey,
+    pub parlay_id: u64,
+    pub owner: Pubkey,
+    pub legs: Vec<ParlayLeg>,
+
+    pub stake: u64,
+    pub fee_lamports: u64,
+    pub payout: u64,
+
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ParlaySettleEvent {
+    pub parlay: Pubkey,
+    pub parlay_id: u64,
+    pub owner: Pubkey,
+    pub outcome: ParlayOutcome,
+    //  lamports sent to the owner
+    pub paid: u64,
+
     pub timestamp: i64,
 }
 
@@ -71,5 +337,6 @@ pub struct CompleteEvent {
 }
 
 pub trait IntoEvent<T: anchor_lang::Event> {
+    #[allow(clippy::wrong_self_convention)]
     fn into_event(&self) -> T;
 }
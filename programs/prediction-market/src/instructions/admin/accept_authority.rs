@@ -1,8 +1,10 @@
 use constants::CONFIG;
 use errors::PredictionMarketError;
+use events::AcceptAuthorityEvent;
 
 use crate::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct AcceptAuthority<'info> {
     //  Pending admin
@@ -22,10 +24,14 @@ pub struct AcceptAuthority<'info> {
 }
 
 impl AcceptAuthority<'_> {
-    pub fn process(&mut self) -> Result<()> {
+    pub fn process(&mut self) -> Result<AcceptAuthorityEvent> {
+        let previous_authority = self.global_config.authority;
         self.global_config.authority = self.new_admin.key();
         self.global_config.pending_authority = Pubkey::default();
 
-        Ok(())
+        Ok(AcceptAuthorityEvent {
+            previous_authority,
+            authority: self.new_admin.key(),
+        })
     }
 }
@@ -1,6 +1,8 @@
 use crate::errors::*;
+use crate::events::{ConfigUpdateEvent, IntoEvent};
 use crate::{
     constants::{CONFIG, GLOBAL},
+    math::BPS_DENOMINATOR,
     state::config::*,
     utils::sol_transfer_from_user,
 };
@@ -8,6 +10,7 @@ use anchor_lang::{prelude::*, system_program, Discriminator};
 use anchor_spl::{associated_token::AssociatedToken, token::Token};
 use borsh::BorshDeserialize;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Configure<'info> {
     #[account(mut)]
@@ -38,7 +41,7 @@ pub struct Configure<'info> {
 }
 
 impl<'info> Configure<'info> {
-    pub fn handler(&mut self, new_config: Config, config_bump: u8) -> Result<()> {
+    pub fn handler(&mut self, new_config: Config, config_bump: u8) -> Result<ConfigUpdateEvent> {
         //  check params
         let decimal_multiplier = 10u64.pow(new_config.token_decimals_config as u32);
         let fractional_tokens = new_config.token_supply_config % decimal_multiplier;
@@ -47,18 +50,16 @@ impl<'info> Configure<'info> {
         }
 
         require!(
-            new_config.token_supply_config
-                >= (new_config.initial_real_token_reserves_config / decimal_multiplier
-                    * new_config.initial_real_token_reserves_config),
+            new_config.initial_real_token_reserves_config <= new_config.token_supply_config,
             PredictionMarketError::InvalidAmount
         );
-
         require!(
-            new_config.token_supply_config
-                >= (new_config.initial_real_token_reserves_config / decimal_multiplier
-                    * new_config.initial_real_token_reserves_config),
+            new_config.referral_fee_bps <= BPS_DENOMINATOR,
             PredictionMarketError::InvalidAmount
         );
+        new_config.validate_fee_rates()?;
+        new_config.validate_fee_tiers()?;
+        new_config.validate_outcome_uris()?;
 
         let serialized_config =
             [&Config::DISCRIMINATOR, new_config.try_to_vec()?.as_slice()].concat();
@@ -82,7 +83,7 @@ impl<'info> Configure<'info> {
             )?;
         } else {
             let data = self.config.try_borrow_data()?;
-            if data.len() < 8 || &data[0..8] != Config::DISCRIMINATOR {
+            if data.len() < 8 || data[0..8] != Config::DISCRIMINATOR {
                 return err!(PredictionMarketError::IncorrectConfigAccount);
             }
             let config = Config::deserialize(&mut &data[8..])?;
@@ -119,6 +120,6 @@ impl<'info> Configure<'info> {
                 1000000,
             )?;
         }
-        Ok(())
+        Ok(new_config.into_event())
     }
 }
@@ -1,9 +1,11 @@
 use constants::CONFIG;
 use errors::PredictionMarketError;
+use events::NominateAuthorityEvent;
 // use state::config::*;
 
 use crate::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct NominateAuthority<'info> {
     // Current admin
@@ -23,8 +25,12 @@ pub struct NominateAuthority<'info> {
 }
 
 impl NominateAuthority<'_> {
-    pub fn process(&mut self, new_admin: Pubkey) -> Result<()> {
+    pub fn process(&mut self, new_admin: Pubkey) -> Result<NominateAuthorityEvent> {
         self.global_config.pending_authority = new_admin;
-        Ok(())
+
+        Ok(NominateAuthorityEvent {
+            authority: self.admin.key(),
+            pending_authority: new_admin,
+        })
     }
 }
@@ -1,6 +1,7 @@
 use crate::{
     constants::{CONFIG, GLOBAL, MARKET, USERINFO},
     errors::PredictionMarketError,
+    events::AddLiquidityEvent,
     state::{config::*, market::*},
 };
 use anchor_lang::{prelude::*, system_program};
@@ -9,6 +10,10 @@ use anchor_spl::{
     token::{self, Mint, Token},
 };
 
+//  market statuses the instruction runs in
+const ACCEPTED: &[MarketStatus] = &[MarketStatus::Pending, MarketStatus::Trading];
+
+#[event_cpi]
 #[derive(Accounts)]
 pub struct AddLiquidity<'info> {
     #[account(
@@ -67,12 +72,37 @@ pub struct AddLiquidity<'info> {
 }
 
 impl<'info> AddLiquidity<'info> { 
-    pub fn handler(&mut self, amount: u64) -> Result<()> {
-        //A decentralized prediction market platform built on Solana blockchain, inspired by Polymarket. This project enables users to create markets, trade positions, and resolve outcomes based on real-world events.    
-        // **Telegram**: [@Tru3B1iss](https://t.me/Tru3B1iss)
-        // **X (Twitter)**: [@XTruebliss](https://x.com/XTruebliss)
-        // **Discord**: [@trueb1iss](https://discord.com/users/1274339638668038187)
+    pub fn handler(&mut self, amount: u64) -> Result<AddLiquidityEvent> {
+        self.market
+            .sync(&Clock::get()?, self.global_config.min_sol_liquidity)?;
+        self.market.require_status(ACCEPTED)?;
+
+        let user_info_pda = &mut self.user_info;
+        if !user_info_pda.is_initialized {
+            user_info_pda.user = self.user.key();
+            user_info_pda.is_initialized = true;
+        }
+
+        self.market.add_liquidity(
+            &mut self.global_vault,
+            &self.user,
+            amount,
+            user_info_pda,
+            &self.system_program,
+        )?;
+
+        Ok(AddLiquidityEvent {
+            user: self.user.key(),
+            market: self.market.key(),
+
+            sol_amount: amount,
+            user_lp_amount: self.user_info.lp_amount,
+            total_lp_amount: self.market.total_lp_amount,
+
+            real_yes_sol_reserves: self.market.real_yes_sol_reserves,
+            real_no_sol_reserves: self.market.real_no_sol_reserves,
 
-        Ok(())
+            timestamp: Clock::get()?.unix_timestamp,
+        })
     }
 }
\ No newline at end of file
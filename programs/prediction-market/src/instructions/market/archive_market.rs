@@ -0,0 +1,64 @@
+use crate::{
+    constants::{CATEGORY, CATEGORY_PAGE, CONFIG, MARKET, MARKET_ENTRY},
+    errors::*,
+    events::MarketRegistryEvent,
+    state::{config::*, market::*, registry::*},
+};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ArchiveMarket<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    //  anyone once the outcome is final, the config authority at any time
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [MARKET.as_bytes(), &market.yes_token_mint.to_bytes(), &market.no_token_mint.to_bytes()],
+        bump,
+    )]
+    market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_ENTRY.as_bytes(), &market.id.to_le_bytes()],
+        bump,
+        constraint = market_entry.market == market.key() @PredictionMarketError::InvalidArgument,
+    )]
+    market_entry: Box<Account<'info, MarketEntry>>,
+
+    #[account(
+        mut,
+        seeds = [CATEGORY.as_bytes(), market_entry.category.as_bytes()],
+        bump,
+    )]
+    category: Box<Account<'info, Category>>,
+
+    #[account(
+        mut,
+        seeds = [
+            CATEGORY_PAGE.as_bytes(),
+            market_entry.category.as_bytes(),
+            &market_entry.page.to_le_bytes(),
+        ],
+        bump,
+    )]
+    category_page: Box<Account<'info, CategoryPage>>,
+}
+
+impl ArchiveMarket<'_> {
+    pub fn handler(&mut self) -> Result<MarketRegistryEvent> {
+        require!(
+            self.market.status.is_final() || self.signer.key() == self.global_config.authority,
+            PredictionMarketError::IncorrectAuthority
+        );
+
+        self.category
+            .archive(&mut self.market_entry, &mut self.category_page)
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{
+    constants::{CONFIG, GLOBAL, MAX_BATCH_LEGS, USERINFO, USER_STATS},
+    errors::PredictionMarketError,
+    events::TradeEvent,
+    math::TOKEN_TYPE_YES,
+    state::{config::*, market::*, referrer::Referrer, user_stats::UserStats},
+    utils::require_valid_slot,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address, AssociatedToken},
+    token::{self, Token},
+};
+
+//  market statuses each leg runs in
+const ACCEPTED: &[MarketStatus] = &[MarketStatus::Trading];
+
+//  accounts of a leg in `remaining_accounts`: the market, the traded outcome mint, its vault
+//  and user ATAs, and the user_info of the market
+pub const BATCH_LEG_ACCOUNTS: usize = 5;
+
+//  `swap` over several markets in one transaction, a failing leg fails the whole batch. The
+//  slot deadline and the referrer apply to every leg
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BatchSwap<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    /// CHECK: should be same with the address in the global_config
+    #[account(
+        mut,
+        constraint = global_config.team_wallet == team_wallet.key() @PredictionMarketError::IncorrectAuthority
+    )]
+    pub team_wallet: AccountInfo<'info>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        mut,
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    pub global_vault: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [USER_STATS.as_bytes(), &user.key().to_bytes()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    #[account(address = associated_token::ID)]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    //  frontend credited with `referral_fee_bps` of the platform fee of every leg
+    #[account(mut)]
+    pub referrer: Option<Box<Account<'info, Referrer>>>,
+}
+
+impl<'info> BatchSwap<'info> {
+    pub fn handler(
+        &mut self,
+        legs: &[SwapLeg],
+        valid_until_slot: Option<u64>,
+        leg_accounts: &'info [AccountInfo<'info>],
+        global_vault_bump: u8,
+    ) -> Result<Vec<TradeEvent>> {
+        let clock = Clock::get()?;
+        require_valid_slot(&clock, valid_until_slot)?;
+        require!(
+            !legs.is_empty()
+                && legs.len() <= MAX_BATCH_LEGS
+                && leg_accounts.len() == legs.len() * BATCH_LEG_ACCOUNTS,
+            PredictionMarketError::InvalidBatch
+        );
+        if self.user_stats.user == Pubkey::default() {
+            self.user_stats.user = self.user.key();
+        }
+
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+        let mut events = Vec::with_capacity(legs.len());
+        for (leg, accounts) in legs.iter().zip(leg_accounts.chunks(BATCH_LEG_ACCOUNTS)) {
+            let [market_info, mint, global_ata, user_ata, user_info] = accounts else {
+                return err!(PredictionMarketError::InvalidBatch);
+            };
+            require_keys_eq!(
+                market_info.key(),
+                leg.market,
+                PredictionMarketError::InvalidBatch
+            );
+
+            //  written back before the next leg, which may trade the same market
+            let mut market = Account::<Market>::try_from(market_info)?;
+            market.sync(&clock, self.global_config.min_sol_liquidity)?;
+            market.require_status(ACCEPTED)?;
+
+            let outcome_mint = if leg.token_type == TOKEN_TYPE_YES {
+                market.yes_token_mint
+            } else {
+                market.no_token_mint
+            };
+            require_keys_eq!(
+                mint.key(),
+                outcome_mint,
+                PredictionMarketError::InvalidBatch
+            );
+            require_keys_eq!(
+                global_ata.key(),
+                get_associated_token_address(&self.global_vault.key(), &outcome_mint),
+                PredictionMarketError::InvalidBatch
+            );
+            require_keys_eq!(
+                user_ata.key(),
+                get_associated_token_address(&self.user.key(), &outcome_mint),
+                PredictionMarketError::InvalidBatch
+            );
+            if user_ata.data_is_empty() {
+                associated_token::create(CpiContext::new(
+                    self.associated_token_program.to_account_info(),
+                    associated_token::Create {
+                        payer: self.user.to_account_info(),
+                        associated_token: user_ata.clone(),
+                        authority: self.user.to_account_info(),
+                        mint: mint.clone(),
+                        system_program: self.system_program.to_account_info(),
+                        token_program: self.token_program.to_account_info(),
+                    },
+                ))?;
+            }
+            let mut user_info = self.load_user_info(&market.key(), user_info)?;
+
+            //  only the traded side's accounts are used, the other ones are placeholders
+            let mut global_ata = global_ata.clone();
+            let mut user_ata = user_ata.clone();
+            let mut unused_global_ata = global_ata.clone();
+            let mut unused_user_ata = user_ata.clone();
+            let (global_yes_ata, user_yes_ata, global_no_ata, user_no_ata) =
+                if leg.token_type == TOKEN_TYPE_YES {
+                    (
+                        &mut global_ata,
+                        &mut user_ata,
+                        &mut unused_global_ata,
+                        &mut unused_user_ata,
+                    )
+                } else {
+                    (
+                        &mut unused_global_ata,
+                        &mut unused_user_ata,
+                        &mut global_ata,
+                        &mut user_ata,
+                    )
+                };
+
+            events.push(market.swap(
+                &self.global_config,
+                global_yes_ata,
+                user_yes_ata,
+                global_no_ata,
+                user_no_ata,
+                &mut self.global_vault,
+                &mut self.team_wallet,
+                self.referrer.as_deref_mut(),
+                leg.amount,
+                leg.direction,
+                leg.token_type,
+                leg.minimum_receive_amount,
+                leg.max_price_impact_bps,
+                &self.user,
+                signer_seeds,
+                &mut user_info,
+                &mut self.user_stats,
+                &self.token_program,
+                &self.system_program,
+            )?);
+
+            market.exit(&crate::ID)?;
+            user_info.exit(&crate::ID)?;
+        }
+        Ok(events)
+    }
+
+    //  the user's position in `market`, created on its first trade as `swap` would. Like
+    //  `init_if_needed`, lamports sent to the address beforehand only lower the rent paid
+    fn load_user_info(
+        &self,
+        market: &Pubkey,
+        info: &'info AccountInfo<'info>,
+    ) -> Result<Account<'info, UserInfo>> {
+        let user = self.user.key();
+        let (address, bump) = Pubkey::find_program_address(
+            &[USERINFO.as_bytes(), &user.to_bytes(), &market.to_bytes()],
+            &crate::ID,
+        );
+        require_keys_eq!(info.key(), address, PredictionMarketError::InvalidBatch);
+        if info.owner == &crate::ID {
+            return Account::try_from(info);
+        }
+
+        let space = 8 + std::mem::size_of::<UserInfo>();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            USERINFO.as_bytes(),
+            &user.to_bytes(),
+            &market.to_bytes(),
+            &[bump],
+        ]];
+        let shortfall = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(info.lamports());
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: self.user.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                system_program::Allocate {
+                    account_to_allocate: info.clone(),
+                },
+                signer_seeds,
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                system_program::Assign {
+                    account_to_assign: info.clone(),
+                },
+                signer_seeds,
+            ),
+            &crate::ID,
+        )?;
+        let mut user_info = Account::<UserInfo>::try_from_unchecked(info)?;
+        user_info.user = user;
+        user_info.is_initialized = true;
+        Ok(user_info)
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{
+    constants::{CONFIG, GLOBAL, MARKET, MARKET_ENTRY, MARKET_METADATA},
+    errors::PredictionMarketError,
+    events::CloseMarketEvent,
+    state::{config::*, market::*, market_metadata::*, registry::*},
+    utils::sol_transfer_with_signer,
+};
+use anchor_lang::{prelude::*, system_program};
+
+//  market statuses the instruction runs in, resolved and voided ones after the claim period
+const ACCEPTED: &[MarketStatus] = &[
+    MarketStatus::Resolved,
+    MarketStatus::Invalid,
+    MarketStatus::Settled,
+];
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseMarket<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    /// CHECK: should be same with the address in the global_config
+    #[account(
+        mut,
+        constraint = global_config.team_wallet == team_wallet.key() @PredictionMarketError::IncorrectAuthority
+    )]
+    pub team_wallet: AccountInfo<'info>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        mut,
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    pub global_vault: AccountInfo<'info>,
+
+    /// CHECK: receives the rent of the market accounts
+    #[account(
+        mut,
+        address = market.creator @PredictionMarketError::IncorrectAuthority
+    )]
+    pub creator: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [MARKET.as_bytes(), &market.yes_token_mint.to_bytes(), &market.no_token_mint.to_bytes()],
+        bump,
+    )]
+    market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [MARKET_METADATA.as_bytes(), &market.key().to_bytes()],
+        bump
+    )]
+    market_metadata: Box<Account<'info, MarketMetadata>>,
+
+    //  the registry entry stays as a record of the market id
+    #[account(
+        seeds = [MARKET_ENTRY.as_bytes(), &market.id.to_le_bytes()],
+        bump,
+        constraint = market_entry.archived @PredictionMarketError::MarketNotArchived,
+    )]
+    market_entry: Box<Account<'info, MarketEntry>>,
+
+    //  the parent of a conditional market, released once the market no longer reads its outcome
+    #[account(mut)]
+    parent_market: Option<Box<Account<'info, Market>>>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl CloseMarket<'_> {
+    pub fn handler(&mut self, global_vault_bump: u8) -> Result<CloseMarketEvent> {
+        self.market.try_settle()?;
+        self.market.require_status(ACCEPTED)?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        let deadline_passed = self
+            .market
+            .claim_deadline()
+            .is_some_and(|deadline| timestamp >= deadline);
+        require!(
+            self.market.status == MarketStatus::Settled || deadline_passed,
+            PredictionMarketError::ClaimsOutstanding
+        );
+        //  LP principal and fees are never swept, they wait for their providers
+        require!(
+            self.market.total_lp_amount == 0,
+            PredictionMarketError::LiquidityOutstanding
+        );
+        //  settle_parlay reads the outcome from the market account
+        require!(
+            self.market.open_parlays == 0,
+            PredictionMarketError::ParlaysOutstanding
+        );
+        //  void_conditional_market and propose_resolution read the outcome from the parent
+        require!(
+            self.market.open_conditional_markets == 0,
+            PredictionMarketError::ConditionalMarketsOutstanding
+        );
+        match (self.market.condition, self.parent_market.as_deref_mut()) {
+            (None, None) => {}
+            (Some(condition), Some(parent)) if parent.key() == condition.parent => {
+                parent.open_conditional_markets = parent
+                    .open_conditional_markets
+                    .checked_sub(1)
+                    .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+            }
+            _ => return err!(PredictionMarketError::InvalidCondition),
+        }
+
+        //  rounding dust, or trader winnings left unclaimed past the deadline
+        let swept = self
+            .market
+            .curve_reserves()
+            .total_sol()
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        if swept > 0 {
+            let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+            sol_transfer_with_signer(
+                self.global_vault.clone(),
+                self.team_wallet.clone(),
+                &self.system_program,
+                signer_seeds,
+                swept,
+            )?;
+        }
+        self.market.real_yes_sol_reserves = 0;
+        self.market.real_no_sol_reserves = 0;
+
+        Ok(CloseMarketEvent {
+            market: self.market.key(),
+            creator: self.creator.key(),
+            team_wallet: self.team_wallet.key(),
+
+            rent: self.market.get_lamports() + self.market_metadata.get_lamports(),
+            swept,
+
+            timestamp,
+        })
+    }
+}
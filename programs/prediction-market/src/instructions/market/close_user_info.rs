@@ -0,0 +1,52 @@
+use crate::{
+    constants::USERINFO, errors::PredictionMarketError, events::CloseUserInfoEvent,
+    state::market::*, ID,
+};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseUserInfo<'info> {
+    //  receives the rent, user_info is always paid for by its user
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: bound through the user_info seeds, may already be closed
+    market: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [USERINFO.as_bytes(), &user.key().to_bytes(), &market.key().to_bytes()],
+        bump
+    )]
+    user_info: Box<Account<'info, UserInfo>>,
+}
+
+impl CloseUserInfo<'_> {
+    pub fn handler(&mut self) -> Result<CloseUserInfoEvent> {
+        //  LP principal can only be withdrawn through user_info
+        require!(
+            self.user_info.lp_amount == 0,
+            PredictionMarketError::PositionNotEmpty
+        );
+
+        //  once settled, tokens that were not redeemed are forfeited, a resolved market
+        //  still pays them
+        let flat = self.user_info.yes_balance == 0 && self.user_info.no_balance == 0;
+        let settled = if *self.market.owner == ID {
+            Market::try_deserialize(&mut &self.market.try_borrow_data()?[..])?.status
+                == MarketStatus::Settled
+        } else {
+            //  closed by `close_market`
+            true
+        };
+        require!(flat || settled, PredictionMarketError::PositionNotEmpty);
+
+        Ok(CloseUserInfoEvent {
+            user: self.user.key(),
+            market: self.market.key(),
+            rent: self.user_info.get_lamports(),
+        })
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    constants::{GLOBAL, MARKET, USERINFO},
+    errors::PredictionMarketError,
+    events::PayoutEvent,
+    state::market::*,
+    utils::token_burn_with_signer,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+//  market statuses the instruction runs in, a proposed outcome is confirmed by the authority first
+const ACCEPTED: &[MarketStatus] = &[MarketStatus::Resolved];
+
+//  redeems every outcome token the owner holds in the caller-supplied token accounts, the
+//  owner may be a PDA the caller signs for and the payout goes to a caller-supplied account.
+//  Like `resolution` after the outcome is confirmed, the owner's signature is all it takes
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CpiRedeem<'info> {
+    #[account(
+        mut,
+        seeds = [MARKET.as_bytes(), &market.yes_token_mint.to_bytes(), &market.no_token_mint.to_bytes()],
+        bump,
+    )]
+    market: Box<Account<'info, Market>>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        mut,
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    pub global_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [USERINFO.as_bytes(), &owner.key().to_bytes(), &market.key().to_bytes()],
+        bump,
+    )]
+    pub user_info: Box<Account<'info, UserInfo>>,
+
+    #[account(mut, address = market.yes_token_mint)]
+    pub yes_token: Box<Account<'info, Mint>>,
+
+    #[account(mut, address = market.no_token_mint)]
+    pub no_token: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = yes_token_account.mint == market.yes_token_mint @PredictionMarketError::InvalidTokenAccount,
+    )]
+    pub yes_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = no_token_account.mint == market.no_token_mint @PredictionMarketError::InvalidTokenAccount,
+    )]
+    pub no_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub owner: Signer<'info>,
+
+    /// CHECK: receives the payout, chosen by the owner
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+impl CpiRedeem<'_> {
+    pub fn handler(&mut self, global_vault_bump: u8) -> Result<PayoutEvent> {
+        self.market.require_status(ACCEPTED)?;
+        let winning_token_type = self
+            .market
+            .winning_token_type
+            .ok_or(PredictionMarketError::RESOLUTIONTOKEYTYPEERROR)?;
+
+        //  every token the owner holds is redeemed, however it was acquired, and burned
+        //  so it cannot be sold or redeemed again
+        let yes_amount = self.yes_token_account.amount;
+        let no_amount = self.no_token_account.amount;
+        for (account, mint, amount) in [
+            (&self.yes_token_account, &self.yes_token, yes_amount),
+            (&self.no_token_account, &self.no_token, no_amount),
+        ] {
+            if amount > 0 {
+                token_burn_with_signer(
+                    account.to_account_info(),
+                    mint.to_account_info(),
+                    self.owner.to_account_info(),
+                    &self.token_program,
+                    &[],
+                    amount,
+                )?;
+            }
+        }
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+        let sol_amount = self.market.resolution(
+            &mut self.global_vault,
+            &mut self.recipient,
+            signer_seeds,
+            &mut self.user_info,
+            yes_amount,
+            no_amount,
+            winning_token_type,
+            &self.system_program,
+        )?;
+        self.market.try_settle()?;
+
+        Ok(PayoutEvent {
+            user: self.owner.key(),
+            market: self.market.key(),
+            winning_token_type,
+
+            yes_amount,
+            no_amount,
+            sol_amount,
+
+            timestamp: Clock::get()?.unix_timestamp,
+        })
+    }
+}
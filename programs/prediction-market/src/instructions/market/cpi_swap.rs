@@ -0,0 +1,183 @@
+use crate::{
+    constants::{CONFIG, GLOBAL, MARKET, USERINFO, USER_STATS},
+    errors::PredictionMarketError,
+    events::TradeEvent,
+    math::TOKEN_TYPE_YES,
+    state::{config::*, market::*, user_stats::UserStats},
+    utils::require_valid_slot,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+//  market statuses the instruction runs in
+const ACCEPTED: &[MarketStatus] = &[MarketStatus::Trading];
+
+//  `swap` for composing programs: the owner may be a PDA the caller signs for, rent is
+//  paid by a separate payer and tokens move through a caller-supplied token account
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CpiSwap<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    /// CHECK: should be same with the address in the global_config
+    #[account(
+        mut,
+        constraint = global_config.team_wallet == team_wallet.key() @PredictionMarketError::IncorrectAuthority
+    )]
+    pub team_wallet: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [MARKET.as_bytes(), &yes_token.key().to_bytes(), &no_token.key().to_bytes()],
+        bump
+    )]
+    market: Box<Account<'info, Market>>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        mut,
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    pub global_vault: AccountInfo<'info>,
+
+    pub yes_token: Box<Account<'info, Mint>>,
+    pub no_token: Box<Account<'info, Mint>>,
+
+    /// CHECK: ata of global vault
+    #[account(
+        mut,
+        seeds = [
+            global_vault.key().as_ref(),
+            anchor_spl::token::spl_token::ID.as_ref(),
+            yes_token.key().as_ref(),
+        ],
+        bump,
+        seeds::program = anchor_spl::associated_token::ID
+    )]
+    global_yes_ata: AccountInfo<'info>,
+
+    /// CHECK: ata of global vault
+    #[account(
+        mut,
+        seeds = [
+            global_vault.key().as_ref(),
+            anchor_spl::token::spl_token::ID.as_ref(),
+            no_token.key().as_ref(),
+        ],
+        bump,
+        seeds::program = anchor_spl::associated_token::ID
+    )]
+    global_no_ata: AccountInfo<'info>,
+
+    //  receives bought tokens, sold tokens leave it with the owner as authority
+    #[account(mut)]
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + std::mem::size_of::<UserInfo>(),
+        seeds = [USERINFO.as_bytes(), &owner.key().to_bytes(), &market.key().to_bytes()],
+        bump
+    )]
+    pub user_info: Box<Account<'info, UserInfo>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [USER_STATS.as_bytes(), &owner.key().to_bytes()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    //  holds the position, pays SOL for buys and receives it for sells
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    //  pays the user_info and user_stats rent
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> CpiSwap<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn handler(
+        &mut self,
+        amount: u64,
+        direction: u8,
+        token_type: u8,
+        minimum_receive_amount: u64,
+        valid_until_slot: Option<u64>,
+        max_price_impact_bps: Option<u64>,
+        global_vault_bump: u8,
+    ) -> Result<TradeEvent> {
+        let clock = Clock::get()?;
+        require_valid_slot(&clock, valid_until_slot)?;
+        self.market
+            .sync(&clock, self.global_config.min_sol_liquidity)?;
+        self.market.require_status(ACCEPTED)?;
+
+        let outcome_mint = if token_type == TOKEN_TYPE_YES {
+            self.yes_token.key()
+        } else {
+            self.no_token.key()
+        };
+        require_keys_eq!(
+            self.token_account.mint,
+            outcome_mint,
+            PredictionMarketError::InvalidTokenAccount
+        );
+
+        if !self.user_info.is_initialized {
+            self.user_info.user = self.owner.key();
+            self.user_info.is_initialized = true;
+        }
+        if self.user_stats.user == Pubkey::default() {
+            self.user_stats.user = self.owner.key();
+        }
+
+        //  only the traded side's account is used, the other one is a placeholder
+        let mut token_account = self.token_account.to_account_info();
+        let mut unused = token_account.clone();
+        let (user_yes_ata, user_no_ata) = if token_type == TOKEN_TYPE_YES {
+            (&mut token_account, &mut unused)
+        } else {
+            (&mut unused, &mut token_account)
+        };
+
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+        self.market.swap(
+            &self.global_config,
+            &mut self.global_yes_ata,
+            user_yes_ata,
+            &mut self.global_no_ata,
+            user_no_ata,
+            &mut self.global_vault,
+            &mut self.team_wallet,
+            None,
+            amount,
+            direction,
+            token_type,
+            minimum_receive_amount,
+            max_price_impact_bps,
+            &self.owner,
+            signer_seeds,
+            &mut self.user_info,
+            &mut self.user_stats,
+            &self.token_program,
+            &self.system_program,
+        )
+    }
+}
@@ -1,17 +1,19 @@
 use crate::{
-    constants::{CONFIG, GLOBAL, MARKET, METADATA, YES_NAME},
+    constants::*,
     errors::*,
-    events::CreateEvent,
-    state::{config::*, market::*},
+    events::{CreateEvent, IntoEvent, MarketMetadataEvent, MarketRegistryEvent},
+    state::{config::*, market::*, market_metadata::*, registry::*},
 };
 use anchor_lang::{prelude::*, solana_program::sysvar::SysvarId, system_program};
 use anchor_spl::{
     associated_token::{self, AssociatedToken},
     metadata::{self, mpl_token_metadata::types::DataV2, Metadata},
-    token::{self, spl_token::instruction::AuthorityType, Mint, Token},
+    token::{self, spl_token::instruction::AuthorityType, Mint, Token, TokenAccount},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
+#[instruction(params: CreateMarketParams)]
 pub struct CreateMarket<'info> {
     #[account(
         mut,
@@ -41,6 +43,16 @@ pub struct CreateMarket<'info> {
 
     pub no_token: Box<Account<'info, Mint>>,
 
+    //  proves the no token came from mint_no_token by the same creator and is still unused
+    #[account(
+        mut,
+        seeds = [NO_TOKEN.as_bytes(), no_token.key().as_ref()],
+        bump,
+        has_one = creator @PredictionMarketError::InvalidNoToken,
+        constraint = no_token_record.market.is_none() @PredictionMarketError::InvalidNoToken,
+    )]
+    no_token_record: Box<Account<'info, NoTokenRecord>>,
+
     #[account(
         init,
         payer = creator,
@@ -50,6 +62,55 @@ pub struct CreateMarket<'info> {
     )]
     market: Box<Account<'info, Market>>,
 
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + MarketMetadata::INIT_SPACE,
+        seeds = [MARKET_METADATA.as_bytes(), &market.key().to_bytes()],
+        bump
+    )]
+    market_metadata: Box<Account<'info, MarketMetadata>>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + MarketRegistry::INIT_SPACE,
+        seeds = [REGISTRY.as_bytes()],
+        bump
+    )]
+    registry: Box<Account<'info, MarketRegistry>>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + MarketEntry::INIT_SPACE,
+        seeds = [MARKET_ENTRY.as_bytes(), &registry.market_count.to_le_bytes()],
+        bump
+    )]
+    market_entry: Box<Account<'info, MarketEntry>>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + Category::INIT_SPACE,
+        seeds = [CATEGORY.as_bytes(), params.metadata.category.as_bytes()],
+        bump
+    )]
+    category: Box<Account<'info, Category>>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + CategoryPage::INIT_SPACE,
+        seeds = [
+            CATEGORY_PAGE.as_bytes(),
+            params.metadata.category.as_bytes(),
+            &category.append_page().to_le_bytes(),
+        ],
+        bump
+    )]
+    category_page: Box<Account<'info, CategoryPage>>,
+
     /// CHECK: passed to token metadata program
     #[account(mut,
         seeds = [
@@ -88,6 +149,12 @@ pub struct CreateMarket<'info> {
     )]
     global_yes_token_account: UncheckedAccount<'info>,
 
+    #[account(
+        associated_token::mint = no_token,
+        associated_token::authority = global_vault,
+    )]
+    global_no_token_account: Box<Account<'info, TokenAccount>>,
+
     #[account(address = system_program::ID)]
     system_program: Program<'info, System>,
     #[account(address = Rent::id())]
@@ -106,17 +173,237 @@ pub struct CreateMarket<'info> {
         constraint = global_config.team_wallet == team_wallet.key() @PredictionMarketError::IncorrectAuthority
     )]
     pub team_wallet: UncheckedAccount<'info>,
+
+    //  the parent of a conditional market, see `CreateMarketParams::condition`
+    #[account(mut)]
+    parent_market: Option<Box<Account<'info, Market>>>,
 }
 
 impl<'info> CreateMarket<'info> {
-    pub fn handler(&mut self, params: CreateMarketParams, global_vault_bump: u8) -> Result<()> {
+    pub fn handler(
+        &mut self,
+        params: CreateMarketParams,
+        global_vault_bump: u8,
+    ) -> Result<(CreateEvent, MarketMetadataEvent, MarketRegistryEvent)> {
         msg!("CreateMarket start");
 
-        //A decentralized prediction market platform built on Solana blockchain, inspired by Polymarket. This project enables users to create markets, trade positions, and resolve outcomes based on real-world events.    
-        // **Telegram**: [@Tru3B1iss](https://t.me/Tru3B1iss)
-        // **X (Twitter)**: [@XTruebliss](https://x.com/XTruebliss)
-        // **Discord**: [@trueb1iss](https://discord.com/users/1274339638668038187)
+        let global_config = &self.global_config;
+        let creator = &self.creator;
+        let yes_token: &Account<'info, Mint> = &self.yes_token;
+        let no_token: &Account<'info, Mint> = &self.no_token;
+        let global_yes_token_account = &self.global_yes_token_account;
+        let global_vault = &self.global_vault;
+        let yes_name = YES_NAME;
+
+        //  no token must come from mint_no_token with a fixed supply, all of it in the vault
+        require!(
+            no_token.mint_authority.is_none(),
+            PredictionMarketError::MintAuthorityEnabled
+        );
+        require!(
+            no_token.freeze_authority.is_none(),
+            PredictionMarketError::FreezeAuthorityEnabled
+        );
+        require!(
+            no_token.supply == global_config.token_supply_config
+                && self.global_no_token_account.amount == no_token.supply,
+            PredictionMarketError::InvalidAmount
+        );
+
+        params.metadata.validate()?;
+        params.trade_limits.validate(global_config)?;
+
+        //  a parent with a final outcome would void or resolve the market right away
+        match (params.condition, self.parent_market.as_deref_mut()) {
+            (None, None) => {}
+            (Some(condition), Some(parent)) => {
+                require!(
+                    parent.key() == condition.parent
+                        && condition.outcome <= TOKEN_TYPE_YES
+                        && !parent.status.is_final(),
+                    PredictionMarketError::InvalidCondition
+                );
+                //  the parent stays open until this market is closed
+                parent.open_conditional_markets = parent
+                    .open_conditional_markets
+                    .checked_add(1)
+                    .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+            }
+            _ => return err!(PredictionMarketError::InvalidCondition),
+        }
+
+        // validate start and end time
+        let clock = Clock::get()?;
+        let start_slot = params.start_slot.unwrap_or(clock.slot);
+        if let Some(start_slot) = params.start_slot {
+            require!(
+                start_slot >= clock.slot && start_slot - clock.slot <= MAX_START_SLOT_DELAY,
+                PredictionMarketError::InvalidStartTime
+            );
+        }
+        if let Some(ending_slot) = params.ending_slot {
+            require!(
+                ending_slot > start_slot,
+                PredictionMarketError::InvalidEndTime
+            );
+        }
+        let start_time = params.start_time.unwrap_or(clock.unix_timestamp);
+        if let Some(start_time) = params.start_time {
+            require!(
+                start_time >= clock.unix_timestamp
+                    && start_time - clock.unix_timestamp <= MAX_START_TIME_DELAY,
+                PredictionMarketError::InvalidStartTime
+            );
+        }
+        if let Some(end_time) = params.end_time {
+            require!(end_time > start_time, PredictionMarketError::InvalidEndTime);
+        }
+
+        // create global token account
+        associated_token::create(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: creator.to_account_info(),
+                associated_token: global_yes_token_account.to_account_info(),
+                authority: global_vault.to_account_info(),
+                mint: yes_token.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+            },
+        ))?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+
+        // mint tokens to bonding curve & team
+        token::mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                token::MintTo {
+                    mint: yes_token.to_account_info(),
+                    to: global_yes_token_account.to_account_info(),
+                    authority: global_vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            global_config.token_supply_config,
+        )?;
+
+        // create metadata
+        metadata::create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                self.mpl_token_metadata_program.to_account_info(),
+                metadata::CreateMetadataAccountsV3 {
+                    metadata: self.yes_token_metadata_account.to_account_info(),
+                    mint: yes_token.to_account_info(),
+                    mint_authority: global_vault.to_account_info(),
+                    payer: creator.to_account_info(),
+                    update_authority: global_vault.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    rent: self.rent.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            DataV2 {
+                name: yes_name.to_string(),
+                symbol: params.yes_symbol,
+                uri: params.yes_uri,
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            },
+            //  mutable so the name can show the outcome after resolution
+            true,
+            true,
+            None,
+        )?;
+
+        //  revoke mint authority
+        token::set_authority(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                token::SetAuthority {
+                    current_authority: global_vault.to_account_info(),
+                    account_or_mint: yes_token.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+
+        //  initialize market
+        let market = &mut self.market;
+        market.yes_token_mint = yes_token.key();
+        market.no_token_mint = no_token.key();
+        market.creator = creator.key();
+
+        market.initial_yes_token_reserves = global_config.initial_real_token_reserves_config;
+        market.real_yes_token_reserves = global_config.initial_real_token_reserves_config;
+        market.real_yes_sol_reserves = 0;
+        market.token_yes_total_supply = global_config.token_supply_config;
+
+        market.initial_no_token_reserves = global_config.initial_real_token_reserves_config;
+        market.real_no_token_reserves = global_config.initial_real_token_reserves_config;
+        market.real_no_sol_reserves = 0;
+        market.token_no_total_supply = global_config.token_supply_config;
+
+        market.status = MarketStatus::Pending;
+        market.start_slot = params.start_slot;
+        market.ending_slot = params.ending_slot;
+        market.start_time = params.start_time;
+        market.end_time = params.end_time;
+        market.trade_limits = params.trade_limits;
+        market.condition = params.condition;
+
+        market.lps = Vec::new();
+        market.total_lp_amount = 0;
+        market.lp_fees = 0;
+        market.open_parlays = 0;
+        market.open_conditional_markets = 0;
+        market.id = self.registry.market_count;
+        self.no_token_record.market = Some(market.key());
+
+        let market_metadata = &mut self.market_metadata;
+        market_metadata.market = market.key();
+        market_metadata.creator = market.creator;
+        market_metadata.set(params.metadata)?;
+
+        let registry_event = self.category.register(
+            &mut self.registry,
+            &mut self.market_entry,
+            &mut self.category_page,
+            &market_metadata.category,
+            market.key(),
+        )?;
+
+        msg!("CreateMarket end");
+
+        let create_event = CreateEvent {
+            creator: market.creator,
+            market: market.key(),
+
+            token_yes: market.yes_token_mint,
+            metadata_yes: self.yes_token_metadata_account.key(),
+            token_yes_total_supply: market.token_yes_total_supply,
+            real_yes_sol_reserves: market.real_yes_sol_reserves,
+
+            token_no: market.no_token_mint,
+            metadata_no: self.no_token_metadata_account.key(),
+            token_no_total_supply: market.token_no_total_supply,
+            real_no_sol_reserves: market.real_no_sol_reserves,
+
+            start_slot: market.start_slot.unwrap_or(clock.slot),
+            ending_slot: market.ending_slot.unwrap_or(u64::MAX),
+            start_time: market.start_time.unwrap_or(clock.unix_timestamp),
+            end_time: market.end_time.unwrap_or(i64::MAX),
+
+            max_position_per_wallet: market.trade_limits.max_position_per_wallet,
+            min_trade_lamports: market.trade_limits.min_trade_lamports,
+            max_trade_lamports: market.trade_limits.max_trade_lamports,
 
-        Ok(())
+            condition: market.condition,
+        };
+        Ok((create_event, market_metadata.into_event(), registry_event))
     }
 }
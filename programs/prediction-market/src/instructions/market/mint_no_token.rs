@@ -1,6 +1,7 @@
 use crate::{
-    constants::{CONFIG, GLOBAL, METADATA, NO_NAME},
-    state::config::*,
+    constants::{CONFIG, GLOBAL, METADATA, NO_NAME, NO_TOKEN},
+    events::MintNoTokenEvent,
+    state::{config::*, market::NoTokenRecord},
 };
 use anchor_lang::{prelude::*, solana_program::sysvar::SysvarId, system_program};
 use anchor_spl::{
@@ -9,6 +10,7 @@ use anchor_spl::{
     token::{self, spl_token::instruction::AuthorityType, Mint, Token},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct MintNoToken<'info> {
     #[account(
@@ -37,6 +39,15 @@ pub struct MintNoToken<'info> {
     )]
     no_token: Box<Account<'info, Mint>>,
 
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + NoTokenRecord::INIT_SPACE,
+        seeds = [NO_TOKEN.as_bytes(), no_token.key().as_ref()],
+        bump
+    )]
+    no_token_record: Box<Account<'info, NoTokenRecord>>,
+
     /// CHECK: passed to token metadata program
     #[account(
         mut,
@@ -83,14 +94,18 @@ impl<'info> MintNoToken<'info> {
         no_symbol: String,
         no_uri: String,
         global_vault_bump: u8,
-    ) -> Result<()> {
+    ) -> Result<MintNoTokenEvent> {
         let global_config = &self.global_config;
         let creator = &self.creator;
-        let no_token: &Box<Account<'info, Mint>> = &self.no_token;
+        let no_token: &Account<'info, Mint> = &self.no_token;
         let global_no_token_account = &self.global_no_token_account;
         let global_vault = &self.global_vault;
         let no_name = NO_NAME;
 
+        //  create_market checks the record before using the mint
+        self.no_token_record.creator = creator.key();
+        self.no_token_record.market = None;
+
         // create global token account
         associated_token::create(CpiContext::new(
             self.associated_token_program.to_account_info(),
@@ -144,7 +159,8 @@ impl<'info> MintNoToken<'info> {
                 collection: None,
                 uses: None,
             },
-            false,
+            //  mutable so the name can show the outcome after resolution
+            true,
             true,
             None,
         )?;
@@ -163,6 +179,11 @@ impl<'info> MintNoToken<'info> {
             None,
         )?;
 
-        Ok(())
+        Ok(MintNoTokenEvent {
+            creator: creator.key(),
+            token_no: no_token.key(),
+            metadata_no: self.no_token_metadata_account.key(),
+            token_no_total_supply: global_config.token_supply_config,
+        })
     }
 }
@@ -1,6 +1,21 @@
 pub mod add_liquidity;
+pub mod archive_market;
+pub mod batch_swap;
+pub mod close_market;
+pub mod close_user_info;
+pub mod cpi_redeem;
+pub mod cpi_swap;
 pub mod create_market;
 pub mod mint_no_token;
+pub mod observe;
+pub mod propose_resolution;
+pub mod quote_swap;
+pub mod refund;
 pub mod resolution;
 pub mod swap;
+pub mod update_market_metadata;
+pub mod update_outcome_metadata;
+pub mod update_trade_limits;
+pub mod void_conditional_market;
+pub mod void_market;
 pub mod withdraw_liquidity;
@@ -0,0 +1,27 @@
+use crate::{
+    constants::MARKET,
+    errors::PredictionMarketError,
+    state::{market::*, price_oracle::Twap},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Observe<'info> {
+    #[account(
+        seeds = [MARKET.as_bytes(), &market.yes_token_mint.to_bytes(), &market.no_token_mint.to_bytes()],
+        bump,
+    )]
+    market: Box<Account<'info, Market>>,
+}
+
+impl<'info> Observe<'info> {
+    pub fn handler(&self, window_slots: u64) -> Result<Twap> {
+        let (yes_price, no_price) = self
+            .market
+            .implied_prices()
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        self.market
+            .price_oracle
+            .observe(Clock::get()?.slot, window_slots, yes_price, no_price)
+    }
+}
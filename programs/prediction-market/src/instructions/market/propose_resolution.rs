@@ -0,0 +1,83 @@
+use crate::{
+    constants::{CONFIG, MARKET},
+    errors::*,
+    events::ResolutionProposedEvent,
+    math::TOKEN_TYPE_YES,
+    state::{config::*, market::*},
+};
+use anchor_lang::prelude::*;
+
+//  market statuses the instruction runs in, a pending proposal can be corrected
+const ACCEPTED: &[MarketStatus] = &[
+    MarketStatus::Trading,
+    MarketStatus::Closed,
+    MarketStatus::ResolutionProposed,
+];
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeResolution<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [MARKET.as_bytes(), &market.yes_token_mint.to_bytes(), &market.no_token_mint.to_bytes()],
+        bump,
+    )]
+    market: Box<Account<'info, Market>>,
+
+    #[account(
+        constraint = authority.key() == global_config.authority @PredictionMarketError::IncorrectAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    //  required for a conditional market, which resolves only once its parent met the condition
+    parent_market: Option<Box<Account<'info, Market>>>,
+}
+
+impl ProposeResolution<'_> {
+    pub fn handler(&mut self, winning_token_type: u8) -> Result<ResolutionProposedEvent> {
+        require!(
+            winning_token_type <= TOKEN_TYPE_YES,
+            PredictionMarketError::RESOLUTIONTOKEYTYPEERROR
+        );
+
+        let clock = Clock::get()?;
+        self.market
+            .sync(&clock, self.global_config.min_sol_liquidity)?;
+        self.market.require_status(ACCEPTED)?;
+        if let Some(condition) = self.market.condition {
+            let parent = self
+                .parent_market
+                .as_ref()
+                .filter(|parent| parent.key() == condition.parent)
+                .ok_or(PredictionMarketError::InvalidCondition)?;
+            match self.market.condition_met(parent) {
+                Some(true) => {}
+                Some(false) => return err!(PredictionMarketError::ConditionFailed),
+                None => return err!(PredictionMarketError::ConditionPending),
+            }
+        }
+
+        //  proposing ends trading early
+        if self.market.status == MarketStatus::Trading {
+            self.market.transition(MarketStatus::Closed)?;
+        }
+        if self.market.status == MarketStatus::Closed {
+            self.market.transition(MarketStatus::ResolutionProposed)?;
+        }
+        self.market.winning_token_type = Some(winning_token_type);
+
+        Ok(ResolutionProposedEvent {
+            authority: self.authority.key(),
+            market: self.market.key(),
+            winning_token_type,
+
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
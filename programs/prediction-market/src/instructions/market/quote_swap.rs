@@ -0,0 +1,65 @@
+use crate::{
+    constants::{CONFIG, MARKET, USER_STATS},
+    state::{config::*, market::*, user_stats::UserStats},
+    utils::require_valid_slot,
+};
+use anchor_lang::prelude::*;
+
+//  market statuses the instruction runs in, the same as `swap`
+const ACCEPTED: &[MarketStatus] = &[MarketStatus::Trading];
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [MARKET.as_bytes(), &market.yes_token_mint.to_bytes(), &market.no_token_mint.to_bytes()],
+        bump,
+    )]
+    market: Box<Account<'info, Market>>,
+
+    //  quotes at the fee tier of this trader, at the flat fees without it
+    #[account(
+        seeds = [USER_STATS.as_bytes(), &user_stats.user.to_bytes()],
+        bump,
+    )]
+    user_stats: Option<Box<Account<'info, UserStats>>>,
+}
+
+impl<'info> QuoteSwap<'info> {
+    pub fn handler(
+        &self,
+        amount: u64,
+        direction: u8,
+        token_type: u8,
+        minimum_receive_amount: u64,
+        valid_until_slot: Option<u64>,
+        max_price_impact_bps: Option<u64>,
+    ) -> Result<SwapQuote> {
+        let clock = Clock::get()?;
+        require_valid_slot(&clock, valid_until_slot)?;
+
+        //  the status `swap` would see, on a copy as nothing is written back
+        let mut market = Market::clone(&self.market);
+        market.sync(&clock, self.global_config.min_sol_liquidity)?;
+        market.require_status(ACCEPTED)?;
+
+        let volume = self
+            .user_stats
+            .as_ref()
+            .map_or(0, |user_stats| user_stats.volume(clock.unix_timestamp));
+        market.quote_swap(
+            &self.global_config,
+            volume,
+            amount,
+            direction,
+            token_type,
+            minimum_receive_amount,
+            max_price_impact_bps,
+        )
+    }
+}
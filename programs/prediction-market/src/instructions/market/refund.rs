@@ -0,0 +1,125 @@
+use crate::{
+    constants::{GLOBAL, MARKET, USERINFO},
+    events::RefundEvent,
+    state::market::*,
+    utils::{token_balance, token_burn_with_signer},
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::{self, Mint, Token};
+
+//  market statuses the instruction runs in
+const ACCEPTED: &[MarketStatus] = &[MarketStatus::Invalid];
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(
+        mut,
+        seeds = [MARKET.as_bytes(), &market.yes_token_mint.to_bytes(), &market.no_token_mint.to_bytes()],
+        bump,
+    )]
+    market: Box<Account<'info, Market>>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        mut,
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    pub global_vault: AccountInfo<'info>,
+
+    #[account(mut, address = market.yes_token_mint)]
+    pub yes_token: Box<Account<'info, Mint>>,
+    #[account(mut, address = market.no_token_mint)]
+    pub no_token: Box<Account<'info, Mint>>,
+
+    /// CHECK: ata of user, the refunded yes tokens are burned from it
+    #[account(
+        mut,
+        seeds = [
+            user.key().as_ref(),
+            anchor_spl::token::spl_token::ID.as_ref(),
+            yes_token.key().as_ref(),
+        ],
+        bump,
+        seeds::program = anchor_spl::associated_token::ID
+    )]
+    user_yes_ata: AccountInfo<'info>,
+
+    /// CHECK: ata of user, the refunded no tokens are burned from it
+    #[account(
+        mut,
+        seeds = [
+            user.key().as_ref(),
+            anchor_spl::token::spl_token::ID.as_ref(),
+            no_token.key().as_ref(),
+        ],
+        bump,
+        seeds::program = anchor_spl::associated_token::ID
+    )]
+    user_no_ata: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [USERINFO.as_bytes(), &user.key().to_bytes(), &market.key().to_bytes()],
+        bump,
+    )]
+    pub user_info: Box<Account<'info, UserInfo>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+impl Refund<'_> {
+    pub fn handler(&mut self, global_vault_bump: u8) -> Result<RefundEvent> {
+        self.market.require_status(ACCEPTED)?;
+
+        //  the position's tokens still in the wallet are refunded at cost and burned
+        let yes_amount = token_balance(&self.user_yes_ata)?.min(self.user_info.yes_balance);
+        let no_amount = token_balance(&self.user_no_ata)?.min(self.user_info.no_balance);
+        for (ata, mint, amount) in [
+            (&self.user_yes_ata, &self.yes_token, yes_amount),
+            (&self.user_no_ata, &self.no_token, no_amount),
+        ] {
+            if amount > 0 {
+                token_burn_with_signer(
+                    ata.to_account_info(),
+                    mint.to_account_info(),
+                    self.user.to_account_info(),
+                    &self.token_program,
+                    &[],
+                    amount,
+                )?;
+            }
+        }
+
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+        let sol_amount = self.market.refund(
+            &mut self.global_vault,
+            &self.user,
+            signer_seeds,
+            &mut self.user_info,
+            yes_amount,
+            no_amount,
+            &self.system_program,
+        )?;
+        self.market.try_settle()?;
+
+        Ok(RefundEvent {
+            user: self.user.key(),
+            market: self.market.key(),
+
+            yes_amount,
+            no_amount,
+            sol_amount,
+
+            timestamp: Clock::get()?.unix_timestamp,
+        })
+    }
+}
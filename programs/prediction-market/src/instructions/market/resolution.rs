@@ -1,7 +1,9 @@
 use crate::{
     constants::{CONFIG, GLOBAL, MARKET, USERINFO},
     errors::PredictionMarketError,
+    events::{PayoutEvent, ResolutionEvent},
     state::{config::*, market::*},
+    utils::token_burn_with_signer,
 };
 use anchor_lang::{prelude::*, system_program};
 use anchor_spl::{
@@ -9,6 +11,7 @@ use anchor_spl::{
     token::{self, Mint, Token},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Resolution<'info> {
     #[account(
@@ -33,9 +36,37 @@ pub struct Resolution<'info> {
     )]
     pub global_vault: AccountInfo<'info>,
 
+    #[account(mut)]
     pub yes_token: Box<Account<'info, Mint>>,
+    #[account(mut)]
     pub no_token: Box<Account<'info, Mint>>,
 
+    /// CHECK: ata of user, the redeemed yes tokens are burned from it
+    #[account(
+        mut,
+        seeds = [
+            user.key().as_ref(),
+            anchor_spl::token::spl_token::ID.as_ref(),
+            yes_token.key().as_ref(),
+        ],
+        bump,
+        seeds::program = anchor_spl::associated_token::ID
+    )]
+    user_yes_ata: AccountInfo<'info>,
+
+    /// CHECK: ata of user, the redeemed no tokens are burned from it
+    #[account(
+        mut,
+        seeds = [
+            user.key().as_ref(),
+            anchor_spl::token::spl_token::ID.as_ref(),
+            no_token.key().as_ref(),
+        ],
+        bump,
+        seeds::program = anchor_spl::associated_token::ID
+    )]
+    user_no_ata: AccountInfo<'info>,
+
     #[account(
         mut,
         seeds = [USERINFO.as_bytes(), &user.key().to_bytes(), &market.key().to_bytes()],
@@ -43,11 +74,13 @@ pub struct Resolution<'info> {
     )]
     pub user_info: Box<Account<'info, UserInfo>>,
 
+    //  receives the payout and signs for burning the redeemed tokens
     #[account(mut)]
-    pub user: AccountInfo<'info>,
+    pub user: Signer<'info>,
 
+    //  confirms a proposed outcome with the first payout, later payouts need only the holder
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub authority: Option<Signer<'info>>,
 
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
@@ -59,18 +92,88 @@ pub struct Resolution<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+//  market statuses the instruction runs in, the first payout confirms the proposal
+const ACCEPTED: &[MarketStatus] = &[MarketStatus::ResolutionProposed, MarketStatus::Resolved];
+
 impl<'info> Resolution<'info>{
-    pub fn handler(&mut self, yes_amount: u64, no_amount: u64 ,token_type: u8, is_completed: bool ,global_vault_bump:u8)-> Result<()>{
-        require!(
-            self.authority.key() == self.global_config.authority.key(),
-            PredictionMarketError::InvalidMigrationAuthority
-        );
-        
-        //A decentralized prediction market platform built on Solana blockchain, inspired by Polymarket. This project enables users to create markets, trade positions, and resolve outcomes based on real-world events.    
-        // **Telegram**: [@Tru3B1iss](https://t.me/Tru3B1iss)
-        // **X (Twitter)**: [@XTruebliss](https://x.com/XTruebliss)
-        // **Discord**: [@trueb1iss](https://discord.com/users/1274339638668038187)
-
-        Ok(())
+    pub fn handler(&mut self, yes_amount: u64, no_amount: u64 ,token_type: u8 ,global_vault_bump:u8)-> Result<(Option<ResolutionEvent>, PayoutEvent)>{
+        self.market.require_status(ACCEPTED)?;
+
+        let was_proposed = self.market.status == MarketStatus::ResolutionProposed;
+        if was_proposed {
+            require!(
+                self.authority
+                    .as_ref()
+                    .is_some_and(|authority| authority.key() == self.global_config.authority),
+                PredictionMarketError::InvalidMigrationAuthority
+            );
+            require!(
+                self.market.winning_token_type == Some(token_type),
+                PredictionMarketError::RESOLUTIONTOKEYTYPEERROR
+            );
+            self.market.transition(MarketStatus::Resolved)?;
+        }
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            GLOBAL.as_bytes(),
+            &[global_vault_bump],
+        ]];
+
+        //  the tokens are gone before the position pays, so they cannot be sold or redeemed twice
+        for (ata, mint, amount) in [
+            (&self.user_yes_ata, &self.yes_token, yes_amount),
+            (&self.user_no_ata, &self.no_token, no_amount),
+        ] {
+            if amount > 0 {
+                token_burn_with_signer(
+                    ata.to_account_info(),
+                    mint.to_account_info(),
+                    self.user.to_account_info(),
+                    &self.token_program,
+                    &[],
+                    amount,
+                )?;
+            }
+        }
+
+        let payout = self.market.resolution(
+            &mut self.global_vault,
+            &mut self.user.to_account_info(),
+            signer_seeds,
+            &mut self.user_info,
+            yes_amount,
+            no_amount,
+            token_type,
+            &self.system_program,
+        )?;
+        self.market.try_settle()?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        let resolution_event = was_proposed.then(|| ResolutionEvent {
+            authority: self.global_config.authority,
+            market: self.market.key(),
+            winning_token_type: token_type,
+
+            real_yes_sol_reserves: self.market.real_yes_sol_reserves,
+            real_no_sol_reserves: self.market.real_no_sol_reserves,
+            total_lp_amount: self.market.total_lp_amount,
+
+            timestamp,
+        });
+
+        Ok((
+            resolution_event,
+            PayoutEvent {
+                user: self.user.key(),
+                market: self.market.key(),
+                winning_token_type: token_type,
+
+                yes_amount,
+                no_amount,
+                sol_amount: payout,
+
+                timestamp,
+            },
+        ))
     }
 }
@@ -1,7 +1,9 @@
 use crate::{
-    constants::{CONFIG, GLOBAL, MARKET, USERINFO},
+    constants::{CONFIG, GLOBAL, MARKET, USERINFO, USER_STATS},
     errors::PredictionMarketError,
-    state::{config::*, market::*},
+    events::TradeEvent,
+    state::{config::*, market::*, referrer::Referrer, user_stats::UserStats},
+    utils::require_valid_slot,
 };
 use anchor_lang::{prelude::*, system_program};
 use anchor_spl::{
@@ -9,6 +11,10 @@ use anchor_spl::{
     token::{self, Mint, Token},
 };
 
+//  market statuses the instruction runs in
+const ACCEPTED: &[MarketStatus] = &[MarketStatus::Trading];
+
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Swap<'info> {
     #[account(
@@ -105,6 +111,16 @@ pub struct Swap<'info> {
     )]
     pub user_info: Box<Account<'info, UserInfo>>,
 
+    //  rolling volume that picks the user's fee tier
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [USER_STATS.as_bytes(), &user.key().to_bytes()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
     #[account(mut)]
     pub user: Signer<'info>,
     
@@ -116,35 +132,33 @@ pub struct Swap<'info> {
 
     #[account(address = associated_token::ID)]
     pub associated_token_program: Program<'info, AssociatedToken>,
+
+    //  frontend credited with `referral_fee_bps` of the platform fee, registered by the
+    //  config authority
+    #[account(mut)]
+    pub referrer: Option<Box<Account<'info, Referrer>>>,
 }
 
 
 impl<'info> Swap<'info> { 
 
-    pub fn handler(&mut self, amount: u64, direction: u8, token_type: u8 ,minimum_receive_amount: u64, global_vault_bump:u8) -> Result<()> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn handler(&mut self, amount: u64, direction: u8, token_type: u8 ,minimum_receive_amount: u64, valid_until_slot: Option<u64>, max_price_impact_bps: Option<u64>, global_vault_bump:u8) -> Result<TradeEvent> {
 
         let market = &mut self.market;
         
-
         let clock = Clock::get()?;
-        // validate end time
-        if let Some(ending_slot) = market.ending_slot {
-            require!(
-                ending_slot >= clock.slot,
-                PredictionMarketError::InvalidEndTime
-            )
-        }
+        require_valid_slot(&clock, valid_until_slot)?;
 
-        //  check market is not completed
-        require!(
-            market.is_completed == false,
-            PredictionMarketError::CurveAlreadyCompleted
-        );
+
+        //  open once started and seeded with enough liquidity, until the end of the window
+        market.sync(&clock, self.global_config.min_sol_liquidity)?;
+        market.require_status(ACCEPTED)?;
 
         let user_info_pda = &mut self.user_info;
 
         // Check if the user_info account exists
-        if user_info_pda.is_initialized == false {
+        if !user_info_pda.is_initialized {
             // If it doesn't exist, initialize it
             msg!("User info does not exist, initializing...");
             
@@ -153,6 +167,7 @@ impl<'info> Swap<'info> {
             user_info_pda.user = self.user.key();
             user_info_pda.yes_balance = 0;
             user_info_pda.no_balance = 0;
+            user_info_pda.lp_amount = 0;
             user_info_pda.is_lp = false;
             user_info_pda.is_initialized = true;
             msg!("User info initialized.");
@@ -160,6 +175,10 @@ impl<'info> Swap<'info> {
             msg!("User info already exists.");
         }
 
+        if self.user_stats.user == Pubkey::default() {
+            self.user_stats.user = self.user.key();
+        }
+
         let source = &mut self.global_vault.to_account_info();
         let team_wallet = &mut self.team_wallet;
 
@@ -220,35 +239,36 @@ impl<'info> Swap<'info> {
         ]];
 
 
-        market.swap(
-            &*self.global_config,
+        let event = market.swap(
+            &self.global_config,
             
-            yes_token.as_ref(),
             &mut self.global_yes_ata,
             user_yes_ata,
 
-            no_token.as_ref(),
             &mut self.global_no_ata,
             user_no_ata,
             
             source,
             team_wallet,
+            self.referrer.as_deref_mut(),
             
             amount,
             direction,
             token_type,
             minimum_receive_amount,
+            max_price_impact_bps,
             
             &self.user,
             signer_seeds,
 
             user_info_pda,
+            &mut self.user_stats,
 
             &self.token_program,
             &self.system_program,
         )?;
 
-        Ok(())
+        Ok(event)
     }
 
 }
\ No newline at end of file
@@ -0,0 +1,51 @@
+use crate::{
+    constants::{MARKET, MARKET_METADATA},
+    errors::*,
+    events::{IntoEvent, MarketMetadataEvent},
+    state::{market::*, market_metadata::*},
+};
+use anchor_lang::prelude::*;
+
+//  market statuses the instruction runs in
+const ACCEPTED: &[MarketStatus] = &[MarketStatus::Pending];
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateMarketMetadata<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [MARKET.as_bytes(), &market.yes_token_mint.to_bytes(), &market.no_token_mint.to_bytes()],
+        bump,
+        has_one = creator @PredictionMarketError::IncorrectAuthority,
+    )]
+    market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_METADATA.as_bytes(), &market.key().to_bytes()],
+        bump,
+    )]
+    market_metadata: Box<Account<'info, MarketMetadata>>,
+}
+
+impl UpdateMarketMetadata<'_> {
+    pub fn handler(&mut self, params: MarketMetadataParams) -> Result<MarketMetadataEvent> {
+        //  traders rely on the description once the market opens
+        let clock = Clock::get()?;
+        self.market.require_status(ACCEPTED)?;
+        require!(
+            self.market.is_scheduled(&clock),
+            PredictionMarketError::MarketAlreadyStarted
+        );
+
+        //  the category pages keep listing the market under its original category
+        require!(
+            params.category == self.market_metadata.category,
+            PredictionMarketError::CategoryChangeNotAllowed
+        );
+        self.market_metadata.set(params)?;
+
+        Ok(self.market_metadata.into_event())
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{
+    constants::{CONFIG, GLOBAL, LOST_SUFFIX, MARKET, METADATA, NO_NAME, WON_SUFFIX, YES_NAME},
+    errors::*,
+    events::OutcomeMetadataEvent,
+    math::TOKEN_TYPE_YES,
+    state::{config::*, market::*},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::{self, mpl_token_metadata::types::DataV2, Metadata, MetadataAccount};
+
+//  market statuses the instruction runs in, once the outcome is confirmed
+const ACCEPTED: &[MarketStatus] = &[MarketStatus::Resolved, MarketStatus::Settled];
+
+//  permissionless once the outcome is confirmed, renames both outcome tokens so wallets show
+//  which one redeems, their uris are the config's won/lost base uri followed by the mint
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateOutcomeMetadata<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [MARKET.as_bytes(), &market.yes_token_mint.to_bytes(), &market.no_token_mint.to_bytes()],
+        bump,
+    )]
+    market: Box<Account<'info, Market>>,
+
+    /// CHECK: global vault pda, update authority of the token metadata
+    #[account(
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    pub global_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            METADATA.as_bytes(),
+            metadata::ID.as_ref(),
+            market.yes_token_mint.as_ref(),
+        ],
+        bump,
+        seeds::program = metadata::ID
+    )]
+    yes_token_metadata_account: Box<Account<'info, MetadataAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            METADATA.as_bytes(),
+            metadata::ID.as_ref(),
+            market.no_token_mint.as_ref(),
+        ],
+        bump,
+        seeds::program = metadata::ID
+    )]
+    no_token_metadata_account: Box<Account<'info, MetadataAccount>>,
+
+    pub signer: Signer<'info>,
+
+    #[account(address = metadata::ID)]
+    mpl_token_metadata_program: Program<'info, Metadata>,
+}
+
+impl UpdateOutcomeMetadata<'_> {
+    pub fn handler(&mut self, global_vault_bump: u8) -> Result<OutcomeMetadataEvent> {
+        self.market.require_status(ACCEPTED)?;
+        //  a voided market settles without a winner
+        let winning_token_type = self
+            .market
+            .winning_token_type
+            .ok_or(PredictionMarketError::InvalidMarketStatus)?;
+
+        let won_uri = &self.global_config.won_uri;
+        let lost_uri = &self.global_config.lost_uri;
+        require!(
+            !won_uri.is_empty() && !lost_uri.is_empty(),
+            PredictionMarketError::OutcomeUrisNotConfigured
+        );
+        let yes_won = winning_token_type == TOKEN_TYPE_YES;
+        let (yes_suffix, yes_uri) = if yes_won {
+            (WON_SUFFIX, won_uri)
+        } else {
+            (LOST_SUFFIX, lost_uri)
+        };
+        let (no_suffix, no_uri) = if yes_won {
+            (LOST_SUFFIX, lost_uri)
+        } else {
+            (WON_SUFFIX, won_uri)
+        };
+        let yes_name = format!("{YES_NAME} {yes_suffix}");
+        let no_name = format!("{NO_NAME} {no_suffix}");
+
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+        for (account, name, uri) in [
+            (&self.yes_token_metadata_account, &yes_name, yes_uri),
+            (&self.no_token_metadata_account, &no_name, no_uri),
+        ] {
+            //  tokens of markets created before the names could change
+            require!(
+                account.is_mutable,
+                PredictionMarketError::ImmutableTokenMetadata
+            );
+            metadata::update_metadata_accounts_v2(
+                CpiContext::new_with_signer(
+                    self.mpl_token_metadata_program.to_account_info(),
+                    metadata::UpdateMetadataAccountsV2 {
+                        metadata: account.to_account_info(),
+                        update_authority: self.global_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                None,
+                Some(DataV2 {
+                    name: name.clone(),
+                    //  Metaplex pads the symbol with nul bytes
+                    symbol: account.symbol.trim_end_matches('\0').to_string(),
+                    uri: format!("{uri}{}", account.mint),
+                    seller_fee_basis_points: 0,
+                    creators: None,
+                    collection: None,
+                    uses: None,
+                }),
+                None,
+                None,
+            )?;
+        }
+
+        Ok(OutcomeMetadataEvent {
+            authority: self.signer.key(),
+            market: self.market.key(),
+            winning_token_type,
+            yes_name,
+            no_name,
+
+            timestamp: Clock::get()?.unix_timestamp,
+        })
+    }
+}
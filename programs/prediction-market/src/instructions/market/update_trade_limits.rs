@@ -0,0 +1,48 @@
+use crate::{
+    constants::{CONFIG, MARKET},
+    errors::*,
+    events::TradeLimitsEvent,
+    state::{config::*, market::*},
+};
+use anchor_lang::prelude::*;
+
+//  market statuses the instruction runs in
+const ACCEPTED: &[MarketStatus] = &[MarketStatus::Pending, MarketStatus::Trading];
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateTradeLimits<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MARKET.as_bytes(), &market.yes_token_mint.to_bytes(), &market.no_token_mint.to_bytes()],
+        bump,
+        has_one = creator @PredictionMarketError::IncorrectAuthority,
+    )]
+    market: Box<Account<'info, Market>>,
+}
+
+impl UpdateTradeLimits<'_> {
+    pub fn handler(&mut self, trade_limits: TradeLimits) -> Result<TradeLimitsEvent> {
+        self.market.require_status(ACCEPTED)?;
+        //  existing positions above a lowered cap can still be sold down
+        trade_limits.validate(&self.global_config)?;
+        self.market.trade_limits = trade_limits;
+
+        Ok(TradeLimitsEvent {
+            market: self.market.key(),
+            creator: self.creator.key(),
+
+            max_position_per_wallet: trade_limits.max_position_per_wallet,
+            min_trade_lamports: trade_limits.min_trade_lamports,
+            max_trade_lamports: trade_limits.max_trade_lamports,
+        })
+    }
+}
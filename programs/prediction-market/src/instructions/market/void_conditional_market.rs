@@ -0,0 +1,51 @@
+use crate::{constants::MARKET, errors::*, events::MarketVoidedEvent, state::market::*};
+use anchor_lang::prelude::*;
+
+//  market statuses the instruction runs in, any status before a confirmed outcome
+const ACCEPTED: &[MarketStatus] = &[
+    MarketStatus::Pending,
+    MarketStatus::Trading,
+    MarketStatus::Closed,
+    MarketStatus::ResolutionProposed,
+];
+
+//  permissionless once the parent resolved against the condition, positions are refunded at cost
+#[event_cpi]
+#[derive(Accounts)]
+pub struct VoidConditionalMarket<'info> {
+    #[account(
+        mut,
+        seeds = [MARKET.as_bytes(), &market.yes_token_mint.to_bytes(), &market.no_token_mint.to_bytes()],
+        bump,
+    )]
+    market: Box<Account<'info, Market>>,
+
+    #[account(
+        constraint = market.condition.is_some_and(|condition| condition.parent == parent_market.key())
+            @PredictionMarketError::InvalidCondition,
+    )]
+    parent_market: Box<Account<'info, Market>>,
+
+    pub signer: Signer<'info>,
+}
+
+impl VoidConditionalMarket<'_> {
+    pub fn handler(&mut self) -> Result<MarketVoidedEvent> {
+        self.market.require_status(ACCEPTED)?;
+        match self.market.condition_met(&self.parent_market) {
+            Some(false) => {}
+            Some(true) => return err!(PredictionMarketError::ConditionMet),
+            None => return err!(PredictionMarketError::ConditionPending),
+        }
+        self.market.winning_token_type = None;
+        self.market.transition(MarketStatus::Invalid)?;
+        self.market.try_settle()?;
+
+        Ok(MarketVoidedEvent {
+            authority: self.signer.key(),
+            market: self.market.key(),
+
+            timestamp: Clock::get()?.unix_timestamp,
+        })
+    }
+}
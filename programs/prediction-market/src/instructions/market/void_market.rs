@@ -0,0 +1,53 @@
+use crate::{
+    constants::{CONFIG, MARKET},
+    errors::*,
+    events::MarketVoidedEvent,
+    state::{config::*, market::*},
+};
+use anchor_lang::prelude::*;
+
+//  market statuses the instruction runs in, any status before a confirmed outcome
+const ACCEPTED: &[MarketStatus] = &[
+    MarketStatus::Pending,
+    MarketStatus::Trading,
+    MarketStatus::Closed,
+    MarketStatus::ResolutionProposed,
+];
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct VoidMarket<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [MARKET.as_bytes(), &market.yes_token_mint.to_bytes(), &market.no_token_mint.to_bytes()],
+        bump,
+    )]
+    market: Box<Account<'info, Market>>,
+
+    #[account(
+        constraint = authority.key() == global_config.authority @PredictionMarketError::IncorrectAuthority,
+    )]
+    pub authority: Signer<'info>,
+}
+
+impl VoidMarket<'_> {
+    pub fn handler(&mut self) -> Result<MarketVoidedEvent> {
+        self.market.require_status(ACCEPTED)?;
+        self.market.winning_token_type = None;
+        self.market.transition(MarketStatus::Invalid)?;
+        self.market.try_settle()?;
+
+        Ok(MarketVoidedEvent {
+            authority: self.authority.key(),
+            market: self.market.key(),
+
+            timestamp: Clock::get()?.unix_timestamp,
+        })
+    }
+}
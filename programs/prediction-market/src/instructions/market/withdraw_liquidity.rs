@@ -1,6 +1,7 @@
 use crate::{
     constants::{CONFIG, GLOBAL, MARKET, USERINFO},
     errors::PredictionMarketError,
+    events::WithdrawLiquidityEvent,
     state::{config::*, market::*},
 };
 use anchor_lang::{prelude::*, system_program};
@@ -9,6 +10,17 @@ use anchor_spl::{
     token::{self, Mint, Token},
 };
 
+//  market statuses the instruction runs in, a trading market only before its first trade
+const ACCEPTED: &[MarketStatus] = &[
+    MarketStatus::Pending,
+    MarketStatus::Trading,
+    MarketStatus::Closed,
+    MarketStatus::ResolutionProposed,
+    MarketStatus::Resolved,
+    MarketStatus::Invalid,
+];
+
+#[event_cpi]
 #[derive(Accounts)]
 pub struct WithdrawLiquidity<'info> {
     #[account(
@@ -65,15 +77,46 @@ pub struct WithdrawLiquidity<'info> {
 }
 
 impl<'info> WithdrawLiquidity<'info> { 
-    pub fn handler(&mut self, amount: u64, global_vault_bump:u8) -> Result<()> {
+    pub fn handler(&mut self, amount: u64, global_vault_bump:u8) -> Result<WithdrawLiquidityEvent> {
         //validate user is lp
-        require!(self.user_info.is_lp == true, PredictionMarketError::WITHDRAWNOTLPERROR);
+        require!(self.user_info.is_lp, PredictionMarketError::WITHDRAWNOTLPERROR);
+        self.market
+            .sync(&Clock::get()?, self.global_config.min_sol_liquidity)?;
+        self.market.require_status(ACCEPTED)?;
+        //  the depth traders priced against stays until trading closes
+        require!(
+            self.market.status != MarketStatus::Trading || !self.market.has_trades(),
+            PredictionMarketError::IncorrectLaunchPhase
+        );
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            GLOBAL.as_bytes(),
+            &[global_vault_bump],
+        ]];
+
+        let fee_amount = self.market.withdraw_liquidity(
+            &mut self.global_vault,
+            &self.user,
+            amount,
+            signer_seeds,
+            &mut self.user_info,
+            &self.system_program,
+        )?;
+        self.market.try_settle()?;
+
+        Ok(WithdrawLiquidityEvent {
+            user: self.user.key(),
+            market: self.market.key(),
+
+            sol_amount: amount,
+            fee_amount,
+            user_lp_amount: self.user_info.lp_amount,
+            total_lp_amount: self.market.total_lp_amount,
 
-        //A decentralized prediction market platform built on Solana blockchain, inspired by Polymarket. This project enables users to create markets, trade positions, and resolve outcomes based on real-world events.    
-        // **Telegram**: [@Tru3B1iss](https://t.me/Tru3B1iss)
-        // **X (Twitter)**: [@XTruebliss](https://x.com/XTruebliss)
-        // **Discord**: [@trueb1iss](https://discord.com/users/1274339638668038187)
+            real_yes_sol_reserves: self.market.real_yes_sol_reserves,
+            real_no_sol_reserves: self.market.real_no_sol_reserves,
 
-        Ok(())
+            timestamp: Clock::get()?.unix_timestamp,
+        })
     }
 }
\ No newline at end of file
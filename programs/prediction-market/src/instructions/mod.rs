@@ -2,3 +2,7 @@ pub mod admin;
 pub use admin::*;
 pub mod market;
 pub use market::*;
+pub mod parlay;
+pub use parlay::*;
+pub mod referral;
+pub use referral::*;
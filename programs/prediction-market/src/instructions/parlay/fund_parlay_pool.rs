@@ -0,0 +1,59 @@
+use crate::{
+    constants::{CONFIG, PARLAY_POOL},
+    errors::PredictionMarketError,
+    events::ParlayPoolEvent,
+    state::{config::Config, parlay::ParlayPool},
+    utils::sol_transfer_from_user,
+};
+use anchor_lang::{prelude::*, system_program};
+
+//  the config authority backs parlays with its own lamports
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FundParlayPool<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ParlayPool::INIT_SPACE,
+        seeds = [PARLAY_POOL.as_bytes()],
+        bump
+    )]
+    pub parlay_pool: Box<Account<'info, ParlayPool>>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == global_config.authority @PredictionMarketError::IncorrectAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl FundParlayPool<'_> {
+    pub fn handler(&mut self, amount: u64) -> Result<ParlayPoolEvent> {
+        require!(amount > 0, PredictionMarketError::InvalidAmount);
+        self.parlay_pool.fund(amount)?;
+        sol_transfer_from_user(
+            &self.authority,
+            self.parlay_pool.to_account_info(),
+            &self.system_program,
+            amount,
+        )?;
+
+        Ok(ParlayPoolEvent {
+            authority: self.authority.key(),
+            amount,
+            is_withdrawal: false,
+            liquidity: self.parlay_pool.liquidity,
+            reserved: self.parlay_pool.reserved,
+            timestamp: Clock::get()?.unix_timestamp,
+        })
+    }
+}
@@ -0,0 +1,4 @@
+pub mod fund_parlay_pool;
+pub mod open_parlay;
+pub mod settle_parlay;
+pub mod withdraw_parlay_pool;
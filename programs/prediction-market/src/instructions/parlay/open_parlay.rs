@@ -0,0 +1,174 @@
+use crate::{
+    constants::{CONFIG, MAX_PARLAY_LEGS, MIN_PARLAY_LEGS, PARLAY, PARLAY_POOL},
+    errors::PredictionMarketError,
+    events::ParlayOpenEvent,
+    math::{bps_of, parlay_payout, Decimal, Rounding, TOKEN_TYPE_NO, TOKEN_TYPE_YES},
+    state::{
+        config::Config,
+        market::{Market, MarketStatus},
+        parlay::{Parlay, ParlayLeg, ParlayPick, ParlayPool},
+    },
+    utils::sol_transfer_from_user,
+};
+use anchor_lang::{prelude::*, system_program};
+
+//  market statuses each leg opens in
+const ACCEPTED: &[MarketStatus] = &[MarketStatus::Trading];
+
+//  escrows a stake on every pick winning, priced at the markets' implied prices but never
+//  below their twap
+#[event_cpi]
+#[derive(Accounts)]
+pub struct OpenParlay<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    /// CHECK: should be same with the address in the global_config
+    #[account(
+        mut,
+        constraint = global_config.team_wallet == team_wallet.key() @PredictionMarketError::IncorrectAuthority
+    )]
+    pub team_wallet: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [PARLAY_POOL.as_bytes()],
+        bump
+    )]
+    pub parlay_pool: Box<Account<'info, ParlayPool>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Parlay::INIT_SPACE,
+        seeds = [PARLAY.as_bytes(), &parlay_pool.parlay_count.to_le_bytes()],
+        bump
+    )]
+    pub parlay: Box<Account<'info, Parlay>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenParlay<'info> {
+    pub fn handler(
+        &mut self,
+        picks: &[ParlayPick],
+        stake: u64,
+        min_payout: u64,
+        markets: &'info [AccountInfo<'info>],
+    ) -> Result<ParlayOpenEvent> {
+        require!(
+            (MIN_PARLAY_LEGS..=MAX_PARLAY_LEGS).contains(&picks.len())
+                && markets.len() == picks.len(),
+            PredictionMarketError::InvalidParlay
+        );
+        require!(stake > 0, PredictionMarketError::InvalidAmount);
+
+        let clock = Clock::get()?;
+        let mut legs = Vec::with_capacity(picks.len());
+        for (index, (pick, market_info)) in picks.iter().zip(markets).enumerate() {
+            require!(
+                pick.token_type == TOKEN_TYPE_YES || pick.token_type == TOKEN_TYPE_NO,
+                PredictionMarketError::InvalidParlay
+            );
+            require!(
+                market_info.key() == pick.market
+                    && picks[..index]
+                        .iter()
+                        .all(|other| other.market != pick.market),
+                PredictionMarketError::InvalidParlay
+            );
+
+            let mut market = Account::<Market>::try_from(market_info)?;
+            market.sync(&clock, self.global_config.min_sol_liquidity)?;
+            market.require_status(ACCEPTED)?;
+
+            let (yes_price, no_price) = market.parlay_prices(clock.slot)?;
+            let price = if pick.token_type == TOKEN_TYPE_YES {
+                yes_price
+            } else {
+                no_price
+            };
+            require!(
+                price > Decimal::ZERO,
+                PredictionMarketError::UnpricedParlayLeg
+            );
+
+            //  kept open until the parlay is settled against it
+            market.open_parlays = market
+                .open_parlays
+                .checked_add(1)
+                .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+            market.exit(&crate::ID)?;
+            legs.push(ParlayLeg {
+                market: pick.market,
+                token_type: pick.token_type,
+                entry_price: price
+                    .raw
+                    .try_into()
+                    .map_err(|_| PredictionMarketError::OverflowOrUnderflowOccurred)?,
+            });
+        }
+
+        let prices: Vec<_> = legs
+            .iter()
+            .map(|leg| Decimal::from_raw(leg.entry_price as u128))
+            .collect();
+        let payout = parlay_payout(stake, &prices)
+            .ok_or(PredictionMarketError::InsufficientParlayLiquidity)?;
+        require!(
+            payout >= min_payout,
+            PredictionMarketError::ReturnAmountTooSmall
+        );
+        self.parlay_pool.reserve(stake, payout)?;
+
+        //  the platform fee is charged on top of the stake, as on a buy
+        let fee_lamports = bps_of(self.global_config.platform_buy_fee, stake, Rounding::Up)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        sol_transfer_from_user(
+            &self.owner,
+            self.parlay_pool.to_account_info(),
+            &self.system_program,
+            stake,
+        )?;
+        if fee_lamports > 0 {
+            sol_transfer_from_user(
+                &self.owner,
+                self.team_wallet.clone(),
+                &self.system_program,
+                fee_lamports,
+            )?;
+        }
+
+        let parlay_id = self.parlay_pool.parlay_count;
+        self.parlay_pool.parlay_count = parlay_id
+            .checked_add(1)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        self.parlay.set_inner(Parlay {
+            id: parlay_id,
+            owner: self.owner.key(),
+            legs: legs.clone(),
+            stake,
+            payout,
+            opened_at: clock.unix_timestamp,
+        });
+
+        Ok(ParlayOpenEvent {
+            parlay: self.parlay.key(),
+            parlay_id,
+            owner: self.owner.key(),
+            legs,
+            stake,
+            fee_lamports,
+            payout,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    constants::{PARLAY, PARLAY_POOL},
+    errors::PredictionMarketError,
+    events::ParlaySettleEvent,
+    state::{
+        market::Market,
+        parlay::{Parlay, ParlayPool},
+    },
+};
+use anchor_lang::prelude::*;
+
+//  permissionless once every market of the parlay has a final outcome, pays the owner
+//  what the parlay earned and returns its rent
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SettleParlay<'info> {
+    #[account(
+        mut,
+        seeds = [PARLAY_POOL.as_bytes()],
+        bump
+    )]
+    pub parlay_pool: Box<Account<'info, ParlayPool>>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        seeds = [PARLAY.as_bytes(), &parlay.id.to_le_bytes()],
+        bump
+    )]
+    pub parlay: Box<Account<'info, Parlay>>,
+
+    /// CHECK: the parlay's owner, receives the payout and the rent
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+}
+
+impl<'info> SettleParlay<'info> {
+    pub fn handler(&mut self, markets: &'info [AccountInfo<'info>]) -> Result<ParlaySettleEvent> {
+        require!(
+            markets.len() == self.parlay.legs.len(),
+            PredictionMarketError::InvalidParlay
+        );
+        let markets = self
+            .parlay
+            .legs
+            .iter()
+            .zip(markets)
+            .map(|(leg, market_info)| {
+                require_keys_eq!(
+                    market_info.key(),
+                    leg.market,
+                    PredictionMarketError::InvalidParlay
+                );
+                let mut market = Account::<Market>::try_from(market_info)?;
+                //  no longer holds the market open
+                market.open_parlays = market
+                    .open_parlays
+                    .checked_sub(1)
+                    .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+                market.exit(&crate::ID)?;
+                Ok(market.into_inner())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let outcome = self.parlay.outcome(&markets)?;
+        let paid = self.parlay.paid(outcome);
+        self.parlay_pool.release(self.parlay.payout, paid)?;
+        if paid > 0 {
+            self.parlay_pool.sub_lamports(paid)?;
+            self.owner.add_lamports(paid)?;
+        }
+
+        Ok(ParlaySettleEvent {
+            parlay: self.parlay.key(),
+            parlay_id: self.parlay.id,
+            owner: self.owner.key(),
+            outcome,
+            paid,
+            timestamp: Clock::get()?.unix_timestamp,
+        })
+    }
+}
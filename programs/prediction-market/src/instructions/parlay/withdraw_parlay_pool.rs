@@ -0,0 +1,50 @@
+use crate::{
+    constants::{CONFIG, PARLAY_POOL},
+    errors::PredictionMarketError,
+    events::ParlayPoolEvent,
+    state::{config::Config, parlay::ParlayPool},
+};
+use anchor_lang::prelude::*;
+
+//  the config authority takes back liquidity no open parlay reserves
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawParlayPool<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [PARLAY_POOL.as_bytes()],
+        bump
+    )]
+    pub parlay_pool: Box<Account<'info, ParlayPool>>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == global_config.authority @PredictionMarketError::IncorrectAuthority,
+    )]
+    pub authority: Signer<'info>,
+}
+
+impl WithdrawParlayPool<'_> {
+    pub fn handler(&mut self, amount: u64) -> Result<ParlayPoolEvent> {
+        require!(amount > 0, PredictionMarketError::InvalidAmount);
+        self.parlay_pool.withdraw(amount)?;
+        //  the liquidity sits on top of the rent, the account is owned by the program
+        self.parlay_pool.sub_lamports(amount)?;
+        self.authority.add_lamports(amount)?;
+
+        Ok(ParlayPoolEvent {
+            authority: self.authority.key(),
+            amount,
+            is_withdrawal: true,
+            liquidity: self.parlay_pool.liquidity,
+            reserved: self.parlay_pool.reserved,
+            timestamp: Clock::get()?.unix_timestamp,
+        })
+    }
+}
@@ -0,0 +1,27 @@
+use crate::{constants::REFERRER, events::ReferralClaimEvent, state::referrer::Referrer};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimReferralFees<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [REFERRER.as_bytes(), authority.key().as_ref()],
+        bump
+    )]
+    pub referrer: Box<Account<'info, Referrer>>,
+}
+
+impl ClaimReferralFees<'_> {
+    pub fn handler(&mut self) -> Result<ReferralClaimEvent> {
+        let event = self.referrer.claim()?;
+        //  the fees were transferred in on top of the rent, the account is owned by the program
+        self.referrer.sub_lamports(event.amount)?;
+        self.authority.add_lamports(event.amount)?;
+        Ok(event)
+    }
+}
@@ -0,0 +1,2 @@
+pub mod claim_referral_fees;
+pub mod register_referrer;
@@ -0,0 +1,49 @@
+use crate::{
+    constants::{CONFIG, REFERRER},
+    errors::PredictionMarketError,
+    events::RegisterReferrerEvent,
+    state::{config::Config, referrer::Referrer},
+};
+use anchor_lang::{prelude::*, system_program};
+
+//  the config authority opens the account a frontend's referral fees accrue to, so traders
+//  cannot name themselves to get the referral share back
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(referrer_authority: Pubkey)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == global_config.authority @PredictionMarketError::IncorrectAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Referrer::INIT_SPACE,
+        seeds = [REFERRER.as_bytes(), referrer_authority.as_ref()],
+        bump
+    )]
+    pub referrer: Box<Account<'info, Referrer>>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl RegisterReferrer<'_> {
+    pub fn handler(&mut self, referrer_authority: Pubkey) -> Result<RegisterReferrerEvent> {
+        self.referrer.authority = referrer_authority;
+        Ok(RegisterReferrerEvent {
+            authority: referrer_authority,
+            referrer: self.referrer.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        })
+    }
+}
@@ -6,13 +6,23 @@ pub mod instructions;
 pub mod state;
 pub mod utils;
 
+pub use prediction_market_math as math;
+
 use instructions::{
-    accept_authority::*, add_liquidity::*, configure::*, create_market::*, mint_no_token::*,
-    nominate_authority::*, resolution::*, swap::*, withdraw_liquidity::*,
+    accept_authority::*, add_liquidity::*, archive_market::*, batch_swap::*,
+    claim_referral_fees::*, close_market::*, close_user_info::*, configure::*, cpi_redeem::*,
+    cpi_swap::*, create_market::*, fund_parlay_pool::*, mint_no_token::*, nominate_authority::*,
+    observe::*, open_parlay::*, propose_resolution::*, quote_swap::*, refund::*,
+    register_referrer::*, resolution::*, settle_parlay::*, swap::*, update_market_metadata::*,
+    update_outcome_metadata::*, update_trade_limits::*, void_conditional_market::*,
+    void_market::*, withdraw_liquidity::*, withdraw_parlay_pool::*,
 };
 
 use state::config::*;
 use state::market::*;
+use state::market_metadata::*;
+use state::parlay::*;
+use state::price_oracle::*;
 
 declare_id!("5q1C8N47AYvLu7w6LKngwXhLjrZCZ5izMB8nbziZhYEV");
 
@@ -26,17 +36,23 @@ pub mod prediction_market {
     //  need to check the signer is authority
     pub fn configure(ctx: Context<Configure>, new_config: Config) -> Result<()> {
         msg!("configure: {:#?}", new_config);
-        ctx.accounts.handler(new_config, ctx.bumps.config)
+        let event = ctx.accounts.handler(new_config, ctx.bumps.config)?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     //  Admin can hand over admin role
     pub fn nominate_authority(ctx: Context<NominateAuthority>, new_admin: Pubkey) -> Result<()> {
-        ctx.accounts.process(new_admin)
+        let event = ctx.accounts.process(new_admin)?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     //  Pending admin should accept the admin role
     pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
-        ctx.accounts.process()
+        let event = ctx.accounts.process()?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     pub fn mint_no_token(
@@ -45,12 +61,47 @@ pub mod prediction_market {
         no_symbol: String,
         no_uri: String,
     ) -> Result<()> {
-        ctx.accounts
-            .handler(no_symbol, no_uri, ctx.bumps.global_vault)
+        let event = ctx
+            .accounts
+            .handler(no_symbol, no_uri, ctx.bumps.global_vault)?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     pub fn create_market(ctx: Context<CreateMarket>, params: CreateMarketParams) -> Result<()> {
-        ctx.accounts.handler(params, ctx.bumps.global_vault)
+        let (create_event, metadata_event, registry_event) =
+            ctx.accounts.handler(params, ctx.bumps.global_vault)?;
+        emit_cpi!(create_event);
+        emit_cpi!(metadata_event);
+        emit_cpi!(registry_event);
+        Ok(())
+    }
+
+    //  creator can fix the market description until trading starts
+    pub fn update_market_metadata(
+        ctx: Context<UpdateMarketMetadata>,
+        params: MarketMetadataParams,
+    ) -> Result<()> {
+        let event = ctx.accounts.handler(params)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  creator can move the trade limits within the config bounds until trading ends
+    pub fn update_trade_limits(
+        ctx: Context<UpdateTradeLimits>,
+        trade_limits: TradeLimits,
+    ) -> Result<()> {
+        let event = ctx.accounts.handler(trade_limits)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  drop a market from its category listing, permissionless once it is completed
+    pub fn archive_market(ctx: Context<ArchiveMarket>) -> Result<()> {
+        let event = ctx.accounts.handler()?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     pub fn swap(
@@ -59,37 +110,229 @@ pub mod prediction_market {
         direction: u8,
         token_type: u8,
         minimum_receive_amount: u64,
+        valid_until_slot: Option<u64>,
+        max_price_impact_bps: Option<u64>,
     ) -> Result<()> {
-        ctx.accounts.handler(
+        let event = ctx.accounts.handler(
+            amount,
+            direction,
+            token_type,
+            minimum_receive_amount,
+            valid_until_slot,
+            max_price_impact_bps,
+            ctx.bumps.global_vault,
+        )?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  `swap` on several markets at once, each leg brings `BATCH_LEG_ACCOUNTS` remaining accounts
+    pub fn batch_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchSwap<'info>>,
+        legs: Vec<SwapLeg>,
+        valid_until_slot: Option<u64>,
+    ) -> Result<()> {
+        let events = ctx.accounts.handler(
+            &legs,
+            valid_until_slot,
+            ctx.remaining_accounts,
+            ctx.bumps.global_vault,
+        )?;
+        for event in events {
+            emit_cpi!(event);
+        }
+        Ok(())
+    }
+
+    //  `swap` for other programs: the owner may be a PDA and tokens move through a caller-supplied account
+    pub fn cpi_swap(
+        ctx: Context<CpiSwap>,
+        amount: u64,
+        direction: u8,
+        token_type: u8,
+        minimum_receive_amount: u64,
+        valid_until_slot: Option<u64>,
+        max_price_impact_bps: Option<u64>,
+    ) -> Result<()> {
+        let event = ctx.accounts.handler(
             amount,
             direction,
             token_type,
             minimum_receive_amount,
+            valid_until_slot,
+            max_price_impact_bps,
             ctx.bumps.global_vault,
+        )?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  what `swap` with the same arguments would trade, read through return data
+    pub fn quote_swap(
+        ctx: Context<QuoteSwap>,
+        amount: u64,
+        direction: u8,
+        token_type: u8,
+        minimum_receive_amount: u64,
+        valid_until_slot: Option<u64>,
+        max_price_impact_bps: Option<u64>,
+    ) -> Result<SwapQuote> {
+        ctx.accounts.handler(
+            amount,
+            direction,
+            token_type,
+            minimum_receive_amount,
+            valid_until_slot,
+            max_price_impact_bps,
         )
     }
 
+    //  time weighted average prices over the last `window_slots`, read through return data
+    pub fn observe(ctx: Context<Observe>, window_slots: u64) -> Result<Twap> {
+        ctx.accounts.handler(window_slots)
+    }
+
+    //  ends trading and names the winner, the first payout confirms it
+    pub fn propose_resolution(
+        ctx: Context<ProposeResolution>,
+        winning_token_type: u8,
+    ) -> Result<()> {
+        let event = ctx.accounts.handler(winning_token_type)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  holder-signed payout, the authority co-signs only the first one, which confirms the
+    //  outcome it proposed
     pub fn resolution(
         ctx: Context<Resolution>,
         yes_amount: u64,
         no_amount: u64,
         token_type: u8,
-        is_completed: bool,
     ) -> Result<()> {
-        ctx.accounts.handler(
-            yes_amount,
-            no_amount,
-            token_type,
-            is_completed,
-            ctx.bumps.global_vault,
-        )
+        let (resolution_event, payout_event) =
+            ctx.accounts
+                .handler(yes_amount, no_amount, token_type, ctx.bumps.global_vault)?;
+        if let Some(event) = resolution_event {
+            emit_cpi!(event);
+        }
+        emit_cpi!(payout_event);
+        Ok(())
+    }
+
+    //  permissionless crank after resolution, marks the outcome tokens as won or lost
+    pub fn update_outcome_metadata(ctx: Context<UpdateOutcomeMetadata>) -> Result<()> {
+        let event = ctx.accounts.handler(ctx.bumps.global_vault)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  owner-signed payout of the owner's outcome tokens to a caller-supplied recipient, under
+    //  the same policy as `resolution` once the outcome is confirmed
+    pub fn cpi_redeem(ctx: Context<CpiRedeem>) -> Result<()> {
+        let event = ctx.accounts.handler(ctx.bumps.global_vault)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  called by admin when the outcome cannot be determined, positions are refunded at cost
+    pub fn void_market(ctx: Context<VoidMarket>) -> Result<()> {
+        let event = ctx.accounts.handler()?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  permissionless once the parent of a conditional market resolved against its condition
+    pub fn void_conditional_market(ctx: Context<VoidConditionalMarket>) -> Result<()> {
+        let event = ctx.accounts.handler()?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn refund(ctx: Context<Refund>) -> Result<()> {
+        let event = ctx.accounts.handler(ctx.bumps.global_vault)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  config authority opens the account a frontend's share of the platform fee accrues to
+    pub fn register_referrer(
+        ctx: Context<RegisterReferrer>,
+        referrer_authority: Pubkey,
+    ) -> Result<()> {
+        let event = ctx.accounts.handler(referrer_authority)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  pays the referral fees accrued since the last claim to the referrer's authority
+    pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
+        let event = ctx.accounts.handler()?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  config authority adds lamports backing parlays
+    pub fn fund_parlay_pool(ctx: Context<FundParlayPool>, amount: u64) -> Result<()> {
+        let event = ctx.accounts.handler(amount)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  config authority takes back pool liquidity no open parlay reserves
+    pub fn withdraw_parlay_pool(ctx: Context<WithdrawParlayPool>, amount: u64) -> Result<()> {
+        let event = ctx.accounts.handler(amount)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  bet that every pick wins, the markets follow as remaining accounts in pick order
+    pub fn open_parlay<'info>(
+        ctx: Context<'_, '_, 'info, 'info, OpenParlay<'info>>,
+        picks: Vec<ParlayPick>,
+        stake: u64,
+        min_payout: u64,
+    ) -> Result<()> {
+        let event = ctx
+            .accounts
+            .handler(&picks, stake, min_payout, ctx.remaining_accounts)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  permissionless once every market of the parlay is final, markets follow in leg order
+    pub fn settle_parlay<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleParlay<'info>>,
+    ) -> Result<()> {
+        let event = ctx.accounts.handler(ctx.remaining_accounts)?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     pub fn add_liquidity(ctx: Context<AddLiquidity>, amount: u64) -> Result<()> {
-        ctx.accounts.handler(amount)
+        let event = ctx.accounts.handler(amount)?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>, amount: u64) -> Result<()> {
-        ctx.accounts.handler(amount, ctx.bumps.global_vault)
+        let event = ctx.accounts.handler(amount, ctx.bumps.global_vault)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  return the user_info rent once the position is flat or the market is settled
+    pub fn close_user_info(ctx: Context<CloseUserInfo>) -> Result<()> {
+        let event = ctx.accounts.handler()?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    //  permissionless once every claim is paid or the claim period is over and LPs have
+    //  withdrawn, rent goes back to the creator and unclaimed winnings to the team wallet
+    pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
+        let event = ctx.accounts.handler(ctx.bumps.global_vault)?;
+        emit_cpi!(event);
+        Ok(())
     }
 }
@@ -1,4 +1,7 @@
+use crate::constants::{MAX_FEE_TIERS, MAX_PUBKEY_STR_LEN, MAX_URI_LEN};
 use crate::errors::*;
+use crate::events::{ConfigUpdateEvent, IntoEvent};
+use crate::math::{FeeRates, BPS_DENOMINATOR};
 use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
 use core::fmt::Debug;
 
@@ -11,14 +14,20 @@ pub struct Config {
 
     pub team_wallet: Pubkey,
 
-    //  platform fee percentage
+    //  platform fee, in bps of the traded amount
     pub platform_buy_fee: u64,
     pub platform_sell_fee: u64,
 
-    //  lp fee percentage
+    //  lp fee, in bps of the traded amount
     pub lp_buy_fee: u64,
     pub lp_sell_fee: u64,
 
+    //  share of the platform fee paid to a swap's referrer, in bps
+    pub referral_fee_bps: u64,
+
+    //  lower platform fees for high-volume traders, by ascending `min_volume`
+    pub fee_tiers: Vec<FeeTier>,
+
     pub token_supply_config: u64,
     pub token_decimals_config: u8,
 
@@ -26,9 +35,127 @@ pub struct Config {
 
     pub min_sol_liquidity: u64,
 
+    //  bounds on the trade limits a creator may pick for a market
+    pub max_position_per_wallet_config: AmountConfig<u64>,
+    pub min_trade_lamports_config: AmountConfig<u64>,
+    pub max_trade_lamports_config: AmountConfig<u64>,
+
+    //  base metadata uris of the winning and losing tokens once a market resolved, each token
+    //  gets its mint appended so every market points at its own document
+    pub won_uri: String,
+    pub lost_uri: String,
+
     pub initialized: bool,
 }
 
+//  platform fees of a trader whose rolling volume reached `min_volume` lamports
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct FeeTier {
+    pub min_volume: u64,
+    pub platform_buy_fee: u64,
+    pub platform_sell_fee: u64,
+}
+
+impl Config {
+    //  the highest tier `volume` reaches, none below the first one
+    pub fn fee_tier(&self, volume: u64) -> Option<&FeeTier> {
+        self.fee_tiers
+            .iter()
+            .rev()
+            .find(|tier| volume >= tier.min_volume)
+    }
+
+    pub fn buy_fee_rates(&self, volume: u64) -> FeeRates {
+        FeeRates {
+            platform_bps: self
+                .fee_tier(volume)
+                .map_or(self.platform_buy_fee, |tier| tier.platform_buy_fee),
+            lp_bps: self.lp_buy_fee,
+        }
+    }
+
+    pub fn sell_fee_rates(&self, volume: u64) -> FeeRates {
+        FeeRates {
+            platform_bps: self
+                .fee_tier(volume)
+                .map_or(self.platform_sell_fee, |tier| tier.platform_sell_fee),
+            lp_bps: self.lp_sell_fee,
+        }
+    }
+
+    //  platform and lp fee together never take more than the traded amount
+    pub fn validate_fee_rates(&self) -> Result<()> {
+        require!(
+            fits_in_bps(self.platform_buy_fee, self.lp_buy_fee)
+                && fits_in_bps(self.platform_sell_fee, self.lp_sell_fee),
+            PredictionMarketError::InvalidAmount
+        );
+        Ok(())
+    }
+
+    pub fn validate_outcome_uris(&self) -> Result<()> {
+        //  room for the mint the token's uri ends with
+        let max_len = MAX_URI_LEN - MAX_PUBKEY_STR_LEN;
+        require!(
+            self.won_uri.len() <= max_len && self.lost_uri.len() <= max_len,
+            PredictionMarketError::InvalidArgument
+        );
+        Ok(())
+    }
+
+    pub fn validate_fee_tiers(&self) -> Result<()> {
+        require!(
+            self.fee_tiers.len() <= MAX_FEE_TIERS,
+            PredictionMarketError::InvalidFeeTiers
+        );
+        require!(
+            self.fee_tiers
+                .windows(2)
+                .all(|pair| pair[0].min_volume < pair[1].min_volume),
+            PredictionMarketError::InvalidFeeTiers
+        );
+        require!(
+            self.fee_tiers.iter().all(|tier| {
+                fits_in_bps(tier.platform_buy_fee, self.lp_buy_fee)
+                    && fits_in_bps(tier.platform_sell_fee, self.lp_sell_fee)
+            }),
+            PredictionMarketError::InvalidFeeTiers
+        );
+        Ok(())
+    }
+}
+
+//  fees of one direction, as a share of the traded amount
+fn fits_in_bps(platform_bps: u64, lp_bps: u64) -> bool {
+    platform_bps
+        .checked_add(lp_bps)
+        .is_some_and(|bps| bps <= BPS_DENOMINATOR)
+}
+
+impl IntoEvent<ConfigUpdateEvent> for Config {
+    fn into_event(&self) -> ConfigUpdateEvent {
+        ConfigUpdateEvent {
+            authority: self.authority,
+            team_wallet: self.team_wallet,
+            platform_buy_fee: self.platform_buy_fee,
+            platform_sell_fee: self.platform_sell_fee,
+            lp_buy_fee: self.lp_buy_fee,
+            lp_sell_fee: self.lp_sell_fee,
+            referral_fee_bps: self.referral_fee_bps,
+            fee_tiers: self.fee_tiers.clone(),
+            token_supply_config: self.token_supply_config,
+            token_decimals_config: self.token_decimals_config,
+            initial_real_token_reserves_config: self.initial_real_token_reserves_config,
+            min_sol_liquidity: self.min_sol_liquidity,
+            max_position_per_wallet_config: self.max_position_per_wallet_config.clone(),
+            min_trade_lamports_config: self.min_trade_lamports_config.clone(),
+            max_trade_lamports_config: self.max_trade_lamports_config.clone(),
+            won_uri: self.won_uri.clone(),
+            lost_uri: self.lost_uri.clone(),
+        }
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
 pub enum AmountConfig<T: PartialEq + PartialOrd + Debug> {
     Range { min: Option<T>, max: Option<T> },
@@ -17,8 +17,8 @@ pub struct Global {
 
     pub team_wallet: Pubkey,
 
-    pub platform_buy_fee: f64, //  platform fee percentage
-    pub platform_sell_fee: f64,
+    pub platform_buy_fee: u64, //  platform fee in basis points
+    pub platform_sell_fee: u64,
 
     // Prediction Market initial values
     pub initial_real_token_reserves: u64,
@@ -34,8 +34,8 @@ impl Default for Global {
             initialized: true,
             global_authority: Pubkey::default(),
             team_wallet: Pubkey::default(),
-            platform_buy_fee: 1.0,
-            platform_sell_fee: 1.0,
+            platform_buy_fee: 100,
+            platform_sell_fee: 100,
 
             // prediction-market initial values
             initial_real_token_reserves: 1000000000000000,
@@ -1,20 +1,160 @@
+use crate::constants::{CLAIM_PERIOD, PARLAY_TWAP_SLOTS};
 use crate::errors::PredictionMarketError;
+use crate::events::TradeEvent;
+use crate::math::{
+    self, bps_of, get_tokens_for_buy_sol, get_tokens_for_sell_sol, mul_div, price_impact_bps,
+    quote_buy, quote_sell, Decimal, Reserves, Rounding,
+};
 use crate::state::config::*;
+use crate::state::market_metadata::MarketMetadataParams;
+pub use crate::state::market_status::MarketStatus;
+use crate::state::price_oracle::PriceOracle;
+use crate::state::referrer::Referrer;
+use crate::state::user_stats::UserStats;
 use crate::utils::*;
 
 use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
-use anchor_spl::token::Mint;
 use anchor_spl::token::Token;
 
 // use anchor_spl::token::{self};
 
+//  provenance of a NO mint, so each one backs exactly one market
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct NoTokenRecord {
+    pub creator: Pubkey,
+    pub market: Option<Pubkey>,
+}
+
 #[account]
 pub struct UserInfo {
     pub user: Pubkey,     // User's public key
     pub yes_balance: u64, // Amount of YES tokens purchased
     pub no_balance: u64,  // Amount of NO tokens purchased
+    pub lp_amount: u64,   // Amount of SOL provided as liquidity
     pub is_lp: bool,
     pub is_initialized: bool,
+
+    //  lamports paid, fees included, for the tokens still held
+    pub yes_cost_basis: u64,
+    pub no_cost_basis: u64,
+    pub total_sol_spent: u64,
+    pub total_sol_received: u64,
+    pub realized_pnl: i64,
+    pub fees_paid: u64,
+}
+
+impl UserInfo {
+    pub fn balance(&self, token_type: u8) -> u64 {
+        if token_type == TOKEN_TYPE_YES {
+            self.yes_balance
+        } else {
+            self.no_balance
+        }
+    }
+
+    pub fn cost_basis(&self, token_type: u8) -> u64 {
+        if token_type == TOKEN_TYPE_YES {
+            self.yes_cost_basis
+        } else {
+            self.no_cost_basis
+        }
+    }
+
+    //  lamports paid per token base unit, `None` without a position
+    pub fn average_entry_price(&self, token_type: u8) -> Option<Decimal> {
+        let balance = self.balance(token_type);
+        if balance == 0 {
+            return None;
+        }
+        Decimal::from_ratio(self.cost_basis(token_type), balance, Rounding::Up)
+    }
+
+    fn position_mut(&mut self, token_type: u8) -> (&mut u64, &mut u64) {
+        if token_type == TOKEN_TYPE_YES {
+            (&mut self.yes_balance, &mut self.yes_cost_basis)
+        } else {
+            (&mut self.no_balance, &mut self.no_cost_basis)
+        }
+    }
+
+    //  cost basis carried by `token_amount` of the position, all of it from its balance up
+    pub fn cost_of(&self, token_type: u8, token_amount: u64) -> Option<u64> {
+        let balance = self.balance(token_type);
+        let cost_basis = self.cost_basis(token_type);
+        if token_amount >= balance {
+            return Some(cost_basis);
+        }
+        mul_div(
+            cost_basis as u128,
+            token_amount as u128,
+            balance as u128,
+            Rounding::Down,
+        )?
+        .try_into()
+        .ok()
+    }
+
+    //  drop `token_amount` from the position and return the cost basis it carried,
+    //  tokens received outside of the program carry none
+    fn release(&mut self, token_type: u8, token_amount: u64) -> Option<u64> {
+        let released = token_amount.min(self.balance(token_type));
+        let cost = self.cost_of(token_type, released)?;
+        let (balance, cost_basis) = self.position_mut(token_type);
+        *balance -= released;
+        *cost_basis -= cost;
+        Some(cost)
+    }
+
+    fn realize(&mut self, received: u64, cost: u64) -> Option<()> {
+        let pnl = i64::try_from(received as i128 - cost as i128).ok()?;
+        self.realized_pnl = self.realized_pnl.checked_add(pnl)?;
+        Some(())
+    }
+
+    pub fn record_buy(
+        &mut self,
+        token_type: u8,
+        token_amount: u64,
+        paid: u64,
+        fees: u64,
+    ) -> Option<()> {
+        let (balance, cost_basis) = self.position_mut(token_type);
+        *balance = balance.checked_add(token_amount)?;
+        *cost_basis = cost_basis.checked_add(paid)?;
+        self.total_sol_spent = self.total_sol_spent.checked_add(paid)?;
+        self.fees_paid = self.fees_paid.checked_add(fees)?;
+        Some(())
+    }
+
+    pub fn record_sell(
+        &mut self,
+        token_type: u8,
+        token_amount: u64,
+        received: u64,
+        fees: u64,
+    ) -> Option<u64> {
+        let cost = self.release(token_type, token_amount)?;
+        self.realize(received, cost)?;
+        self.total_sol_received = self.total_sol_received.checked_add(received)?;
+        self.fees_paid = self.fees_paid.checked_add(fees)?;
+        Some(cost)
+    }
+
+    //  winning tokens realize the payout against their cost, losing tokens their cost as a loss
+    pub fn record_redemption(
+        &mut self,
+        yes_amount: u64,
+        no_amount: u64,
+        payout: u64,
+    ) -> Option<u64> {
+        let cost = self
+            .release(TOKEN_TYPE_YES, yes_amount)?
+            .checked_add(self.release(TOKEN_TYPE_NO, no_amount)?)?;
+        self.realize(payout, cost)?;
+        self.total_sol_received = self.total_sol_received.checked_add(payout)?;
+        Some(cost)
+    }
 }
 
 #[account]
@@ -40,71 +180,545 @@ pub struct Market {
     pub real_no_sol_reserves: u64,
     pub token_no_total_supply: u64,
 
-    pub is_completed: bool,
+    pub status: MarketStatus,
     pub start_slot: Option<u64>,
     pub ending_slot: Option<u64>,
 
     pub lps: Vec<LpInfo>,
     pub total_lp_amount: u64,
+
+    //  sequential id assigned by the market registry
+    pub id: u64,
+
+    //  set by the resolution proposal, payouts must follow the same outcome
+    pub winning_token_type: Option<u8>,
+    //  when the market was resolved or voided, starts the claim period
+    pub resolved_at: Option<i64>,
+
+    //  unix timestamp window, enforced together with the slot window
+    pub start_time: Option<i64>,
+    pub end_time: Option<i64>,
+
+    //  cost basis of every open position, refunds of a voided market are pro rata of it
+    pub open_cost_basis: u64,
+
+    pub trade_limits: TradeLimits,
+
+    //  twap accumulators, updated before every trade moves the curve
+    pub price_oracle: PriceOracle,
+
+    //  set for a market that only counts if its parent resolves to the given outcome
+    pub condition: Option<MarketCondition>,
+
+    //  lp fees of every trade, kept out of the reserves and paid to liquidity providers
+    //  pro rata of the principal they withdraw
+    pub lp_fees: u64,
+
+    //  unsettled parlays with a leg on the market, which stays open until they read its outcome
+    pub open_parlays: u64,
+
+    //  conditional markets on this one that are not closed yet, they read its outcome until then
+    pub open_conditional_markets: u64,
 }
 
-#[derive(Debug, Clone)]
-pub struct SellResult {
-    pub token_amount: u64,
-    pub change_amount: u64,
-    pub current_yes_reserves: u64,
-    pub current_no_reserves: u64,
-    pub new_yes_reserves: u64,
-    pub new_no_reserves: u64,
+pub use crate::math::{
+    BuyResult, SellResult, DIRECTION_BUY, DIRECTION_SELL, TOKEN_TYPE_NO, TOKEN_TYPE_YES,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateMarketParams {
+    pub yes_symbol: String,
+    pub yes_uri: String,
+
+    pub start_slot: Option<u64>,
+    pub ending_slot: Option<u64>,
+
+    pub start_time: Option<i64>,
+    pub end_time: Option<i64>,
+
+    pub trade_limits: TradeLimits,
+
+    pub metadata: MarketMetadataParams,
+
+    pub condition: Option<MarketCondition>,
 }
 
-#[derive(Debug, Clone)]
-pub struct BuyResult {
+//  parent market and the outcome it must resolve to, otherwise the conditional market is voided
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MarketCondition {
+    pub parent: Pubkey,
+    pub outcome: u8,
+}
+
+//  one swap of `batch_swap`, with the per-trade arguments of `swap`, the slot deadline and
+//  referrer are given once for the batch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapLeg {
+    pub market: Pubkey,
+    pub token_type: u8,
+    pub direction: u8,
+    pub amount: u64,
+    pub minimum_receive_amount: u64,
+    pub max_price_impact_bps: Option<u64>,
+}
+
+//  a swap at the current reserves, the curve result of `BuyResult`/`SellResult` with its fees
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapQuote {
     pub token_amount: u64,
+    //  lamports into the curve for a buy, out of it for a sell, fees excluded
     pub change_amount: u64,
     pub current_yes_reserves: u64,
     pub current_no_reserves: u64,
     pub new_yes_reserves: u64,
     pub new_no_reserves: u64,
+
+    pub platform_fee: u64,
+    pub lp_fee: u64,
+    //  tokens for a buy, lamports for a sell, checked against `minimum_receive_amount`
+    pub amount_out: u64,
+    pub price_impact_bps: u64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct CreateMarketParams {
-    pub yes_symbol: String,
-    pub yes_uri: String,
+impl From<BuyResult> for SwapQuote {
+    fn from(result: BuyResult) -> Self {
+        Self {
+            token_amount: result.token_amount,
+            change_amount: result.change_amount,
+            current_yes_reserves: result.current_yes_reserves,
+            current_no_reserves: result.current_no_reserves,
+            new_yes_reserves: result.new_yes_reserves,
+            new_no_reserves: result.new_no_reserves,
+            ..Default::default()
+        }
+    }
+}
 
-    pub start_slot: Option<u64>,
-    pub ending_slot: Option<u64>,
+impl From<SellResult> for SwapQuote {
+    fn from(result: SellResult) -> Self {
+        Self {
+            token_amount: result.token_amount,
+            change_amount: result.change_amount,
+            current_yes_reserves: result.current_yes_reserves,
+            current_no_reserves: result.current_no_reserves,
+            new_yes_reserves: result.new_yes_reserves,
+            new_no_reserves: result.new_no_reserves,
+            ..Default::default()
+        }
+    }
+}
+
+//  per market exposure caps picked by the creator within the config bounds
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TradeLimits {
+    //  tokens of one outcome a wallet may accumulate through buys
+    pub max_position_per_wallet: u64,
+    //  bounds on the SOL side of a single buy or sell
+    pub min_trade_lamports: u64,
+    pub max_trade_lamports: u64,
+}
+
+impl Default for TradeLimits {
+    fn default() -> Self {
+        Self {
+            max_position_per_wallet: u64::MAX,
+            min_trade_lamports: 0,
+            max_trade_lamports: u64::MAX,
+        }
+    }
+}
+
+impl TradeLimits {
+    pub fn validate(&self, config: &Config) -> Result<()> {
+        config
+            .max_position_per_wallet_config
+            .validate(&self.max_position_per_wallet)?;
+        config
+            .min_trade_lamports_config
+            .validate(&self.min_trade_lamports)?;
+        config
+            .max_trade_lamports_config
+            .validate(&self.max_trade_lamports)?;
+        require!(
+            self.min_trade_lamports <= self.max_trade_lamports,
+            PredictionMarketError::InvalidTradeLimits
+        );
+        Ok(())
+    }
+
+    //  selling a whole position is exempt from the minimum so dust can always exit
+    pub fn check_trade(&self, lamports: u64, closes_position: bool) -> Result<()> {
+        require!(
+            closes_position || lamports >= self.min_trade_lamports,
+            PredictionMarketError::TradeTooSmall
+        );
+        require!(
+            lamports <= self.max_trade_lamports,
+            PredictionMarketError::TradeTooLarge
+        );
+        Ok(())
+    }
+}
+
+impl Market {
+    //  probability of each outcome at the current reserves
+    pub fn implied_prices(&self) -> Option<(Decimal, Decimal)> {
+        math::implied_prices(&self.curve_reserves())
+    }
+
+    //  prices a parlay leg opens at, the higher of the spot price and its twap so moving the
+    //  curve right before opening cannot lengthen the odds. A market younger than the window
+    //  averages since its first trade, without trades it is still at its initial prices
+    pub fn parlay_prices(&self, slot: u64) -> Result<(Decimal, Decimal)> {
+        let (yes_price, no_price) = self
+            .implied_prices()
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        let window_slots = match self.price_oracle.oldest() {
+            Some(oldest) => PARLAY_TWAP_SLOTS.min(slot.saturating_sub(oldest.slot)),
+            None => 0,
+        };
+        if window_slots == 0 {
+            return Ok((yes_price, no_price));
+        }
+        let twap = self
+            .price_oracle
+            .observe(slot, window_slots, yes_price, no_price)?;
+        Ok((
+            yes_price.max(Decimal::from_raw(twap.yes_price as u128)),
+            no_price.max(Decimal::from_raw(twap.no_price as u128)),
+        ))
+    }
+
+    pub fn accumulate_prices(&mut self, slot: u64) -> Option<()> {
+        let (yes_price, no_price) = self.implied_prices()?;
+        self.price_oracle.update(slot, yes_price, no_price)
+    }
+
+    //  what `swap` trades at the current reserves, without moving them, for a trader with
+    //  `volume` lamports in the fee tier window; trade limits depend on the position and are
+    //  left to `swap`
+    #[allow(clippy::too_many_arguments)]
+    pub fn quote_swap(
+        &self,
+        global_config: &Config,
+        volume: u64,
+        amount: u64,
+        direction: u8,
+        token_type: u8,
+        minimum_receive_amount: u64,
+        max_price_impact_bps: Option<u64>,
+    ) -> Result<SwapQuote> {
+        require!(amount > 0, PredictionMarketError::InvalidAmount);
+        require!(
+            token_type == TOKEN_TYPE_NO || token_type == TOKEN_TYPE_YES,
+            PredictionMarketError::InvalidArgument
+        );
+
+        let before = self.curve_reserves();
+        let (curve, fees, amount_out, after) = match direction {
+            //  fees are charged on the SOL paid in and round up
+            DIRECTION_BUY => {
+                let quote = quote_buy(&before, amount, token_type, global_config.buy_fee_rates(volume))
+                    .ok_or(PredictionMarketError::BuyFailed)?;
+                let after = quote.result.reserves_after(&before, token_type);
+                let amount_out = quote.result.token_amount;
+                (SwapQuote::from(quote.result), quote.fees, amount_out, after)
+            }
+            //  fees are charged on the SOL paid out and round up
+            DIRECTION_SELL => {
+                let quote = quote_sell(&before, amount, token_type, global_config.sell_fee_rates(volume))
+                    .ok_or(PredictionMarketError::SellFailed)?;
+                let after = quote.result.reserves_after(&before, token_type);
+                (
+                    SwapQuote::from(quote.result),
+                    quote.fees,
+                    quote.amount_out,
+                    after,
+                )
+            }
+            _ => return err!(PredictionMarketError::InvalidArgument),
+        };
+        require!(
+            amount_out >= minimum_receive_amount,
+            PredictionMarketError::ReturnAmountTooSmall
+        );
+
+        //  measured on the curve alone, fees never enter the reserves
+        let price_impact_bps = after
+            .and_then(|after| price_impact_bps(&before, &after, token_type))
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        if let Some(max_price_impact_bps) = max_price_impact_bps {
+            require!(
+                price_impact_bps <= max_price_impact_bps,
+                PredictionMarketError::PriceImpactTooHigh
+            );
+        }
+
+        Ok(SwapQuote {
+            platform_fee: fees.platform_fee,
+            lp_fee: fees.lp_fee,
+            amount_out,
+            price_impact_bps,
+            ..curve
+        })
+    }
+
+    pub fn curve_reserves(&self) -> Reserves {
+        Reserves {
+            yes_token: self.real_yes_token_reserves,
+            yes_sol: self.real_yes_sol_reserves,
+            no_token: self.real_no_token_reserves,
+            no_sol: self.real_no_sol_reserves,
+        }
+    }
+
+    //  outcome tokens held outside of the pool
+    pub fn circulating_tokens(&self, token_type: u8) -> Option<u64> {
+        if token_type == TOKEN_TYPE_YES {
+            self.initial_yes_token_reserves
+                .checked_sub(self.real_yes_token_reserves)
+        } else {
+            self.initial_no_token_reserves
+                .checked_sub(self.real_no_token_reserves)
+        }
+    }
+
+    //  whether any outcome token has left the pool yet
+    pub fn has_trades(&self) -> bool {
+        self.real_yes_token_reserves != self.initial_yes_token_reserves
+            || self.real_no_token_reserves != self.initial_no_token_reserves
+    }
+
+    //  SOL in the pool that is not owed back to liquidity providers
+    pub fn prize_pool(&self) -> Option<u64> {
+        math::prize_pool(self.curve_reserves().total_sol()?, self.total_lp_amount)
+    }
+
+    //  SOL paid for `token_amount` winning tokens, pro rata of the prize pool
+    pub fn get_payout(&self, token_amount: u64, token_type: u8) -> Option<u64> {
+        math::get_payout(
+            token_amount,
+            self.prize_pool()?,
+            self.circulating_tokens(token_type)?,
+        )
+    }
+
+    //  trading opens at `start_slot` and `start_time`, whichever is set
+    pub fn has_started(&self, clock: &Clock) -> bool {
+        clock.slot >= self.start_slot.unwrap_or(0)
+            && clock.unix_timestamp >= self.start_time.unwrap_or(i64::MIN)
+    }
+
+    //  trading closes after `ending_slot` or after `end_time`, whichever comes first,
+    //  both bounds are the last slot and second of trading
+    pub fn has_ended(&self, clock: &Clock) -> bool {
+        self.ending_slot
+            .is_some_and(|ending_slot| clock.slot > ending_slot)
+            || self
+                .end_time
+                .is_some_and(|end_time| clock.unix_timestamp > end_time)
+    }
+
+    //  a start is set and has not been reached yet
+    pub fn is_scheduled(&self, clock: &Clock) -> bool {
+        (self.start_slot.is_some() || self.start_time.is_some()) && !self.has_started(clock)
+    }
+
+    //  the only way `status` changes
+    pub fn transition(&mut self, next: MarketStatus) -> Result<()> {
+        require!(
+            self.status.can_transition_to(next),
+            PredictionMarketError::InvalidStatusTransition
+        );
+        if matches!(next, MarketStatus::Resolved | MarketStatus::Invalid) {
+            self.resolved_at = Some(Clock::get()?.unix_timestamp);
+        }
+        msg!("market status: {:?} -> {:?}", self.status, next);
+        self.status = next;
+        Ok(())
+    }
+
+    pub fn require_status(&self, accepted: &[MarketStatus]) -> Result<()> {
+        if !accepted.contains(&self.status) {
+            msg!(
+                "market status {:?} is not one of {:?}",
+                self.status,
+                accepted
+            );
+            return err!(PredictionMarketError::InvalidMarketStatus);
+        }
+        Ok(())
+    }
+
+    //  apply the moves driven by the clock and the seeded liquidity
+    pub fn sync(&mut self, clock: &Clock, min_sol_liquidity: u64) -> Result<()> {
+        if self.status == MarketStatus::Pending
+            && self.has_started(clock)
+            && self.total_lp_amount >= min_sol_liquidity
+            && !self.has_ended(clock)
+        {
+            self.transition(MarketStatus::Trading)?;
+        }
+        if matches!(self.status, MarketStatus::Pending | MarketStatus::Trading)
+            && self.has_ended(clock)
+        {
+            self.transition(MarketStatus::Closed)?;
+        }
+        Ok(())
+    }
+
+    //  every claim on the pool is paid out
+    pub fn claims_settled(&self) -> bool {
+        let traders_paid = match self.status {
+            MarketStatus::Resolved => self
+                .winning_token_type
+                .is_some_and(|token_type| self.circulating_tokens(token_type) == Some(0)),
+            MarketStatus::Invalid => self.open_cost_basis == 0,
+            MarketStatus::Settled => true,
+            _ => false,
+        };
+        traders_paid && self.total_lp_amount == 0
+    }
+
+    pub fn try_settle(&mut self) -> Result<()> {
+        if self.status != MarketStatus::Settled && self.claims_settled() {
+            self.transition(MarketStatus::Settled)?;
+        }
+        Ok(())
+    }
+
+    //  cost basis refunded, pro rata of what traders left in the pool when it falls short
+    pub fn get_refund(&self, cost_basis: u64) -> Option<u64> {
+        let pro_rata = math::get_payout(cost_basis, self.prize_pool()?, self.open_cost_basis)?;
+        Some(pro_rata.min(cost_basis))
+    }
+
+    //  whether `parent` resolved to the condition, none while its outcome is not final
+    pub fn condition_met(&self, parent: &Market) -> Option<bool> {
+        let condition = self.condition?;
+        parent
+            .status
+            .is_final()
+            .then_some(parent.winning_token_type == Some(condition.outcome))
+    }
+
+    //  unclaimed SOL can be swept once this passes
+    pub fn claim_deadline(&self) -> Option<i64> {
+        self.resolved_at?.checked_add(CLAIM_PERIOD)
+    }
+
+    fn release_cost_basis(&mut self, cost: u64) -> Result<()> {
+        self.open_cost_basis = self
+            .open_cost_basis
+            .checked_sub(cost)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        Ok(())
+    }
+
+    fn accrue_lp_fee(&mut self, lp_fee: u64) -> Result<()> {
+        self.lp_fees = self
+            .lp_fees
+            .checked_add(lp_fee)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        Ok(())
+    }
+
+    //  lp fees paid out with `sol_amount` of principal, rounds down so the last withdrawal takes the rest
+    pub fn lp_fee_share(&self, sol_amount: u64) -> Option<u64> {
+        if self.total_lp_amount == 0 {
+            return Some(0);
+        }
+        mul_div(
+            self.lp_fees as u128,
+            sol_amount as u128,
+            self.total_lp_amount as u128,
+            Rounding::Down,
+        )?
+        .try_into()
+        .ok()
+    }
+
+    fn add_sol_reserves(&mut self, token_type: u8, amount: u64) -> Option<()> {
+        if token_type == TOKEN_TYPE_YES {
+            self.real_yes_sol_reserves = self.real_yes_sol_reserves.checked_add(amount)?;
+        } else {
+            self.real_no_sol_reserves = self.real_no_sol_reserves.checked_add(amount)?;
+        }
+        Some(())
+    }
+
+    //  take SOL out of the pool, draining `first_token_type` side before the other one
+    fn take_sol_reserves(&mut self, first_token_type: u8, amount: u64) -> Option<()> {
+        let (first, second) = if first_token_type == TOKEN_TYPE_YES {
+            (
+                &mut self.real_yes_sol_reserves,
+                &mut self.real_no_sol_reserves,
+            )
+        } else {
+            (
+                &mut self.real_no_sol_reserves,
+                &mut self.real_yes_sol_reserves,
+            )
+        };
+        let from_first = amount.min(*first);
+        *first -= from_first;
+        *second = second.checked_sub(amount - from_first)?;
+        Some(())
+    }
+
+    //  take SOL out of both sides in proportion to their reserves, keeping the implied price
+    fn take_sol_reserves_pro_rata(&mut self, amount: u64) -> Option<()> {
+        let total = self
+            .real_yes_sol_reserves
+            .checked_add(self.real_no_sol_reserves)?;
+        if amount > total {
+            return None;
+        }
+        let from_yes: u64 = mul_div(
+            amount as u128,
+            self.real_yes_sol_reserves as u128,
+            total as u128,
+            Rounding::Down,
+        )?
+        .try_into()
+        .ok()?;
+        self.real_yes_sol_reserves -= from_yes;
+        self.real_no_sol_reserves = self.real_no_sol_reserves.checked_sub(amount - from_yes)?;
+        Some(())
+    }
 }
+
 pub trait MarketAccount<'info> {
+    #[allow(clippy::too_many_arguments)]
     fn swap(
         &mut self,
         global_config: &Account<'info, Config>,
 
-        yes_token_mint: &Account<'info, Mint>,
         global_yes_ata: &mut AccountInfo<'info>,
         user_yes_ata: &mut AccountInfo<'info>,
 
-        no_token_mint: &Account<'info, Mint>,
         global_no_ata: &mut AccountInfo<'info>,
         user_no_ata: &mut AccountInfo<'info>,
 
         source: &mut AccountInfo<'info>,
         team_wallet: &mut AccountInfo<'info>,
+        referrer: Option<&mut Account<'info, Referrer>>,
 
         amount: u64,
         direction: u8,
         token_type: u8,
         minimum_receive_amount: u64,
+        max_price_impact_bps: Option<u64>,
 
         user: &Signer<'info>,
         signer: &[&[&[u8]]],
 
         user_info_pda: &mut Account<'info, UserInfo>,
+        user_stats: &mut Account<'info, UserStats>,
 
         token_program: &Program<'info, Token>,
         system_program: &Program<'info, System>,
-    ) -> Result<()>;
+    ) -> Result<TradeEvent>;
 
     fn apply_buy(&mut self, sol_amount: u64, token_type: u8) -> Option<BuyResult>;
 
@@ -114,6 +728,7 @@ pub trait MarketAccount<'info> {
 
     fn get_tokens_for_sell_sol(&self, token_amount: u64, token_type: u8) -> Option<SellResult>;
 
+    #[allow(clippy::too_many_arguments)]
     fn resolution(
         &mut self,
 
@@ -123,10 +738,30 @@ pub trait MarketAccount<'info> {
         signer: &[&[&[u8]]],
         user_info_pda: &mut Account<'info, UserInfo>,
 
+        yes_amount: u64,
+        no_amount: u64,
         token_type: u8,
 
         system_program: &Program<'info, System>,
-    ) -> Result<()>;
+    ) -> Result<u64>;
+
+    //  closes the position and returns the SOL paid for the `yes_amount` and `no_amount`
+    //  of it the user still holds
+    #[allow(clippy::too_many_arguments)]
+    fn refund(
+        &mut self,
+
+        source: &mut AccountInfo<'info>,
+
+        user: &Signer<'info>,
+        signer: &[&[&[u8]]],
+        user_info_pda: &mut Account<'info, UserInfo>,
+
+        yes_amount: u64,
+        no_amount: u64,
+
+        system_program: &Program<'info, System>,
+    ) -> Result<u64>;
 
     fn add_liquidity(
         &mut self,
@@ -140,6 +775,7 @@ pub trait MarketAccount<'info> {
         system_program: &Program<'info, System>,
     ) -> Result<()>;
 
+    //  returns the lp fees paid along with the principal
     fn withdraw_liquidity(
         &mut self,
 
@@ -152,7 +788,7 @@ pub trait MarketAccount<'info> {
         user_info_pda: &mut Account<'info, UserInfo>,
 
         system_program: &Program<'info, System>,
-    ) -> Result<()>;
+    ) -> Result<u64>;
 }
 
 impl<'info> MarketAccount<'info> for Account<'info, Market> {
@@ -160,72 +796,272 @@ impl<'info> MarketAccount<'info> for Account<'info, Market> {
         &mut self,
         global_config: &Account<'info, Config>,
 
-        yes_token_mint: &Account<'info, Mint>,
         global_yes_ata: &mut AccountInfo<'info>,
         user_yes_ata: &mut AccountInfo<'info>,
 
-        no_token_mint: &Account<'info, Mint>,
         global_no_ata: &mut AccountInfo<'info>,
         user_no_ata: &mut AccountInfo<'info>,
 
         source: &mut AccountInfo<'info>,
         team_wallet: &mut AccountInfo<'info>,
+        referrer: Option<&mut Account<'info, Referrer>>,
 
         amount: u64,
         direction: u8,
         token_type: u8,
         minimum_receive_amount: u64,
+        max_price_impact_bps: Option<u64>,
 
         user: &Signer<'info>,
         signer: &[&[&[u8]]],
 
         user_info_pda: &mut Account<'info, UserInfo>,
+        user_stats: &mut Account<'info, UserStats>,
 
         token_program: &Program<'info, Token>,
         system_program: &Program<'info, System>,
-    ) -> Result<()> {
-        //A decentralized prediction market platform built on Solana blockchain, inspired by Polymarket. This project enables users to create markets, trade positions, and resolve outcomes based on real-world events.    
-        // **Telegram**: [@Tru3B1iss](https://t.me/Tru3B1iss)
-        // **X (Twitter)**: [@XTruebliss](https://x.com/XTruebliss)
-        // **Discord**: [@trueb1iss](https://discord.com/users/1274339638668038187)
+    ) -> Result<TradeEvent> {
+        let (global_ata, user_ata) = if token_type == TOKEN_TYPE_YES {
+            (global_yes_ata, user_yes_ata)
+        } else {
+            (global_no_ata, user_no_ata)
+        };
+
+        //  a referred swap routes part of the platform fee to the referrer account
+        let referral_fee_bps = match referrer {
+            Some(_) => global_config.referral_fee_bps,
+            None => 0,
+        };
+        let referral_fee = |platform_fee: u64| {
+            bps_of(referral_fee_bps, platform_fee, Rounding::Down)
+                .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)
+        };
+        let referrer_info = referrer.as_ref().map(|referrer| referrer.to_account_info());
+
+        //  fees follow the tier of the volume traded before this swap
+        let timestamp = Clock::get()?.unix_timestamp;
+        let volume = user_stats.volume(timestamp);
+
+        let (sol_amount, token_amount, fee_lamports, referral_fee) = match direction {
+            DIRECTION_BUY => {
+                self.trade_limits.check_trade(amount, false)?;
+
+                let quote = self.quote_swap(
+                    global_config,
+                    volume,
+                    amount,
+                    direction,
+                    token_type,
+                    minimum_receive_amount,
+                    max_price_impact_bps,
+                )?;
+                let platform_fee = quote.platform_fee;
+                let lp_fee = quote.lp_fee;
+                let sol_amount = quote.change_amount;
+
+                let result = self
+                    .apply_buy(sol_amount, token_type)
+                    .ok_or(PredictionMarketError::BuyFailed)?;
+
+                //  lp fee stays in the vault for the liquidity providers
+                self.accrue_lp_fee(lp_fee)?;
+
+                sol_transfer_from_user(user, source.clone(), system_program, sol_amount + lp_fee)?;
+                let referral_fee = referral_fee(platform_fee)?;
+                if platform_fee > referral_fee {
+                    sol_transfer_from_user(
+                        user,
+                        team_wallet.clone(),
+                        system_program,
+                        platform_fee - referral_fee,
+                    )?;
+                }
+                if let Some(referrer_info) = referrer_info.as_ref().filter(|_| referral_fee > 0) {
+                    sol_transfer_from_user(
+                        user,
+                        referrer_info.clone(),
+                        system_program,
+                        referral_fee,
+                    )?;
+                }
+                token_transfer_with_signer(
+                    global_ata.clone(),
+                    source.clone(),
+                    user_ata.clone(),
+                    token_program,
+                    signer,
+                    result.token_amount,
+                )?;
+
+                user_info_pda
+                    .record_buy(
+                        token_type,
+                        result.token_amount,
+                        amount,
+                        platform_fee + lp_fee,
+                    )
+                    .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+                require!(
+                    user_info_pda.balance(token_type) <= self.trade_limits.max_position_per_wallet,
+                    PredictionMarketError::PositionLimitExceeded
+                );
+                self.open_cost_basis = self
+                    .open_cost_basis
+                    .checked_add(amount)
+                    .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+
+                (
+                    amount,
+                    result.token_amount,
+                    platform_fee + lp_fee,
+                    referral_fee,
+                )
+            }
+            DIRECTION_SELL => {
+                let quote = self.quote_swap(
+                    global_config,
+                    volume,
+                    amount,
+                    direction,
+                    token_type,
+                    minimum_receive_amount,
+                    max_price_impact_bps,
+                )?;
+                let platform_fee = quote.platform_fee;
+                let lp_fee = quote.lp_fee;
+                let sol_amount = quote.amount_out;
+                self.trade_limits
+                    .check_trade(sol_amount, amount >= user_info_pda.balance(token_type))?;
+
+                self.apply_sell(amount, token_type)
+                    .ok_or(PredictionMarketError::SellFailed)?;
+
+                self.accrue_lp_fee(lp_fee)?;
+
+                token_transfer_user(
+                    user_ata.clone(),
+                    user,
+                    global_ata.clone(),
+                    token_program,
+                    amount,
+                )?;
+                sol_transfer_with_signer(
+                    source.clone(),
+                    user.to_account_info(),
+                    system_program,
+                    signer,
+                    sol_amount,
+                )?;
+                let referral_fee = referral_fee(platform_fee)?;
+                if platform_fee > referral_fee {
+                    sol_transfer_with_signer(
+                        source.clone(),
+                        team_wallet.clone(),
+                        system_program,
+                        signer,
+                        platform_fee - referral_fee,
+                    )?;
+                }
+                if let Some(referrer_info) = referrer_info.as_ref().filter(|_| referral_fee > 0) {
+                    sol_transfer_with_signer(
+                        source.clone(),
+                        referrer_info.clone(),
+                        system_program,
+                        signer,
+                        referral_fee,
+                    )?;
+                }
+
+                let cost = user_info_pda
+                    .record_sell(token_type, amount, sol_amount, platform_fee + lp_fee)
+                    .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+                self.release_cost_basis(cost)?;
+
+                (sol_amount, amount, platform_fee + lp_fee, referral_fee)
+            }
+            _ => return err!(PredictionMarketError::InvalidArgument),
+        };
+        let referrer = match referrer {
+            Some(referrer) => {
+                referrer.accrue(referral_fee)?;
+                Some(referrer.authority)
+            }
+            None => None,
+        };
+
+        //  counted with fees, as paid into a buy or taken out of the pool by a sell
+        let traded = if direction == DIRECTION_BUY {
+            sol_amount
+        } else {
+            sol_amount + fee_lamports
+        };
+        user_stats
+            .record(timestamp, traded)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+
+        Ok(TradeEvent {
+            user: user.key(),
+            token_yes: self.yes_token_mint,
+            token_no: self.no_token_mint,
+            market_info: self.key(),
+
+            sol_amount,
+            token_amount,
+            fee_lamports,
+            is_buy: direction == DIRECTION_BUY,
+            is_yes_no: token_type == TOKEN_TYPE_YES,
 
-        Ok(())
+            real_sol_reserves: self.real_yes_sol_reserves + self.real_no_sol_reserves,
+            real_token_yes_reserves: self.real_yes_token_reserves,
+            real_token_no_reserves: self.real_no_token_reserves,
+
+            referrer,
+            referral_fee,
+
+            timestamp,
+        })
     }
 
     fn get_tokens_for_buy_sol(&self, change_amount: u64, token_type: u8) -> Option<BuyResult> {
-        Some(BuyResult {
-            token_amount,
-            change_amount,
-            current_yes_reserves,
-            current_no_reserves,
-            new_yes_reserves,
-            new_no_reserves,
-        })
+        get_tokens_for_buy_sol(&self.curve_reserves(), change_amount, token_type)
     }
 
     fn apply_buy(&mut self, change_amount: u64, token_type: u8) -> Option<BuyResult> {
+        self.accumulate_prices(Clock::get().ok()?.slot)?;
+
         // Computing Token Amount out
         let result = self.get_tokens_for_buy_sol(change_amount, token_type)?;
 
+        self.real_yes_token_reserves = result.new_yes_reserves;
+        self.real_no_token_reserves = result.new_no_reserves;
+        self.add_sol_reserves(token_type, change_amount)?;
+
         Some(result)
     }
 
     fn apply_sell(&mut self, change_amount: u64, token_type: u8) -> Option<SellResult> {
+        self.accumulate_prices(Clock::get().ok()?.slot)?;
+
         // Computing Sol Amount out
         let result = self.get_tokens_for_sell_sol(change_amount, token_type)?;
 
+        self.real_yes_token_reserves = result.new_yes_reserves;
+        self.real_no_token_reserves = result.new_no_reserves;
+        if token_type == TOKEN_TYPE_YES {
+            self.real_yes_sol_reserves = self
+                .real_yes_sol_reserves
+                .checked_sub(result.change_amount)?;
+        } else {
+            self.real_no_sol_reserves = self
+                .real_no_sol_reserves
+                .checked_sub(result.change_amount)?;
+        }
+
         Some(result)
     }
 
     fn get_tokens_for_sell_sol(&self, change_amount: u64, token_type: u8) -> Option<SellResult> {
-        Some(SellResult {
-            token_amount,
-            change_amount,
-            current_yes_reserves,
-            current_no_reserves,
-            new_yes_reserves,
-            new_no_reserves,
-        })
+        get_tokens_for_sell_sol(&self.curve_reserves(), change_amount, token_type)
     }
 
     fn resolution(
@@ -237,11 +1073,100 @@ impl<'info> MarketAccount<'info> for Account<'info, Market> {
         signer: &[&[&[u8]]],
         user_info_pda: &mut Account<'info, UserInfo>,
 
+        yes_amount: u64,
+        no_amount: u64,
         token_type: u8,
 
         system_program: &Program<'info, System>,
-    ) -> Result<()> {
-        Ok(())
+    ) -> Result<u64> {
+        require!(
+            token_type == TOKEN_TYPE_NO || token_type == TOKEN_TYPE_YES,
+            PredictionMarketError::RESOLUTIONTOKEYTYPEERROR
+        );
+
+        require!(
+            self.winning_token_type == Some(token_type),
+            PredictionMarketError::RESOLUTIONTOKEYTYPEERROR
+        );
+
+        let winning_amount = if token_type == TOKEN_TYPE_YES {
+            yes_amount
+        } else {
+            no_amount
+        };
+        let payout = self
+            .get_payout(winning_amount, token_type)
+            .ok_or(PredictionMarketError::ArithmeticError)?;
+
+        //  redeemed tokens leave circulation so later claims stay pro rata
+        if token_type == TOKEN_TYPE_YES {
+            self.real_yes_token_reserves += winning_amount;
+        } else {
+            self.real_no_token_reserves += winning_amount;
+        }
+        let losing_token_type = if token_type == TOKEN_TYPE_YES {
+            TOKEN_TYPE_NO
+        } else {
+            TOKEN_TYPE_YES
+        };
+        self.take_sol_reserves(losing_token_type, payout)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+
+        let cost = user_info_pda
+            .record_redemption(yes_amount, no_amount, payout)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        self.release_cost_basis(cost)?;
+
+        if payout > 0 {
+            sol_transfer_with_signer(source.clone(), user.clone(), system_program, signer, payout)?;
+        }
+
+        Ok(payout)
+    }
+
+    fn refund(
+        &mut self,
+
+        source: &mut AccountInfo<'info>,
+
+        user: &Signer<'info>,
+        signer: &[&[&[u8]]],
+        user_info_pda: &mut Account<'info, UserInfo>,
+
+        yes_amount: u64,
+        no_amount: u64,
+
+        system_program: &Program<'info, System>,
+    ) -> Result<u64> {
+        //  tokens sold or sent elsewhere take their share of the cost basis with them
+        let held_cost = user_info_pda
+            .cost_of(TOKEN_TYPE_YES, yes_amount)
+            .zip(user_info_pda.cost_of(TOKEN_TYPE_NO, no_amount))
+            .and_then(|(yes_cost, no_cost)| yes_cost.checked_add(no_cost))
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        let refund = self
+            .get_refund(held_cost)
+            .ok_or(PredictionMarketError::ArithmeticError)?;
+
+        self.take_sol_reserves_pro_rata(refund)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        let (yes_balance, no_balance) = (user_info_pda.yes_balance, user_info_pda.no_balance);
+        let cost = user_info_pda
+            .record_redemption(yes_balance, no_balance, refund)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        self.release_cost_basis(cost)?;
+
+        if refund > 0 {
+            sol_transfer_with_signer(
+                source.clone(),
+                user.to_account_info(),
+                system_program,
+                signer,
+                refund,
+            )?;
+        }
+
+        Ok(refund)
     }
 
     fn add_liquidity(
@@ -256,6 +1181,31 @@ impl<'info> MarketAccount<'info> for Account<'info, Market> {
 
         system_program: &Program<'info, System>,
     ) -> Result<()> {
+        require!(sol_amount > 0, PredictionMarketError::InvalidAmount);
+        //  liquidity is seeded before trading so LP principal stays backed by the curve
+        require!(
+            !self.has_trades(),
+            PredictionMarketError::IncorrectLaunchPhase
+        );
+
+        let yes_amount = sol_amount / 2;
+        self.add_sol_reserves(TOKEN_TYPE_YES, yes_amount)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        self.add_sol_reserves(TOKEN_TYPE_NO, sol_amount - yes_amount)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        self.total_lp_amount = self
+            .total_lp_amount
+            .checked_add(sol_amount)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+
+        user_info_pda.lp_amount = user_info_pda
+            .lp_amount
+            .checked_add(sol_amount)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        user_info_pda.is_lp = true;
+
+        sol_transfer_from_user(user, source.clone(), system_program, sol_amount)?;
+
         Ok(())
     }
 
@@ -270,7 +1220,32 @@ impl<'info> MarketAccount<'info> for Account<'info, Market> {
         user_info_pda: &mut Account<'info, UserInfo>,
 
         system_program: &Program<'info, System>,
-    ) -> Result<()> {
-        Ok(())
+    ) -> Result<u64> {
+        require!(sol_amount > 0, PredictionMarketError::InvalidAmount);
+        require!(
+            sol_amount <= user_info_pda.lp_amount,
+            PredictionMarketError::WITHDRAWLIQUIDITYSOLAMOUNTERROR
+        );
+
+        let fee_amount = self
+            .lp_fee_share(sol_amount)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        self.take_sol_reserves_pro_rata(sol_amount)
+            .ok_or(PredictionMarketError::WITHDRAWLIQUIDITYSOLAMOUNTERROR)?;
+        self.total_lp_amount -= sol_amount;
+        self.lp_fees -= fee_amount;
+
+        user_info_pda.lp_amount -= sol_amount;
+        user_info_pda.is_lp = user_info_pda.lp_amount > 0;
+
+        sol_transfer_with_signer(
+            source.clone(),
+            user.to_account_info(),
+            system_program,
+            signer,
+            sol_amount + fee_amount,
+        )?;
+
+        Ok(fee_amount)
     }
 }
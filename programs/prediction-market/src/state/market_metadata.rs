@@ -0,0 +1,75 @@
+use crate::constants::*;
+use crate::errors::PredictionMarketError;
+use crate::events::{IntoEvent, MarketMetadataEvent};
+use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
+
+//  human readable description of a market, one per market
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct MarketMetadata {
+    pub market: Pubkey,
+    pub creator: Pubkey,
+
+    #[max_len(MAX_QUESTION_LEN)]
+    pub question: String,
+    //  how the outcome is decided
+    #[max_len(MAX_RULES_LEN)]
+    pub rules: String,
+    //  where resolvers look the outcome up
+    #[max_len(MAX_RESOLUTION_SOURCE_LEN)]
+    pub resolution_source: String,
+    #[max_len(MAX_CATEGORY_LEN)]
+    pub category: String,
+    #[max_len(MAX_TAGS, MAX_TAG_LEN)]
+    pub tags: Vec<String>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct MarketMetadataParams {
+    pub question: String,
+    pub rules: String,
+    pub resolution_source: String,
+    pub category: String,
+    pub tags: Vec<String>,
+}
+
+impl MarketMetadataParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.question.len() <= MAX_QUESTION_LEN
+                && self.rules.len() <= MAX_RULES_LEN
+                && self.resolution_source.len() <= MAX_RESOLUTION_SOURCE_LEN
+                && self.category.len() <= MAX_CATEGORY_LEN
+                && self.tags.len() <= MAX_TAGS
+                && self.tags.iter().all(|tag| tag.len() <= MAX_TAG_LEN),
+            PredictionMarketError::MarketMetadataTooLong
+        );
+        Ok(())
+    }
+}
+
+impl MarketMetadata {
+    pub fn set(&mut self, params: MarketMetadataParams) -> Result<()> {
+        params.validate()?;
+        self.question = params.question;
+        self.rules = params.rules;
+        self.resolution_source = params.resolution_source;
+        self.category = params.category;
+        self.tags = params.tags;
+        Ok(())
+    }
+}
+
+impl IntoEvent<MarketMetadataEvent> for MarketMetadata {
+    fn into_event(&self) -> MarketMetadataEvent {
+        MarketMetadataEvent {
+            market: self.market,
+            creator: self.creator,
+            question: self.question.clone(),
+            rules: self.rules.clone(),
+            resolution_source: self.resolution_source.clone(),
+            category: self.category.clone(),
+            tags: self.tags.clone(),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+//  lifecycle of a market, moves only through `Market::transition`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MarketStatus {
+    //  created, waiting for the start and the minimum liquidity
+    #[default]
+    Pending,
+    Trading,
+    //  past the end of the trading window, waiting for an outcome
+    Closed,
+    //  the authority named a winner, payouts start once it is confirmed
+    ResolutionProposed,
+    //  winners redeem and LPs withdraw
+    Resolved,
+    //  voided, positions are refunded at cost
+    Invalid,
+    //  every claim is paid, the market can be closed
+    Settled,
+}
+
+impl MarketStatus {
+    pub fn can_transition_to(self, next: MarketStatus) -> bool {
+        use MarketStatus::*;
+        matches!(
+            (self, next),
+            (Pending, Trading | Closed | Invalid)
+                | (Trading, Closed | Invalid)
+                | (Closed, ResolutionProposed | Invalid)
+                | (ResolutionProposed, Resolved | Invalid)
+                | (Resolved | Invalid, Settled)
+        )
+    }
+
+    //  an outcome is final, trading and liquidity are over
+    pub fn is_final(self) -> bool {
+        matches!(
+            self,
+            MarketStatus::Resolved | MarketStatus::Invalid | MarketStatus::Settled
+        )
+    }
+}
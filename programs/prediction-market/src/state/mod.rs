@@ -1,4 +1,11 @@
 pub mod config;
 pub mod global;
 pub mod market;
+pub mod market_metadata;
+pub mod market_status;
+pub mod parlay;
+pub mod price_oracle;
+pub mod referrer;
+pub mod registry;
+pub mod user_stats;
 pub mod whitelist;
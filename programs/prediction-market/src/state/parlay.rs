@@ -0,0 +1,136 @@
+use crate::constants::MAX_PARLAY_LEGS;
+use crate::errors::PredictionMarketError;
+use crate::state::market::Market;
+use anchor_lang::prelude::*;
+
+//  house side of every parlay, funded by the config authority and held in this account
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct ParlayPool {
+    //  lamports above rent free to back new parlays
+    pub liquidity: u64,
+    //  lamports above rent owed to open parlays if every one of them wins
+    pub reserved: u64,
+    //  parlays opened so far, the id of the next one
+    pub parlay_count: u64,
+}
+
+impl ParlayPool {
+    pub fn fund(&mut self, amount: u64) -> Result<()> {
+        self.liquidity = self
+            .liquidity
+            .checked_add(amount)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        Ok(())
+    }
+
+    pub fn withdraw(&mut self, amount: u64) -> Result<()> {
+        self.liquidity = self
+            .liquidity
+            .checked_sub(amount)
+            .ok_or(PredictionMarketError::InsufficientParlayLiquidity)?;
+        Ok(())
+    }
+
+    //  holds the payout of a new parlay: its stake comes in, the rest from the free liquidity
+    pub fn reserve(&mut self, stake: u64, payout: u64) -> Result<()> {
+        let house = payout
+            .checked_sub(stake)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        self.withdraw(house)?;
+        self.reserved = self
+            .reserved
+            .checked_add(payout)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        Ok(())
+    }
+
+    //  frees the payout of a settled parlay, what is not `paid` goes back to the liquidity
+    pub fn release(&mut self, payout: u64, paid: u64) -> Result<()> {
+        self.reserved = self
+            .reserved
+            .checked_sub(payout)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        let kept = payout
+            .checked_sub(paid)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        self.fund(kept)
+    }
+}
+
+//  outcome picked on one market of a parlay
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParlayPick {
+    pub market: Pubkey,
+    pub token_type: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct ParlayLeg {
+    pub market: Pubkey,
+    pub token_type: u8,
+    //  implied price of the picked outcome when the parlay opened, `Decimal` raw units
+    pub entry_price: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParlayOutcome {
+    //  every leg won, the payout goes to the owner
+    Won,
+    //  a leg lost, the stake stays with the pool
+    Lost,
+    //  no leg lost but a market was voided, the stake is refunded
+    Voided,
+}
+
+//  bet that every leg wins, escrowed in the parlay pool until settlement closes it
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Parlay {
+    pub id: u64,
+    pub owner: Pubkey,
+    #[max_len(MAX_PARLAY_LEGS)]
+    pub legs: Vec<ParlayLeg>,
+    //  lamports at risk, the platform fee excluded
+    pub stake: u64,
+    //  lamports paid if every leg wins, the stake included
+    pub payout: u64,
+    pub opened_at: i64,
+}
+
+impl Parlay {
+    //  the outcome once every market of the parlay has a final one, `markets` in leg order
+    pub fn outcome(&self, markets: &[Market]) -> Result<ParlayOutcome> {
+        require!(
+            markets.len() == self.legs.len(),
+            PredictionMarketError::InvalidParlay
+        );
+        let (mut lost, mut voided) = (false, false);
+        for (leg, market) in self.legs.iter().zip(markets) {
+            require!(
+                market.status.is_final(),
+                PredictionMarketError::ParlayNotFinal
+            );
+            match market.winning_token_type {
+                Some(winner) => lost |= winner != leg.token_type,
+                None => voided = true,
+            }
+        }
+        Ok(if lost {
+            ParlayOutcome::Lost
+        } else if voided {
+            ParlayOutcome::Voided
+        } else {
+            ParlayOutcome::Won
+        })
+    }
+
+    //  lamports the owner receives for `outcome`
+    pub fn paid(&self, outcome: ParlayOutcome) -> u64 {
+        match outcome {
+            ParlayOutcome::Won => self.payout,
+            ParlayOutcome::Lost => 0,
+            ParlayOutcome::Voided => self.stake,
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use crate::constants::PARLAY_TWAP_SLOTS;
+use crate::errors::PredictionMarketError;
+use crate::math::{mul_div, Decimal, Rounding};
+use anchor_lang::prelude::*;
+
+//  observations kept per market, the oldest is overwritten first
+pub const OBSERVATION_CAPACITY: usize = 16;
+
+//  least slots between kept observations, so the ring always reaches `PARLAY_TWAP_SLOTS` back
+pub const OBSERVATION_SPACING: u64 = PARLAY_TWAP_SLOTS / (OBSERVATION_CAPACITY as u64 - 2) + 1;
+
+//  running sums of each outcome's implied price times the slots it held, `Decimal` raw units
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Observation {
+    pub slot: u64,
+    pub yes_price_cumulative: u128,
+    pub no_price_cumulative: u128,
+}
+
+impl Observation {
+    //  the accumulators `slot`s later, with the prices held constant meanwhile
+    fn advance(&self, slot: u64, yes_price: Decimal, no_price: Decimal) -> Option<Self> {
+        let elapsed = slot.checked_sub(self.slot)? as u128;
+        Some(Self {
+            slot,
+            yes_price_cumulative: self
+                .yes_price_cumulative
+                .checked_add(yes_price.raw.checked_mul(elapsed)?)?,
+            no_price_cumulative: self
+                .no_price_cumulative
+                .checked_add(no_price.raw.checked_mul(elapsed)?)?,
+        })
+    }
+
+    //  the accumulators at `slot`, between this observation and the newer `next`
+    fn interpolate(&self, next: &Self, slot: u64) -> Option<Self> {
+        let span = (next.slot - self.slot) as u128;
+        let elapsed = slot.checked_sub(self.slot)? as u128;
+        let along = |from: u128, to: u128| {
+            from.checked_add(mul_div(to - from, elapsed, span, Rounding::Down)?)
+        };
+        Some(Self {
+            slot,
+            yes_price_cumulative: along(self.yes_price_cumulative, next.yes_price_cumulative)?,
+            no_price_cumulative: along(self.no_price_cumulative, next.no_price_cumulative)?,
+        })
+    }
+}
+
+//  time weighted average prices over `window_slots`, returned by `observe`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Twap {
+    pub start_slot: u64,
+    pub end_slot: u64,
+    //  `Decimal` raw units, 1_000_000_000 is a certain outcome
+    pub yes_price: u64,
+    pub no_price: u64,
+}
+
+//  ring buffer of accumulator snapshots, at least `OBSERVATION_SPACING` slots apart but for the
+//  newest one
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PriceOracle {
+    pub observations: [Observation; OBSERVATION_CAPACITY],
+    //  newest observation and how many are filled
+    pub index: u8,
+    pub count: u8,
+}
+
+impl PriceOracle {
+    pub fn latest(&self) -> Option<&Observation> {
+        (self.count > 0).then(|| &self.observations[self.index as usize])
+    }
+
+    //  `i`-th newest observation
+    fn nth_newest(&self, i: usize) -> &Observation {
+        let index = (self.index as usize + OBSERVATION_CAPACITY - i) % OBSERVATION_CAPACITY;
+        &self.observations[index]
+    }
+
+    pub fn oldest(&self) -> Option<&Observation> {
+        (self.count > 0).then(|| self.nth_newest(self.count as usize - 1))
+    }
+
+    fn push(&mut self, observation: Observation) {
+        if self.count > 0 {
+            self.index = ((self.index as usize + 1) % OBSERVATION_CAPACITY) as u8;
+        }
+        self.observations[self.index as usize] = observation;
+        self.count = (self.count + 1).min(OBSERVATION_CAPACITY as u8);
+    }
+
+    //  accumulate the prices that held since the last update, called before reserves move
+    pub fn update(&mut self, slot: u64, yes_price: Decimal, no_price: Decimal) -> Option<()> {
+        let next = match self.latest() {
+            //  the first trade only starts the clock
+            None => Observation {
+                slot,
+                ..Default::default()
+            },
+            Some(latest) if latest.slot >= slot => return Some(()),
+            Some(latest) => latest.advance(slot, yes_price, no_price)?,
+        };
+        //  the newest observation moves along until it is spaced from the one before, so a
+        //  burst of trades cannot push the window out of the ring
+        if self.count > 1 && self.latest()?.slot - self.nth_newest(1).slot < OBSERVATION_SPACING {
+            self.observations[self.index as usize] = next;
+        } else {
+            self.push(next);
+        }
+        Some(())
+    }
+
+    //  accumulators at `slot`, `yes_price`/`no_price` hold since the latest observation
+    fn cumulative_at(
+        &self,
+        slot: u64,
+        yes_price: Decimal,
+        no_price: Decimal,
+    ) -> Result<Observation> {
+        let latest = self
+            .latest()
+            .ok_or(PredictionMarketError::ObservationTooOld)?;
+        if slot >= latest.slot {
+            return latest
+                .advance(slot, yes_price, no_price)
+                .ok_or_else(|| error!(PredictionMarketError::OverflowOrUnderflowOccurred));
+        }
+
+        let mut next = latest;
+        for i in 1..self.count as usize {
+            let observation = self.nth_newest(i);
+            if observation.slot <= slot {
+                return observation
+                    .interpolate(next, slot)
+                    .ok_or_else(|| error!(PredictionMarketError::OverflowOrUnderflowOccurred));
+            }
+            next = observation;
+        }
+        err!(PredictionMarketError::ObservationTooOld)
+    }
+
+    //  average prices over the `window_slots` before `now`
+    pub fn observe(
+        &self,
+        now: u64,
+        window_slots: u64,
+        yes_price: Decimal,
+        no_price: Decimal,
+    ) -> Result<Twap> {
+        require!(window_slots > 0, PredictionMarketError::InvalidArgument);
+        let start_slot = now
+            .checked_sub(window_slots)
+            .ok_or(PredictionMarketError::ObservationTooOld)?;
+
+        let start = self.cumulative_at(start_slot, yes_price, no_price)?;
+        let end = self.cumulative_at(now, yes_price, no_price)?;
+        let average = |from: u128, to: u128| -> Result<u64> {
+            ((to - from) / window_slots as u128)
+                .try_into()
+                .map_err(|_| error!(PredictionMarketError::OverflowOrUnderflowOccurred))
+        };
+
+        Ok(Twap {
+            start_slot,
+            end_slot: now,
+            yes_price: average(start.yes_price_cumulative, end.yes_price_cumulative)?,
+            no_price: average(start.no_price_cumulative, end.no_price_cumulative)?,
+        })
+    }
+}
@@ -0,0 +1,40 @@
+use crate::errors::PredictionMarketError;
+use crate::events::ReferralClaimEvent;
+use anchor_lang::prelude::*;
+
+//  fee share earned by a frontend through the swaps it refers, held in this account until claimed
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Referrer {
+    //  wallet the referrer signs and claims with
+    pub authority: Pubkey,
+    //  lamports above rent waiting for `claim_referral_fees`
+    pub unclaimed: u64,
+    pub total_earned: u64,
+}
+
+impl Referrer {
+    pub fn accrue(&mut self, amount: u64) -> Result<()> {
+        self.unclaimed = self
+            .unclaimed
+            .checked_add(amount)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        self.total_earned = self
+            .total_earned
+            .checked_add(amount)
+            .ok_or(PredictionMarketError::OverflowOrUnderflowOccurred)?;
+        Ok(())
+    }
+
+    //  resets the unclaimed balance, returns what the authority is owed
+    pub fn claim(&mut self) -> Result<ReferralClaimEvent> {
+        require!(self.unclaimed > 0, PredictionMarketError::InvalidAmount);
+        let amount = std::mem::take(&mut self.unclaimed);
+        Ok(ReferralClaimEvent {
+            authority: self.authority,
+            amount,
+            total_earned: self.total_earned,
+            timestamp: Clock::get()?.unix_timestamp,
+        })
+    }
+}
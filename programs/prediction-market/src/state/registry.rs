@@ -0,0 +1,126 @@
+use crate::constants::*;
+use crate::errors::PredictionMarketError;
+use crate::events::MarketRegistryEvent;
+use anchor_lang::prelude::*;
+
+//  assigns sequential market ids
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct MarketRegistry {
+    //  markets created so far, also the id of the next one
+    pub market_count: u64,
+}
+
+//  id -> market lookup, one per market
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct MarketEntry {
+    pub id: u64,
+    pub market: Pubkey,
+    #[max_len(MAX_CATEGORY_LEN)]
+    pub category: String,
+    //  category page listing the market while it is open
+    pub page: u32,
+    pub archived: bool,
+}
+
+//  open markets of one category, spread over `page_count` pages
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Category {
+    #[max_len(MAX_CATEGORY_LEN)]
+    pub name: String,
+    pub page_count: u32,
+    pub last_page_len: u32,
+    pub market_count: u64,
+    pub open_market_count: u64,
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct CategoryPage {
+    pub index: u32,
+    #[max_len(CATEGORY_PAGE_SIZE)]
+    pub markets: Vec<Pubkey>,
+}
+
+impl Category {
+    //  page new markets are appended to, a fresh one once the last is full
+    pub fn append_page(&self) -> u32 {
+        if self.page_count == 0 || self.last_page_len as usize == CATEGORY_PAGE_SIZE {
+            self.page_count
+        } else {
+            self.page_count - 1
+        }
+    }
+
+    //  list `market` in the open pages and record it in `entry`
+    pub fn register(
+        &mut self,
+        registry: &mut MarketRegistry,
+        entry: &mut MarketEntry,
+        page: &mut CategoryPage,
+        name: &str,
+        market: Pubkey,
+    ) -> Result<MarketRegistryEvent> {
+        let index = self.append_page();
+        if index == self.page_count {
+            self.page_count += 1;
+            self.last_page_len = 0;
+            page.index = index;
+        }
+        require!(
+            page.markets.len() < CATEGORY_PAGE_SIZE,
+            PredictionMarketError::CategoryPageFull
+        );
+        page.markets.push(market);
+        self.last_page_len += 1;
+
+        self.name = name.to_string();
+        self.market_count += 1;
+        self.open_market_count += 1;
+
+        entry.id = registry.market_count;
+        entry.market = market;
+        entry.category = name.to_string();
+        entry.page = index;
+        entry.archived = false;
+        registry.market_count += 1;
+
+        Ok(entry.event())
+    }
+
+    //  drop `entry` from its page, its id keeps resolving to the market
+    pub fn archive(
+        &mut self,
+        entry: &mut MarketEntry,
+        page: &mut CategoryPage,
+    ) -> Result<MarketRegistryEvent> {
+        require!(!entry.archived, PredictionMarketError::MarketArchived);
+        let position = page
+            .markets
+            .iter()
+            .position(|market| *market == entry.market)
+            .ok_or(PredictionMarketError::MarketArchived)?;
+        page.markets.swap_remove(position);
+        if entry.page + 1 == self.page_count {
+            self.last_page_len -= 1;
+        }
+        self.open_market_count -= 1;
+        entry.archived = true;
+
+        Ok(entry.event())
+    }
+}
+
+impl MarketEntry {
+    pub fn event(&self) -> MarketRegistryEvent {
+        MarketRegistryEvent {
+            market: self.market,
+            market_id: self.id,
+            category: self.category.clone(),
+            page: self.page,
+            archived: self.archived,
+        }
+    }
+}
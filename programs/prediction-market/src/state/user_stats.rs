@@ -0,0 +1,55 @@
+use crate::constants::{SECONDS_PER_DAY, VOLUME_WINDOW_DAYS};
+use anchor_lang::prelude::*;
+
+//  trading volume of a user across all markets, placing them in `Config::fee_tiers`
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct UserStats {
+    pub user: Pubkey,
+    //  lamports traded per day, indexed by day number modulo the window
+    pub daily_volume: [u64; VOLUME_WINDOW_DAYS],
+    //  day number, since the unix epoch, of the latest recorded trade
+    pub last_trade_day: i64,
+    pub total_volume: u64,
+}
+
+fn day_of(unix_timestamp: i64) -> i64 {
+    unix_timestamp.div_euclid(SECONDS_PER_DAY)
+}
+
+impl UserStats {
+    //  volume over the window ending on the day of `unix_timestamp`
+    pub fn volume(&self, unix_timestamp: i64) -> u64 {
+        let window = VOLUME_WINDOW_DAYS as i64;
+        let oldest_day = day_of(unix_timestamp) - window;
+        self.daily_volume
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| {
+                //  buckets hold the window ending on `last_trade_day`
+                let day =
+                    self.last_trade_day - (self.last_trade_day - *index as i64).rem_euclid(window);
+                day > oldest_day
+            })
+            .fold(0u64, |volume, (_, lamports)| {
+                volume.saturating_add(*lamports)
+            })
+    }
+
+    pub fn record(&mut self, unix_timestamp: i64, lamports: u64) -> Option<()> {
+        let window = VOLUME_WINDOW_DAYS as i64;
+        let today = day_of(unix_timestamp);
+
+        //  clear the days without trades since the last one, the whole window at most
+        let skipped = (today - self.last_trade_day).clamp(0, window);
+        for day in today - skipped + 1..=today {
+            self.daily_volume[day.rem_euclid(window) as usize] = 0;
+        }
+        self.last_trade_day = self.last_trade_day.max(today);
+
+        let bucket = &mut self.daily_volume[today.rem_euclid(window) as usize];
+        *bucket = bucket.checked_add(lamports)?;
+        self.total_volume = self.total_volume.checked_add(lamports)?;
+        Some(())
+    }
+}
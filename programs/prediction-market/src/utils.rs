@@ -1,15 +1,8 @@
+use crate::math::{mul_div, Rounding};
+use crate::errors::PredictionMarketError;
 use crate::*;
-use anchor_spl::token::{self, Token};
+use anchor_spl::token::{self, Token, TokenAccount};
 use solana_program::program::{invoke, invoke_signed};
-use std::ops::{Div, Mul};
-
-pub fn convert_to_float(value: u64, decimals: u8) -> f64 {
-    (value as f64).div(f64::powf(10.0, decimals as f64))
-}
-
-pub fn convert_from_float(value: f64, decimals: u8) -> u64 {
-    value.mul(f64::powf(10.0, decimals as f64)) as u64
-}
 
 pub fn sol_transfer_from_user<'info>(
     signer: &Signer<'info>,
@@ -29,6 +22,25 @@ pub fn sol_transfer_from_user<'info>(
     Ok(())
 }
 
+//  a transaction landing after `valid_until_slot` must not trade at a stale price
+pub fn require_valid_slot(clock: &Clock, valid_until_slot: Option<u64>) -> Result<()> {
+    if let Some(valid_until_slot) = valid_until_slot {
+        require!(
+            clock.slot <= valid_until_slot,
+            PredictionMarketError::TransactionExpired
+        );
+    }
+    Ok(())
+}
+
+//  balance of a token account that may not have been created yet
+pub fn token_balance(account: &AccountInfo) -> Result<u64> {
+    if account.data_is_empty() {
+        return Ok(0);
+    }
+    Ok(TokenAccount::try_deserialize(&mut &account.try_borrow_data()?[..])?.amount)
+}
+
 //  transfer token from user
 pub fn token_transfer_user<'info>(
     from: AccountInfo<'info>,
@@ -93,6 +105,7 @@ pub fn sol_transfer_with_signer<'info>(
 // Burn token from PDA
 pub fn token_burn_with_signer<'info>(
     from: AccountInfo<'info>, // Token account from which tokens will be burned
+    mint: AccountInfo<'info>, // Mint of the burned tokens
     authority: AccountInfo<'info>, // Authority signing the burn transaction (should be the PDA)
     token_program: &Program<'info, Token>, // Token program (SPL token program)
     signer_seeds: &[&[&[u8]]], // Signer seeds for the PDA
@@ -103,9 +116,9 @@ pub fn token_burn_with_signer<'info>(
         token_program.to_account_info(), // Token program
         token::Burn {
             // Burn instruction
-            mint: from.to_account_info(), // Token mint
-            from,                         // Account to burn from
-            authority,                    // Authority signing the burn
+            mint,      // Token mint
+            from,      // Account to burn from
+            authority, // Authority signing the burn
         },
         signer_seeds, // Signer seeds for the PDA
     );
@@ -117,11 +130,9 @@ pub fn token_burn_with_signer<'info>(
 }
 
 pub fn bps_mul(bps: u64, value: u64, divisor: u64) -> Option<u64> {
-    bps_mul_raw(bps, value, divisor).unwrap().try_into().ok()
+    bps_mul_raw(bps, value, divisor)?.try_into().ok()
 }
 
 pub fn bps_mul_raw(bps: u64, value: u64, divisor: u64) -> Option<u128> {
-    (value as u128)
-        .checked_mul(bps as u128)?
-        .checked_div(divisor as u128)
+    mul_div(value as u128, bps as u128, divisor as u128, Rounding::Down)
 }